@@ -0,0 +1,17 @@
+use empa::buffer;
+
+/// Whether two views cover overlapping bytes of the same buffer.
+///
+/// An operation that reads one view while it writes the other (e.g. a gather from `data` into
+/// `output`) races on the overlapping values, which shows up as nondeterministic output.
+pub(crate) fn views_overlap<T, U0, U1>(
+    a: &buffer::View<[T], U0>,
+    b: &buffer::View<[T], U1>,
+) -> bool {
+    let a_start = a.offset_in_bytes();
+    let a_end = a_start + a.size_in_bytes();
+    let b_start = b.offset_in_bytes();
+    let b_end = b_start + b.size_in_bytes();
+
+    a.buffer_id() == b.buffer_id() && a_start < b_end && b_start < a_end
+}
@@ -0,0 +1,319 @@
+use bytemuck::Zeroable;
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+
+const SHADER_U32: ShaderSource = shader_source!("shader_u32.wgsl");
+const SHADER_I32: ShaderSource = shader_source!("shader_i32.wgsl");
+const SHADER_F32: ShaderSource = shader_source!("shader_f32.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+const VALUES_PER_THREAD: u32 = 8;
+
+const SEGMENT_SIZE: u32 = GROUP_SIZE * VALUES_PER_THREAD;
+
+/// The maximum number of elements [BitonicSort] will sort within a single workgroup.
+pub const BITONIC_SORT_SEGMENT_SIZE: u32 = SEGMENT_SIZE;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Pass {
+    LocalSort,
+    GlobalStep,
+    LocalMerge,
+}
+
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug, Zeroable)]
+#[repr(C)]
+struct Uniforms {
+    descending: u32,
+    block_size: u32,
+    step_size: u32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a, T>
+where
+    T: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    max_count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    uniforms: Uniform<'a, Uniforms>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    data: Storage<'a, [T], ReadWrite>,
+}
+
+type ResourcesLayout<T> = <Resources<'static, T> as empa::resource_binding::Resources>::Layout;
+
+pub struct BitonicSortInput<'a, T, U> {
+    pub data: buffer::View<'a, [T], U>,
+    pub descending: bool,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// Sorts a buffer in-place using a bitonic sorting network.
+///
+/// If the data holds no more than [BITONIC_SORT_SEGMENT_SIZE] elements, the data is sorted
+/// entirely within a single workgroup in a single dispatch, which makes this sort a good choice
+/// for small arrays where the fixed overhead of a [RadixSort](crate::radix_sort::RadixSort)
+/// dominates. Larger arrays fall back to a multi-workgroup network that requires
+/// `O(log²(n))` dispatches.
+///
+/// The sort is not stable.
+pub struct BitonicSort<T>
+where
+    T: abi::Sized,
+{
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    pass_uniforms: Vec<UniformCache<Uniforms>>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
+    local_sort_pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+    global_step_pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+    local_merge_pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+}
+
+impl<T> BitonicSort<T>
+where
+    T: abi::Sized + 'static,
+{
+    pub fn requirements() -> Requirements {
+        Requirements::new(GROUP_SIZE, SEGMENT_SIZE * 4, 1)
+    }
+
+    async fn init_internal(device: Device, shader_source: &ShaderSource) -> Self {
+        let shader = device.create_shader_module(shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let create_local_sort_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&shader, "local_sort").finish())
+                .finish(),
+        );
+        let create_global_step_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&shader, "global_step").finish())
+                .finish(),
+        );
+        let create_local_merge_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&shader, "local_merge").finish())
+                .finish(),
+        );
+
+        let (local_sort_pipeline, global_step_pipeline, local_merge_pipeline) = join!(
+            create_local_sort_pipeline,
+            create_global_step_pipeline,
+            create_local_merge_pipeline
+        );
+
+        let fallback_count = UniformCache::new(&device, 0);
+
+        BitonicSort {
+            device,
+            fallback_count,
+            pass_uniforms: Vec::new(),
+            bind_group_layout,
+            local_sort_pipeline,
+            global_step_pipeline,
+            local_merge_pipeline,
+        }
+    }
+
+    pub fn encode<U>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: BitonicSortInput<T, U>,
+    ) -> CommandEncoder
+    where
+        U: buffer::StorageBinding,
+    {
+        let BitonicSortInput {
+            data,
+            descending,
+            count,
+        } = input;
+
+        if is_empty_input(data.len()) {
+            return encoder;
+        }
+
+        let len = data.len() as u32;
+
+        self.fallback_count.update(&self.device, len);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        let descending = descending as u32;
+
+        let segment_workgroups = div_ceil(len, SEGMENT_SIZE);
+        let padded_len = len.next_power_of_two();
+        let global_workgroups = div_ceil(padded_len / 2, GROUP_SIZE);
+
+        let mut passes = vec![(
+            Uniforms {
+                descending,
+                block_size: 0,
+                step_size: 0,
+            },
+            Pass::LocalSort,
+            segment_workgroups,
+        )];
+
+        let mut block_size = SEGMENT_SIZE * 2;
+
+        while block_size <= padded_len {
+            passes.push((
+                Uniforms {
+                    descending,
+                    block_size,
+                    step_size: 0,
+                },
+                Pass::GlobalStep,
+                global_workgroups,
+            ));
+
+            let mut step_size = block_size / 4;
+
+            while step_size >= SEGMENT_SIZE {
+                passes.push((
+                    Uniforms {
+                        descending,
+                        block_size,
+                        step_size,
+                    },
+                    Pass::GlobalStep,
+                    global_workgroups,
+                ));
+
+                step_size /= 2;
+            }
+
+            passes.push((
+                Uniforms {
+                    descending,
+                    block_size,
+                    step_size: 0,
+                },
+                Pass::LocalMerge,
+                segment_workgroups,
+            ));
+
+            block_size *= 2;
+        }
+
+        // The uniform buffers are cached per pass index, so that sorting data of the same size in
+        // the same order again does not allocate new buffers.
+        for (i, (uniforms, _, _)) in passes.iter().enumerate() {
+            if let Some(pass_uniforms) = self.pass_uniforms.get_mut(i) {
+                pass_uniforms.update(&self.device, *uniforms);
+            } else {
+                self.pass_uniforms
+                    .push(UniformCache::new(&self.device, *uniforms));
+            }
+        }
+
+        for (i, (_, pass, workgroups)) in passes.into_iter().enumerate() {
+            encoder = self.encode_pass(
+                encoder,
+                data,
+                &count,
+                &self.pass_uniforms[i],
+                pass,
+                workgroups,
+            );
+        }
+
+        encoder
+    }
+
+    fn encode_pass<U>(
+        &self,
+        encoder: CommandEncoder,
+        data: buffer::View<[T], U>,
+        count: &CountBuffer,
+        uniforms: &UniformCache<Uniforms>,
+        pass: Pass,
+        workgroups: u32,
+    ) -> CommandEncoder
+    where
+        U: buffer::StorageBinding,
+    {
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                max_count: count.uniform(),
+                uniforms: uniforms.uniform(),
+                data: data.storage(),
+            },
+        );
+
+        let pipeline = match pass {
+            Pass::LocalSort => &self.local_sort_pipeline,
+            Pass::GlobalStep => &self.global_step_pipeline,
+            Pass::LocalMerge => &self.local_merge_pipeline,
+        };
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: workgroups,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
+
+impl BitonicSort<u32> {
+    pub async fn init_u32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_U32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_u32(device))
+    }
+}
+
+impl BitonicSort<i32> {
+    pub async fn init_i32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_I32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_i32(device))
+    }
+}
+
+impl BitonicSort<f32> {
+    pub async fn init_f32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_F32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_f32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_f32(device))
+    }
+}
@@ -0,0 +1,160 @@
+// This implements the variant of the bitonic sorting network where the first step of every merge stage compares
+// elements mirrored around the center of the block (a "flip"), rather than reversing the sort direction for every other
+// block. As a result, every compare-exchange moves the "lesser" element to the lower index. We treat every index past
+// the end of the data as holding a virtual padding element that orders after all real elements; because of the above
+// property, such padding elements never move, which means we can simply skip any compare-exchange that involves an
+// index past the end of the data. This allows sorting data of any length, not just powers of 2.
+
+const GROUP_SIZE = 256u;
+const VALUES_PER_THREAD = 8u;
+const SEGMENT_SIZE = 2048u; // GROUP_SIZE * VALUES_PER_THREAD;
+const PAIRS_PER_THREAD = 4u; // VALUES_PER_THREAD / 2;
+
+struct Uniforms {
+    descending: u32,
+    block_size: u32,
+    step_size: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> max_count: u32;
+
+@group(0) @binding(1)
+var<uniform> uniforms: Uniforms;
+
+@group(0) @binding(2)
+var<storage, read_write> data: array<DATA_TYPE>;
+
+var<workgroup> local_data: array<DATA_TYPE, SEGMENT_SIZE>;
+
+fn out_of_order(a: DATA_TYPE, b: DATA_TYPE) -> bool {
+    if uniforms.descending != 0 {
+        return a < b;
+    } else {
+        return a > b;
+    }
+}
+
+fn flip_pair(pair_index: u32, block_size: u32) -> vec2<u32> {
+    let half_block_size = block_size / 2;
+    let block_start = (pair_index / half_block_size) * block_size;
+    let offset = pair_index % half_block_size;
+
+    return vec2(block_start + offset, block_start + block_size - 1 - offset);
+}
+
+fn half_clean_pair(pair_index: u32, step_size: u32) -> vec2<u32> {
+    let a = (pair_index / step_size) * step_size * 2 + pair_index % step_size;
+
+    return vec2(a, a + step_size);
+}
+
+fn local_compare_exchange(pair: vec2<u32>, local_count: u32) {
+    if pair.y < local_count {
+        let a = local_data[pair.x];
+        let b = local_data[pair.y];
+
+        if out_of_order(a, b) {
+            local_data[pair.x] = b;
+            local_data[pair.y] = a;
+        }
+    }
+}
+
+fn local_half_clean(local_index: u32, local_count: u32, start_step_size: u32) {
+    for (var step_size = start_step_size; step_size > 0; step_size >>= 1u) {
+        for (var p = 0u; p < PAIRS_PER_THREAD; p += 1u) {
+            let pair_index = p * GROUP_SIZE + local_index;
+
+            local_compare_exchange(half_clean_pair(pair_index, step_size), local_count);
+        }
+
+        workgroupBarrier();
+    }
+}
+
+fn load_segment(segment_offset: u32, local_count: u32, local_index: u32) {
+    for (var i = local_index; i < local_count; i += GROUP_SIZE) {
+        local_data[i] = data[segment_offset + i];
+    }
+
+    workgroupBarrier();
+}
+
+fn store_segment(segment_offset: u32, local_count: u32, local_index: u32) {
+    for (var i = local_index; i < local_count; i += GROUP_SIZE) {
+        data[segment_offset + i] = local_data[i];
+    }
+}
+
+// Fully sorts each segment. If the data fits within a single segment, this is the only pass required.
+@compute @workgroup_size(256, 1, 1)
+fn local_sort(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let count = min(max_count, arrayLength(&data));
+    let segment_offset = workgroup_id.x * SEGMENT_SIZE;
+
+    if segment_offset >= count {
+        return;
+    }
+
+    let local_count = min(SEGMENT_SIZE, count - segment_offset);
+
+    load_segment(segment_offset, local_count, local_index);
+
+    for (var block_size = 2u; block_size <= SEGMENT_SIZE; block_size <<= 1u) {
+        for (var p = 0u; p < PAIRS_PER_THREAD; p += 1u) {
+            let pair_index = p * GROUP_SIZE + local_index;
+
+            local_compare_exchange(flip_pair(pair_index, block_size), local_count);
+        }
+
+        workgroupBarrier();
+
+        local_half_clean(local_index, local_count, block_size / 4);
+    }
+
+    store_segment(segment_offset, local_count, local_index);
+}
+
+// Performs a single step of a merge stage for which the compared elements lie in different segments. Every thread
+// handles a single pair.
+@compute @workgroup_size(256, 1, 1)
+fn global_step(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let count = min(max_count, arrayLength(&data));
+    let pair_index = global_id.x;
+
+    var pair: vec2<u32>;
+
+    if uniforms.step_size == 0 {
+        pair = flip_pair(pair_index, uniforms.block_size);
+    } else {
+        pair = half_clean_pair(pair_index, uniforms.step_size);
+    }
+
+    if pair.y < count {
+        let a = data[pair.x];
+        let b = data[pair.y];
+
+        if out_of_order(a, b) {
+            data[pair.x] = b;
+            data[pair.y] = a;
+        }
+    }
+}
+
+// Performs the remaining steps of a merge stage once the compared elements all lie within the same segment.
+@compute @workgroup_size(256, 1, 1)
+fn local_merge(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let count = min(max_count, arrayLength(&data));
+    let segment_offset = workgroup_id.x * SEGMENT_SIZE;
+
+    if segment_offset >= count {
+        return;
+    }
+
+    let local_count = min(SEGMENT_SIZE, count - segment_offset);
+
+    load_segment(segment_offset, local_count, local_index);
+    local_half_clean(local_index, local_count, SEGMENT_SIZE / 2);
+    store_segment(segment_offset, local_count, local_index);
+}
@@ -0,0 +1,3 @@
+alias DATA_TYPE = i32;
+
+#include "shader_core.wgsl"
@@ -0,0 +1,3 @@
+alias DATA_TYPE = u32;
+
+#include "shader_core.wgsl"
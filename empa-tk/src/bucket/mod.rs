@@ -0,0 +1,309 @@
+use std::fmt::Write;
+
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::ShaderSource;
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::generate_dispatch::{GenerateDispatch, GenerateDispatchResources};
+use crate::histogram::{Histogram, HistogramInput, HistogramRange};
+use crate::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+use crate::write_value_type::{write_value_type, ValueTypeError};
+
+const SHADER_TEMPLATE: &str = concat!(
+    include_str!("../dispatch.wgsl"),
+    include_str!("shader_template.wgsl")
+);
+
+const GROUP_SIZE: u32 = 256;
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a, V>
+where
+    V: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    bin_ids: Storage<'a, [u32]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    values_in: Storage<'a, [V]>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    bin_offsets: Storage<'a, [u32]>,
+    #[resource(binding = 4, visibility = "COMPUTE")]
+    bin_cursors: Storage<'a, [u32], ReadWrite>,
+    #[resource(binding = 5, visibility = "COMPUTE")]
+    values_out: Storage<'a, [V], ReadWrite>,
+}
+
+type ResourcesLayout<V> = <Resources<'static, V> as empa::resource_binding::Resources>::Layout;
+
+pub struct BucketByInput<'a, V, U0, U1> {
+    /// The bin each value belongs to; bin ids of [bin_count](BucketBy::bin_count) or greater are
+    /// placed in the last bin.
+    pub bin_ids: buffer::View<'a, [u32], U0>,
+    /// The values to bin; must hold at least as many elements as `bin_ids`.
+    pub values: buffer::View<'a, [V], U1>,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+pub struct BucketByOutput<'a, V, U0, U1> {
+    /// Receives the values, grouped into contiguous ranges per bin in bin order; must hold at
+    /// least as many elements as the input `bin_ids`.
+    pub values: buffer::View<'a, [V], U0>,
+    /// Receives the index at which each bin's range starts in the output `values`; must hold at
+    /// least [bin_count](BucketBy::bin_count) elements.
+    pub bin_offsets: buffer::View<'a, [u32], U1>,
+}
+
+/// Scatters values into a fixed number of bins, as identified by a bin id for each value.
+///
+/// This counts the values in each bin, computes the bin offsets with an exclusive prefix sum over
+/// the counts, and then scatters each value into its bin; it is a single pass of a
+/// [RadixSortBy](crate::radix_sort::RadixSortBy) with the digit replaced by an arbitrary bin id.
+/// The order of the values within a bin is unspecified; use a
+/// [RadixSortBy](crate::radix_sort::RadixSortBy) on the bin ids if the values must retain their
+/// relative order.
+pub struct BucketBy<V>
+where
+    V: abi::Sized,
+{
+    device: Device,
+    bin_count: u32,
+    fallback_count: UniformCache<u32>,
+    histogram: Histogram,
+    prefix_sum: PrefixSum<u32>,
+    bin_cursors: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<V>>,
+    pipeline: ComputePipeline<(ResourcesLayout<V>,)>,
+    generate_dispatch: GenerateDispatch,
+    group_size: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
+    dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+}
+
+impl<V> BucketBy<V>
+where
+    V: abi::Sized + 'static,
+{
+    /// The device limits a bucket operator with the given `bin_count` requires.
+    pub fn requirements(bin_count: u32) -> Requirements {
+        Histogram::requirements(bin_count)
+            .max(PrefixSum::<u32>::requirements())
+            .max(Requirements::new(GROUP_SIZE, 0, 5))
+    }
+
+    /// Initializes a new bucket operator with the given `bin_count`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bin_count` is `0` or greater than
+    /// [MAX_BIN_COUNT](crate::histogram::MAX_BIN_COUNT).
+    pub async fn init(device: Device, bin_count: u32) -> Result<Self, ValueTypeError> {
+        let mut code = String::new();
+
+        write_value_type::<V>(&device, &mut code)?;
+
+        write!(
+            code,
+            "const BIN_COUNT = {}u;\n\n{}",
+            bin_count, SHADER_TEMPLATE
+        )
+        .unwrap();
+
+        let shader_source = ShaderSource::unparsed(code);
+        let shader = device.create_shader_module(&shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<V>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let create_pipeline = unsafe {
+            device.create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute_unchecked(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+        };
+
+        // The histogram asserts the `bin_count` is in range
+        let (pipeline, histogram, prefix_sum, generate_dispatch) = join!(
+            create_pipeline,
+            Histogram::init(device.clone(), bin_count),
+            PrefixSum::init_exclusive_u32(device.clone()),
+            GenerateDispatch::init(device.clone()),
+        );
+
+        let bin_cursors = device.create_slice_buffer_zeroed(
+            bin_count as usize,
+            buffer::Usages::storage_binding().and_copy_dst(),
+        );
+        let group_size = device.create_buffer(GROUP_SIZE, buffer::Usages::uniform_binding());
+        let dispatch = device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+
+        let fallback_count = UniformCache::new(&device, 0);
+
+        Ok(BucketBy {
+            device,
+            bin_count,
+            fallback_count,
+            histogram,
+            prefix_sum,
+            bin_cursors,
+            bind_group_layout,
+            pipeline,
+            generate_dispatch,
+            group_size,
+            dispatch,
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device, bin_count: u32) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init(device, bin_count))
+    }
+
+    pub fn bin_count(&self) -> u32 {
+        self.bin_count
+    }
+
+    /// Encodes the scatter of the `input.values` into their bins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input.values` or `output.values` hold fewer elements than `input.bin_ids`, or if
+    /// `output.bin_offsets` holds fewer than [bin_count](Self::bin_count) elements.
+    pub fn encode<U0, U1, U2, U3>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: BucketByInput<V, U0, U1>,
+        output: BucketByOutput<V, U2, U3>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+        U3: buffer::StorageBinding + buffer::CopyDst + 'static,
+    {
+        let BucketByInput {
+            bin_ids,
+            values,
+            count,
+        } = input;
+
+        assert!(
+            values.len() >= bin_ids.len(),
+            "`values` must hold at least as many elements as `bin_ids`"
+        );
+        assert!(
+            output.values.len() >= bin_ids.len(),
+            "the output `values` must hold at least as many elements as `bin_ids`"
+        );
+        assert!(
+            output.bin_offsets.len() >= self.bin_count as usize,
+            "`bin_offsets` must hold at least `bin_count` elements"
+        );
+
+        // An empty input leaves every bin empty
+        if is_empty_input(bin_ids.len()) {
+            return encoder.clear_buffer_slice(output.bin_offsets);
+        }
+
+        let dispatch_indirect = count.is_some();
+
+        self.fallback_count
+            .update(&self.device, bin_ids.len() as u32);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        if dispatch_indirect {
+            encoder = self.generate_dispatch.encode(
+                encoder,
+                GenerateDispatchResources {
+                    group_size: self.group_size.uniform(),
+                    count: count.uniform(),
+                    dispatch: self.dispatch.storage(),
+                    max_count: None,
+                },
+            );
+        }
+
+        // With as many histogram bins as the range holds bin ids, each histogram bin counts
+        // exactly one bin id
+        encoder = self.histogram.encode(
+            encoder,
+            HistogramInput {
+                data: bin_ids,
+                range: HistogramRange {
+                    min: 0,
+                    max: self.bin_count - 1,
+                },
+                count: if dispatch_indirect {
+                    Some(count.uniform())
+                } else {
+                    None
+                },
+            },
+            output.bin_offsets,
+        );
+        encoder = self.prefix_sum.encode(
+            encoder,
+            PrefixSumInput {
+                data: output.bin_offsets,
+                count: None,
+                initial: None,
+                order: ScanOrder::Ascending,
+            },
+        );
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                count: count.uniform(),
+                bin_ids: bin_ids.storage(),
+                values_in: values.storage(),
+                bin_offsets: output.bin_offsets.storage(),
+                bin_cursors: self.bin_cursors.storage(),
+                values_out: output.values.storage(),
+            },
+        );
+
+        let encoder = encoder
+            .clear_buffer_slice(self.bin_cursors.view())
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group);
+
+        if dispatch_indirect {
+            encoder
+                .dispatch_workgroups_indirect(self.dispatch.view())
+                .end()
+        } else {
+            encoder
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: div_ceil(bin_ids.len() as u32, GROUP_SIZE),
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end()
+        }
+    }
+}
@@ -0,0 +1,33 @@
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<storage, read> bin_ids: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read> values_in: array<VALUE_TYPE>;
+
+@group(0) @binding(3)
+var<storage, read> bin_offsets: array<u32>;
+
+@group(0) @binding(4)
+var<storage, read_write> bin_cursors: array<atomic<u32>>;
+
+@group(0) @binding(5)
+var<storage, read_write> values_out: array<VALUE_TYPE>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let index = linear_workgroup_index(workgroup_id, num_workgroups) * 256u + local_index;
+
+    if index >= min(count, arrayLength(&bin_ids)) {
+        return;
+    }
+
+    // Bin ids outside of the bin range are clamped into the last bin, matching the histogram that
+    // produced the bin offsets.
+    let bin = min(bin_ids[index], BIN_COUNT - 1);
+    let output_index = bin_offsets[bin] + atomicAdd(&bin_cursors[bin], 1u);
+
+    values_out[output_index] = values_in[index];
+}
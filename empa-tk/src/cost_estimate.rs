@@ -0,0 +1,27 @@
+/// The work and memory an operation requires to process a given number of values.
+///
+/// Obtain an estimate from an operation's `plan_cost` method (e.g.
+/// [RadixSort::plan_cost](crate::radix_sort::RadixSort::plan_cost)), e.g. to decide whether an
+/// input is large enough to be worth processing on the device. The estimate is computed on the
+/// host and does not encode any commands.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct CostEstimate {
+    /// The number of bytes of temporary storage the operation uses, including both the
+    /// operation's internal per-workgroup state and any temporary buffers the caller must provide.
+    pub scratch_bytes: u64,
+    /// The number of compute dispatches the operation records when no explicit `count` is
+    /// provided; an explicit count adds a dispatch that generates the indirect dispatches.
+    pub dispatch_count: u32,
+    /// The number of passes over the values the operation makes.
+    pub pass_count: u32,
+}
+
+impl CostEstimate {
+    pub(crate) const fn new(scratch_bytes: u64, dispatch_count: u32, pass_count: u32) -> Self {
+        CostEstimate {
+            scratch_bytes,
+            dispatch_count,
+            pass_count,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use empa::buffer::Uniform;
+
+/// The number of values an operation processes, if this differs from the length of its input.
+#[derive(Clone)]
+pub enum Count<'a> {
+    /// A count that is known on the host.
+    ///
+    /// The operation is dispatched directly for this count; no uniform buffer needs to be provided.
+    /// Counts greater than the length of the input are clamped to the length of the input.
+    Static(u32),
+    /// A count that is read from a uniform buffer on the device, e.g. a count that was produced by
+    /// a preceding operation.
+    ///
+    /// The operation is dispatched indirectly, based on this count.
+    Indirect(Uniform<'a, u32>),
+}
+
+impl<'a> Count<'a> {
+    /// Resolves an optional count for an input of length `len` into the count binding for an
+    /// indirect dispatch (if any), and the count to use for a direct dispatch.
+    pub(crate) fn resolve(count: Option<Self>, len: u32) -> (Option<Uniform<'a, u32>>, u32) {
+        match count {
+            None => (None, len),
+            Some(Count::Static(count)) => (None, count.min(len)),
+            Some(Count::Indirect(binding)) => (Some(binding), len),
+        }
+    }
+}
+
+impl From<u32> for Count<'_> {
+    fn from(count: u32) -> Self {
+        Count::Static(count)
+    }
+}
+
+impl<'a> From<Uniform<'a, u32>> for Count<'a> {
+    fn from(binding: Uniform<'a, u32>) -> Self {
+        Count::Indirect(binding)
+    }
+}
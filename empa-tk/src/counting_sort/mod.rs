@@ -0,0 +1,242 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer;
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::type_flag::{O, X};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::generate_dispatch::{GenerateDispatch, GenerateDispatchResources};
+use crate::histogram::{Histogram, HistogramInput, HistogramRange, MAX_BIN_COUNT};
+use crate::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+
+/// The maximum key range a [CountingSort] supports.
+pub const MAX_KEY_RANGE: u32 = MAX_BIN_COUNT;
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    key_range: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    bin_ends: Storage<'a, [u32]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    output: Storage<'a, [u32], ReadWrite>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+pub struct CountingSortInput<'a, U> {
+    pub keys: buffer::View<'a, [u32], U>,
+    /// The key range: all keys must be less than `k`.
+    pub k: u32,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// Sorts `u32` keys that are known to lie in a small range `0..k` by counting the occurrences of
+/// each key.
+///
+/// The keys are counted into `k` bins, after which a prefix sum over the bins determines the
+/// output range of each key. This requires a fixed number of passes regardless of `k`, which makes
+/// it cheaper than a [RadixSort](crate::radix_sort::RadixSort) for small key ranges. As only keys
+/// are sorted, the output is identical to that of a stable sort.
+pub struct CountingSort {
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    key_range: UniformCache<u32>,
+    histogram: Histogram,
+    prefix_sum: PrefixSum<u32>,
+    bin_ends: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: ComputePipeline<(ResourcesLayout,)>,
+    generate_dispatch: GenerateDispatch,
+    group_size: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
+    dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+}
+
+impl CountingSort {
+    pub fn requirements() -> Requirements {
+        Histogram::requirements(MAX_KEY_RANGE)
+            .max(PrefixSum::<u32>::requirements())
+            .max(Requirements::new(GROUP_SIZE, 0, 2))
+    }
+
+    pub async fn init(device: Device) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let create_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                .finish(),
+        );
+
+        let (pipeline, histogram, prefix_sum, generate_dispatch) = join!(
+            create_pipeline,
+            Histogram::init(device.clone(), MAX_KEY_RANGE),
+            PrefixSum::init_inclusive_u32(device.clone()),
+            GenerateDispatch::init(device.clone()),
+        );
+
+        let bin_ends = device.create_slice_buffer_zeroed(
+            MAX_KEY_RANGE as usize,
+            buffer::Usages::storage_binding().and_copy_dst(),
+        );
+        let group_size = device.create_buffer(GROUP_SIZE, buffer::Usages::uniform_binding());
+        let dispatch = device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+
+        let fallback_count = UniformCache::new(&device, 0);
+        let key_range = UniformCache::new(&device, MAX_KEY_RANGE);
+
+        CountingSort {
+            device,
+            fallback_count,
+            key_range,
+            histogram,
+            prefix_sum,
+            bin_ends,
+            bind_group_layout,
+            pipeline,
+            generate_dispatch,
+            group_size,
+            dispatch,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init(device))
+    }
+
+    /// Encodes the sort of the `input.keys` into the `output`.
+    ///
+    /// The `output` must hold at least as many elements as there are keys. If `input.count` is
+    /// specified, only the first `count` keys are sorted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input.k` is `0` or greater than [MAX_KEY_RANGE].
+    pub fn encode<U0, U1>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: CountingSortInput<U0>,
+        output: buffer::View<[u32], U1>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        let CountingSortInput { keys, k, count } = input;
+
+        assert!(
+            k > 0 && k <= MAX_KEY_RANGE,
+            "`k` must be in the range `1..={}` (got {})",
+            MAX_KEY_RANGE,
+            k
+        );
+        assert!(
+            output.len() >= keys.len(),
+            "the output must hold at least as many elements as there are keys"
+        );
+
+        if is_empty_input(keys.len()) {
+            return encoder;
+        }
+
+        let dispatch_indirect = count.is_some();
+
+        self.fallback_count.update(&self.device, keys.len() as u32);
+        self.key_range.update(&self.device, k);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        if dispatch_indirect {
+            encoder = self.generate_dispatch.encode(
+                encoder,
+                GenerateDispatchResources {
+                    group_size: self.group_size.uniform(),
+                    count: count.uniform(),
+                    dispatch: self.dispatch.storage(),
+                    max_count: None,
+                },
+            );
+        }
+
+        // With as many bins as the maximum key range, each bin covers exactly one key
+        encoder = self.histogram.encode(
+            encoder,
+            HistogramInput {
+                data: keys,
+                range: HistogramRange {
+                    min: 0,
+                    max: MAX_KEY_RANGE - 1,
+                },
+                count: if dispatch_indirect {
+                    Some(count.uniform())
+                } else {
+                    None
+                },
+            },
+            self.bin_ends.view(),
+        );
+        encoder = self.prefix_sum.encode(
+            encoder,
+            PrefixSumInput {
+                data: self.bin_ends.view(),
+                count: None,
+                initial: None,
+                order: ScanOrder::Ascending,
+            },
+        );
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                key_range: self.key_range.uniform(),
+                bin_ends: self.bin_ends.storage(),
+                output: output.storage(),
+            },
+        );
+
+        let encoder = encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group);
+
+        if dispatch_indirect {
+            encoder
+                .dispatch_workgroups_indirect(self.dispatch.view())
+                .end()
+        } else {
+            encoder
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: div_ceil(keys.len() as u32, GROUP_SIZE),
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end()
+        }
+    }
+}
@@ -0,0 +1,38 @@
+#include "../dispatch.wgsl"
+
+@group(0) @binding(0)
+var<uniform> key_range: u32;
+
+@group(0) @binding(1)
+var<storage, read> bin_ends: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let index = linear_workgroup_index(workgroup_id, num_workgroups) * 256u + local_index;
+
+    // After the inclusive prefix sum, `bin_ends[key_range - 1]` holds the number of keys that lie in the key range.
+    let total = min(bin_ends[key_range - 1], arrayLength(&output));
+
+    if index >= total {
+        return;
+    }
+
+    // Find the first bin that ends after the output index; that bin's key is the output value.
+    var lo = 0u;
+    var hi = key_range;
+
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+
+        if bin_ends[mid] > index {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    output[index] = lo;
+}
@@ -0,0 +1,132 @@
+//! Host implementations of operations that produce the same results as their GPU counterparts.
+//!
+//! For small inputs the cost of dispatching the GPU pipelines can exceed the cost of the operation
+//! itself, in which case these may serve as a fast path; they also serve as references against
+//! which to verify the results of the GPU operations.
+
+use crate::radix_key::sealed::{KeyType, Sealed};
+use crate::radix_key::RadixKey;
+
+/// Sorts the `data` on the host in the same order as a [RadixSort](crate::radix_sort::RadixSort).
+///
+/// For `f32` values this orders by the bit patterns of the values, so `-0.0` orders before `0.0`
+/// and NaN values order according to their sign bits, which a comparison-based sort would not do.
+pub fn radix_sort_cpu<T>(data: &mut [T])
+where
+    T: RadixKey + Copy,
+{
+    data.sort_by_key(|value| radix_key(*value));
+}
+
+/// Sorts the `keys` on the host and moves the `values` alongside the keys, in the same order as a
+/// `RadixSortBy`.
+///
+/// The sort is stable: values with equal keys retain their relative order.
+///
+/// # Panics
+///
+/// Panics if the `keys` and the `values` are not of equal length.
+pub fn radix_sort_by_cpu<K, V>(keys: &mut [K], values: &mut [V])
+where
+    K: RadixKey + Copy,
+    V: Copy,
+{
+    assert_eq!(
+        keys.len(),
+        values.len(),
+        "the keys and values must be of equal length"
+    );
+
+    let mut pairs: Vec<(K, V)> = keys.iter().copied().zip(values.iter().copied()).collect();
+
+    pairs.sort_by_key(|(key, _)| radix_key(*key));
+
+    for (i, (key, value)) in pairs.into_iter().enumerate() {
+        keys[i] = key;
+        values[i] = value;
+    }
+}
+
+/// Replaces the `data` on the host with its exclusive prefix sum, in the same way as a
+/// [PrefixSum](crate::prefix_sum::PrefixSum) initialized with `init_exclusive_u32`.
+///
+/// The running sum wraps on overflow, as it does on the GPU.
+pub fn prefix_sum_exclusive_cpu(data: &mut [u32]) {
+    let mut sum = 0u32;
+
+    for value in data.iter_mut() {
+        let next = sum.wrapping_add(*value);
+
+        *value = sum;
+        sum = next;
+    }
+}
+
+/// Replaces the `data` on the host with its inclusive prefix sum, in the same way as a
+/// [PrefixSum](crate::prefix_sum::PrefixSum) initialized with `init_inclusive_u32`.
+///
+/// The running sum wraps on overflow, as it does on the GPU.
+pub fn prefix_sum_inclusive_cpu(data: &mut [u32]) {
+    let mut sum = 0u32;
+
+    for value in data.iter_mut() {
+        sum = sum.wrapping_add(*value);
+
+        *value = sum;
+    }
+}
+
+/// The output of [find_runs_cpu]; see [FindRunsOutput](crate::find_runs::FindRunsOutput).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FindRunsCpuOutput {
+    /// The index at which each run starts; the number of runs is the length of this vector.
+    pub run_starts: Vec<u32>,
+    /// The index of the run each input value belongs to.
+    pub run_mapping: Vec<u32>,
+}
+
+/// Finds the runs of equal values in the `data` on the host, in the same way as a
+/// [FindRuns](crate::find_runs::FindRuns).
+///
+/// Values are compared with `==`, as on the GPU, so for `f32` values `-0.0` and `0.0` belong to
+/// the same run and every NaN value starts a new run.
+pub fn find_runs_cpu<T>(data: &[T]) -> FindRunsCpuOutput
+where
+    T: PartialEq,
+{
+    let mut run_starts = Vec::new();
+    let mut run_mapping = Vec::with_capacity(data.len());
+
+    for (i, value) in data.iter().enumerate() {
+        if i == 0 || *value != data[i - 1] {
+            run_starts.push(i as u32);
+        }
+
+        run_mapping.push(run_starts.len() as u32 - 1);
+    }
+
+    FindRunsCpuOutput {
+        run_starts,
+        run_mapping,
+    }
+}
+
+/// The order-preserving unsigned integer key the shaders derive from a value.
+fn radix_key<T>(value: T) -> u32
+where
+    T: RadixKey,
+{
+    let bits = Sealed::to_bits(value);
+
+    match T::KEY_TYPE {
+        KeyType::U32 => bits,
+        KeyType::I32 => bits ^ 0x8000_0000,
+        KeyType::F32 => {
+            if bits & 0x8000_0000 != 0 {
+                !bits
+            } else {
+                bits | 0x8000_0000
+            }
+        }
+    }
+}
@@ -0,0 +1,14 @@
+// Functions shared by the indirect dispatch generation and the kernels it dispatches.
+//
+// A generated dispatch spills workgroups in excess of the device's `max_compute_workgroups_per_dimension` limit into
+// the `y` dimension, so kernels that may be dispatched indirectly map their workgroup back to a linear index with
+// `linear_workgroup_index`, and must tolerate a small number of excess workgroups.
+
+// The WGSL counterpart of `util::div_ceil`, which documents why it is preferred over `(a + b - 1u) / b`.
+fn div_ceil(a: u32, b: u32) -> u32 {
+    return a / b + select(0u, 1u, a % b != 0u);
+}
+
+fn linear_workgroup_index(workgroup_id: vec3<u32>, num_workgroups: vec3<u32>) -> u32 {
+    return workgroup_id.y * num_workgroups.x + workgroup_id.x;
+}
@@ -0,0 +1,92 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer;
+use empa::buffer::{Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::{BindGroupLayout, Resources};
+use empa::shader_module::{shader_source, ShaderSource};
+
+use crate::find_runs::GROUPS_SIZE;
+use crate::util::div_ceil;
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+#[derive(empa::resource_binding::Resources)]
+pub struct CollectKeptRunsResources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    pub count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    pub kept_offsets: Storage<'a, [u32]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    pub run_starts: Storage<'a, [u32], ReadWrite>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    pub run_count: Storage<'a, u32, ReadWrite>,
+}
+
+type ResourcesLayout = <CollectKeptRunsResources<'static> as Resources>::Layout;
+
+pub struct CollectKeptRuns {
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: ComputePipeline<(ResourcesLayout,)>,
+}
+
+impl CollectKeptRuns {
+    pub async fn init(device: Device) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        CollectKeptRuns {
+            device,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    pub fn encode<U>(
+        &self,
+        encoder: CommandEncoder,
+        resources: CollectKeptRunsResources,
+        dispatch_indirect: bool,
+        dispatch: buffer::View<DispatchWorkgroups, U>,
+        fallback_count: u32,
+    ) -> CommandEncoder
+    where
+        U: buffer::Indirect,
+    {
+        let bind_group = self
+            .device
+            .create_bind_group(&self.bind_group_layout, resources);
+
+        let encoder = encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group);
+
+        if dispatch_indirect {
+            encoder.dispatch_workgroups_indirect(dispatch).end()
+        } else {
+            encoder
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: div_ceil(fallback_count, GROUPS_SIZE),
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end()
+        }
+    }
+}
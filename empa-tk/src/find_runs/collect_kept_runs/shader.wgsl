@@ -0,0 +1,43 @@
+#include "../../dispatch.wgsl"
+
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<storage, read> kept_offsets: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read_write> run_starts: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read_write> run_count: u32;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let index = linear_workgroup_index(workgroup_id, num_workgroups) * 256u + local_index;
+    let data_count = min(count, arrayLength(&kept_offsets));
+
+    if index >= data_count {
+        return;
+    }
+
+    // The offsets are an inclusive prefix sum over the flags of the kept run starts, so a kept run starts here if the
+    // offset differs from the offset of the preceding value.
+    let offset = kept_offsets[index];
+
+    var previous = 0u;
+
+    if index > 0 {
+        previous = kept_offsets[index - 1];
+    }
+
+    // Runs beyond the capacity of the output are dropped; the run count still counts them, so that the overflow can
+    // be detected
+    if offset != previous && offset - 1 < arrayLength(&run_starts) {
+        run_starts[offset - 1] = index;
+    }
+
+    if index == data_count - 1 {
+        run_count = offset;
+    }
+}
@@ -10,6 +10,7 @@ use empa::resource_binding::{BindGroupLayout, Resources};
 use empa::shader_module::{shader_source, ShaderSource};
 
 use crate::find_runs::GROUPS_SIZE;
+use crate::util::div_ceil;
 
 const SHADER: ShaderSource = shader_source!("shader.wgsl");
 
@@ -79,7 +80,7 @@ impl CollectRunStarts {
         } else {
             encoder
                 .dispatch_workgroups(DispatchWorkgroups {
-                    count_x: fallback_count.div_ceil(GROUPS_SIZE),
+                    count_x: div_ceil(fallback_count, GROUPS_SIZE),
                     count_y: 1,
                     count_z: 1,
                 })
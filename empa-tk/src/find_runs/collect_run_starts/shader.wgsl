@@ -1,3 +1,5 @@
+#include "../../dispatch.wgsl"
+
 @group(0) @binding(0)
 var<uniform> count: u32;
 
@@ -8,16 +10,20 @@ var<storage, read> temporary_storage: array<u32>;
 var<storage, read_write> run_starts: array<u32>;
 
 @compute @workgroup_size(256, 1, 1)
-fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
-    let index = global_id.x;
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let index = linear_workgroup_index(workgroup_id, num_workgroups) * 256u + local_index;
 
-    if index >= count {
+    if index >= min(count, arrayLength(&temporary_storage)) {
         return;
     }
 
     if index == 0 || temporary_storage[index] != temporary_storage[index - 1] {
         let run_index = temporary_storage[index];
 
-        run_starts[run_index] = index;
+        // Runs beyond the capacity of the output are dropped; the run count still counts them, so that the overflow
+        // can be detected
+        if run_index < arrayLength(&run_starts) {
+            run_starts[run_index] = index;
+        }
     }
 }
@@ -0,0 +1,37 @@
+#include "../../dispatch.wgsl"
+
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<uniform> min_length: u32;
+
+@group(0) @binding(2)
+var<storage, read> run_mapping: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read_write> kept: array<u32>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let index = linear_workgroup_index(workgroup_id, num_workgroups) * 256u + local_index;
+    let data_count = min(count, min(arrayLength(&run_mapping), arrayLength(&kept)));
+
+    if index >= data_count {
+        return;
+    }
+
+    var is_kept = 0u;
+
+    if index == 0 || run_mapping[index] != run_mapping[index - 1] {
+        // A run that starts here is at least `min_length` values long if the value `min_length - 1` positions further
+        // along still belongs to it
+        let distance = min_length - 1u;
+
+        if distance < data_count - index && run_mapping[index + distance] == run_mapping[index] {
+            is_kept = 1u;
+        }
+    }
+
+    kept[index] = is_kept;
+}
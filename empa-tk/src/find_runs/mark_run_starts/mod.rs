@@ -10,6 +10,9 @@ use empa::shader_module::{shader_source, ShaderSource};
 use empa::{abi, buffer};
 
 use crate::find_runs::GROUPS_SIZE;
+use crate::radix_key::sealed::KeyType;
+use crate::radix_key::RadixKey;
+use crate::util::div_ceil;
 
 const SHADER_U32: ShaderSource = shader_source!("shader_u32.wgsl");
 const SHADER_I32: ShaderSource = shader_source!("shader_i32.wgsl");
@@ -90,7 +93,7 @@ where
         } else {
             encoder
                 .dispatch_workgroups(DispatchWorkgroups {
-                    count_x: fallback_count.div_ceil(GROUPS_SIZE),
+                    count_x: div_ceil(fallback_count, GROUPS_SIZE),
                     count_y: 1,
                     count_z: 1,
                 })
@@ -99,20 +102,17 @@ where
     }
 }
 
-impl MarkRunStarts<u32> {
-    pub async fn init_u32(device: Device) -> Self {
-        Self::init_internal(device, &SHADER_U32).await
-    }
-}
-
-impl MarkRunStarts<i32> {
-    pub async fn init_i32(device: Device) -> Self {
-        Self::init_internal(device, &SHADER_I32).await
-    }
-}
+impl<T> MarkRunStarts<T>
+where
+    T: RadixKey,
+{
+    pub async fn init(device: Device) -> Self {
+        let shader_source = match T::KEY_TYPE {
+            KeyType::U32 => &SHADER_U32,
+            KeyType::I32 => &SHADER_I32,
+            KeyType::F32 => &SHADER_F32,
+        };
 
-impl MarkRunStarts<f32> {
-    pub async fn init_f32(device: Device) -> Self {
-        Self::init_internal(device, &SHADER_F32).await
+        Self::init_internal(device, shader_source).await
     }
 }
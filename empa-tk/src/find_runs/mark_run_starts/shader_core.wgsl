@@ -1,3 +1,5 @@
+#include "../../dispatch.wgsl"
+
 @group(0) @binding(0)
 var<uniform> count: u32;
 
@@ -8,10 +10,11 @@ var<storage, read> data: array<DATA_TYPE>;
 var<storage, read_write> temporary_storage: array<u32>;
 
 @compute @workgroup_size(256, 1, 1)
-fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
-    let index = global_id.x;
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let index = linear_workgroup_index(workgroup_id, num_workgroups) * 256u + local_index;
+    let data_count = min(count, min(arrayLength(&data), arrayLength(&temporary_storage)));
 
-    if index != 0 && index < count {
+    if index != 0 && index < data_count {
         if data[index] != data[index - 1] {
             temporary_storage[index] = 1u;
         }
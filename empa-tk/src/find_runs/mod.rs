@@ -1,53 +1,128 @@
-use std::future::{join, Future};
+use std::future::Future;
 
-use empa::buffer::{Buffer, Uniform};
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, Uniform};
 use empa::command::{CommandEncoder, DispatchWorkgroups};
 use empa::device::Device;
 use empa::type_flag::{O, X};
 use empa::{abi, buffer};
+use futures::join;
 
+use crate::cost_estimate::CostEstimate;
 use crate::count_buffer::CountBuffer;
+use crate::find_runs::collect_kept_runs::{CollectKeptRuns, CollectKeptRunsResources};
 use crate::find_runs::collect_run_starts::{CollectRunStarts, CollectRunStartsResources};
+use crate::find_runs::mark_kept_runs::{MarkKeptRuns, MarkKeptRunsResources};
 use crate::find_runs::mark_run_starts::{MarkRunStarts, MarkRunStartsResources};
+use crate::find_runs::resolve_intra_run_offsets::{
+    ResolveIntraRunOffsets, ResolveIntraRunOffsetsResources,
+};
 use crate::find_runs::resolve_run_count::{ResolveRunCount, ResolveRunCountResources};
 use crate::generate_dispatch::{GenerateDispatch, GenerateDispatchResources};
-use crate::prefix_sum::{PrefixSum, PrefixSumInput};
+use crate::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
+use crate::radix_key::RadixKey;
+use crate::requirements::Requirements;
+use crate::scratch_pool::ScratchPool;
+use crate::uniform_cache::UniformCache;
+use crate::util::is_empty_input;
 
+mod collect_kept_runs;
 mod collect_run_starts;
+mod mark_kept_runs;
 mod mark_run_starts;
+mod resolve_intra_run_offsets;
 mod resolve_run_count;
 
 const GROUPS_SIZE: u32 = 256;
 
 pub struct FindRunsInput<'a, T, U> {
     pub data: buffer::View<'a, [T], U>,
+    /// The number of values to search, if it is only known on the device (e.g. the output of a
+    /// prior compaction).
+    ///
+    /// When specified, only the values in `data[..count]` are searched: the values beyond the
+    /// count do not contribute to the runs, and the run count is resolved from the run index of
+    /// the value at `count - 1` rather than from the last value in `data`.
     pub count: Option<Uniform<'a, u32>>,
 }
 
 pub struct FindRunsOutput<'a, U0, U1, U2> {
+    /// Receives the number of runs; `0` if the input is empty.
     pub run_count: buffer::View<'a, u32, U0>,
+    /// Receives the index at which each run starts.
+    ///
+    /// The length of this buffer is its capacity: if there are more runs than it can hold, then
+    /// only the starts of the first runs are written and the remaining runs are dropped. The
+    /// `run_count` still receives the actual number of runs, so compare it against the capacity to
+    /// detect an overflow. Holding as many elements as the input data (every value may start a
+    /// new run) guarantees that no runs are dropped. Must not be empty unless the input is empty.
     pub run_starts: buffer::View<'a, [u32], U1>,
+    /// Receives the index of the run each input value belongs to; must hold at least as many
+    /// elements as the input data.
+    ///
+    /// This is also the buffer in which the run starts are marked and scanned, but the final
+    /// contents are the run indices (the inclusive prefix sum of the run start flags, where the
+    /// first value is not flagged), so it may be used to key subsequent segmented operations.
     pub run_mapping: buffer::View<'a, [u32], U2>,
 }
 
+/// Buffers that can receive the output of a run search; see [FindRuns::output_buffers].
+pub struct FindRunsOutputBuffers {
+    pub run_count: Buffer<u32, buffer::Usages<O, O, X, O, O, O, O, X, O, O>>,
+    pub run_starts: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, O, X, O, O>>,
+    pub run_mapping: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, X, X, O, O>>,
+}
+
+impl FindRunsOutputBuffers {
+    pub fn view(
+        &self,
+    ) -> FindRunsOutput<
+        buffer::Usages<O, O, X, O, O, O, O, X, O, O>,
+        buffer::Usages<O, O, X, O, O, O, O, X, O, O>,
+        buffer::Usages<O, O, X, O, O, O, X, X, O, O>,
+    > {
+        FindRunsOutput {
+            run_count: self.run_count.view(),
+            run_starts: self.run_starts.view(),
+            run_mapping: self.run_mapping.view(),
+        }
+    }
+}
+
+/// Finds the runs of equal consecutive values in the input data.
+///
+/// There is no separate per-element run id output: the [FindRunsOutput::run_mapping] already
+/// receives the index of the run each input value belongs to, so it can key subsequent segmented
+/// operations directly.
 pub struct FindRuns<T>
 where
     T: abi::Sized,
 {
     device: Device,
+    fallback_count: UniformCache<u32>,
     mark_run_starts: MarkRunStarts<T>,
     prefix_sum_inclusive: PrefixSum<u32>,
     collect_run_starts: CollectRunStarts,
     resolve_run_count: ResolveRunCount,
+    resolve_intra_run_offsets: ResolveIntraRunOffsets,
+    mark_kept_runs: MarkKeptRuns,
+    collect_kept_runs: CollectKeptRuns,
     generate_dispatch: GenerateDispatch,
     group_size: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
     dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+    empty_run_mapping: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    min_length: UniformCache<u32>,
+    kept_runs: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
 }
 
 impl<T> FindRuns<T>
 where
     T: abi::Sized + 'static,
 {
+    pub fn requirements() -> Requirements {
+        PrefixSum::<u32>::requirements().max(Requirements::new(GROUPS_SIZE, 0, 3))
+    }
+
     async fn init_internal(
         device: Device,
         init_mark_run_starts: impl Future<Output = MarkRunStarts<T>>,
@@ -57,15 +132,20 @@ where
             prefix_sum_inclusive,
             collect_run_starts,
             resolve_run_count,
+            resolve_intra_run_offsets,
+            mark_kept_runs,
+            collect_kept_runs,
             generate_dispatch,
         ) = join!(
             init_mark_run_starts,
             PrefixSum::init_inclusive_u32(device.clone()),
             CollectRunStarts::init(device.clone()),
             ResolveRunCount::init(device.clone()),
+            ResolveIntraRunOffsets::init(device.clone()),
+            MarkKeptRuns::init(device.clone()),
+            CollectKeptRuns::init(device.clone()),
             GenerateDispatch::init(device.clone()),
-        )
-        .await;
+        );
 
         let group_size = device.create_buffer(GROUPS_SIZE, buffer::Usages::uniform_binding());
         let dispatch = device.create_buffer(
@@ -77,23 +157,200 @@ where
             buffer::Usages::storage_binding().and_indirect(),
         );
 
+        let empty_run_mapping =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+
+        let fallback_count = UniformCache::new(&device, 0);
+        let min_length = UniformCache::new(&device, 1);
+        let kept_runs = device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+
         FindRuns {
             device,
+            fallback_count,
             mark_run_starts,
             prefix_sum_inclusive,
             collect_run_starts,
             resolve_run_count,
+            resolve_intra_run_offsets,
+            mark_kept_runs,
+            collect_kept_runs,
             generate_dispatch,
             group_size,
             dispatch,
+            empty_run_mapping,
+            min_length,
+            kept_runs,
+        }
+    }
+
+    /// Allocates output buffers for searching the runs in up to `len` values.
+    ///
+    /// The `run_starts` buffer holds `len` elements, so that no runs are dropped. All buffers
+    /// can be copied to a readback buffer.
+    pub fn output_buffers(device: &Device, len: usize) -> FindRunsOutputBuffers {
+        FindRunsOutputBuffers {
+            run_count: device
+                .create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_src()),
+            run_starts: device
+                .create_slice_buffer_zeroed(len, buffer::Usages::storage_binding().and_copy_src()),
+            run_mapping: device.create_slice_buffer_zeroed(
+                len,
+                buffer::Usages::storage_binding()
+                    .and_copy_dst()
+                    .and_copy_src(),
+            ),
+        }
+    }
+
+    /// Estimates the cost of searching the runs in `count` values.
+    ///
+    /// The `run_mapping` output doubles as the temporary storage of the search, so the scratch
+    /// memory is only the per-workgroup state of the internal prefix sum.
+    pub fn plan_cost(&self, count: u32) -> CostEstimate {
+        // An empty input only resolves the run count
+        if count == 0 {
+            return CostEstimate::new(0, 1, 0);
+        }
+
+        let prefix_sum = self.prefix_sum_inclusive.plan_cost(count);
+
+        // Marking the run starts, the prefix sum and collecting the run starts each pass over the
+        // values, followed by a dispatch that resolves the run count
+        CostEstimate::new(
+            prefix_sum.scratch_bytes,
+            prefix_sum.dispatch_count + 3,
+            prefix_sum.pass_count + 2,
+        )
+    }
+
+    /// Shrinks the temporary state of the internal prefix sum, and the buffer in which
+    /// [encode_with_min_length](Self::encode_with_min_length) filters the runs, to what searching
+    /// up to `max_count` values requires; see [PrefixSum::shrink_to_fit].
+    pub fn shrink_to_fit(&mut self, max_count: u32) {
+        self.prefix_sum_inclusive.shrink_to_fit(max_count);
+
+        let len = (max_count as usize).max(1);
+
+        if self.kept_runs.len() > len {
+            self.kept_runs = self
+                .device
+                .create_slice_buffer_zeroed(len, self.kept_runs.usage());
         }
     }
 
     pub fn encode<U0, U1, U2, U3>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: FindRunsInput<T, U0>,
+        output: FindRunsOutput<U1, U2, U3>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+        U3: buffer::StorageBinding + buffer::CopyDst + 'static,
+    {
+        self.encode_internal(encoder, input, output, None, None, None)
+    }
+
+    /// Encodes the run search, additionally writing the offset of each input value within its
+    /// run (the distance to the start of its run) to `intra_run_offsets`.
+    ///
+    /// The offsets of values in runs that exceed the capacity of `output.run_starts` are
+    /// unspecified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `intra_run_offsets` holds fewer elements than the input data.
+    ///
+    /// The offsets are requested through this method rather than through an optional field of
+    /// [FindRunsOutput]: such a field would add a usage type parameter to [FindRunsOutput] that
+    /// every caller (including the operations built on a [FindRuns], such as
+    /// [ReduceByKey](crate::reduce_by_key::ReduceByKey)) would have to name, even when it does not
+    /// request the offsets.
+    pub fn encode_with_intra_run_offsets<U0, U1, U2, U3, U4>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: FindRunsInput<T, U0>,
+        output: FindRunsOutput<U1, U2, U3>,
+        intra_run_offsets: buffer::View<[u32], U4>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+        U3: buffer::StorageBinding + buffer::CopyDst + 'static,
+        U4: buffer::StorageBinding,
+    {
+        assert!(
+            intra_run_offsets.len() >= input.data.len(),
+            "`intra_run_offsets` must hold at least as many elements as `data`"
+        );
+
+        // For an empty input there are no offsets to write
+        let intra_run_offsets = if !is_empty_input(input.data.len()) {
+            Some(intra_run_offsets.storage())
+        } else {
+            None
+        };
+
+        self.encode_internal(encoder, input, output, intra_run_offsets, None, None)
+    }
+
+    /// Encodes the run search, only retaining the runs that hold at least `min_length` values.
+    ///
+    /// The `run_count` receives the number of retained runs, and `run_starts` receives the starts
+    /// of the retained runs, in order. The `run_mapping` still receives the index of each value's
+    /// run among all runs, including the runs that are not retained. A `min_length` of `0` or `1`
+    /// retains all runs.
+    pub fn encode_with_min_length<U0, U1, U2, U3>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: FindRunsInput<T, U0>,
+        output: FindRunsOutput<U1, U2, U3>,
+        min_length: u32,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+        U3: buffer::StorageBinding + buffer::CopyDst + 'static,
+    {
+        let min_length = if min_length > 1 {
+            Some(min_length)
+        } else {
+            None
+        };
+
+        self.encode_internal(encoder, input, output, None, None, min_length)
+    }
+
+    /// Encodes the run search, using the temporary buffers in the `scratch_pool` rather than
+    /// the run search's own temporary buffers.
+    pub fn encode_with_scratch_pool<U0, U1, U2, U3>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: FindRunsInput<T, U0>,
+        output: FindRunsOutput<U1, U2, U3>,
+        scratch_pool: &mut ScratchPool,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+        U3: buffer::StorageBinding + buffer::CopyDst + 'static,
+    {
+        self.encode_internal(encoder, input, output, None, Some(scratch_pool), None)
+    }
+
+    fn encode_internal<U0, U1, U2, U3>(
         &mut self,
         mut encoder: CommandEncoder,
         input: FindRunsInput<T, U0>,
         output: FindRunsOutput<U1, U2, U3>,
+        intra_run_offsets: Option<Storage<[u32], ReadWrite>>,
+        scratch_pool: Option<&mut ScratchPool>,
+        min_length: Option<u32>,
     ) -> CommandEncoder
     where
         U0: buffer::StorageBinding,
@@ -109,9 +366,33 @@ where
             run_mapping,
         } = output;
 
+        assert!(
+            run_starts.len() > 0 || data.len() == 0,
+            "`run_starts` must not be empty for a non-empty input"
+        );
+        assert!(
+            run_mapping.len() >= data.len(),
+            "`run_mapping` must hold at least as many elements as `data`"
+        );
+
         let dispatch_indirect = count.is_some();
 
-        let count = CountBuffer::new(count, &self.device, data.len() as u32);
+        self.fallback_count.update(&self.device, data.len() as u32);
+
+        // An empty input has no runs. The run count must still be written, but an empty buffer
+        // view cannot be bound, so the run count is resolved against a placeholder instead.
+        if data.len() == 0 {
+            return self.resolve_run_count.encode(
+                encoder,
+                ResolveRunCountResources {
+                    count: self.fallback_count.uniform(),
+                    temporary_storage: self.empty_run_mapping.storage(),
+                    run_count: run_count.storage(),
+                },
+            );
+        }
+
+        let count = CountBuffer::new(count, &self.fallback_count);
 
         if dispatch_indirect {
             encoder = self.generate_dispatch.encode(
@@ -120,6 +401,7 @@ where
                     group_size: self.group_size.uniform(),
                     count: count.uniform(),
                     dispatch: self.dispatch.storage(),
+                    max_count: None,
                 },
             )
         }
@@ -136,17 +418,76 @@ where
             self.dispatch.view(),
             data.len() as u32,
         );
-        encoder = self.prefix_sum_inclusive.encode(
-            encoder,
-            PrefixSumInput {
-                data: run_mapping,
-                count: if dispatch_indirect {
-                    Some(count.uniform())
-                } else {
-                    None
-                },
+
+        let prefix_sum_input = PrefixSumInput {
+            data: run_mapping,
+            count: if dispatch_indirect {
+                Some(count.uniform())
+            } else {
+                None
             },
-        );
+            initial: None,
+            order: ScanOrder::Ascending,
+        };
+
+        encoder = if let Some(scratch_pool) = scratch_pool {
+            self.prefix_sum_inclusive.encode_with_scratch_pool(
+                encoder,
+                prefix_sum_input,
+                scratch_pool,
+            )
+        } else {
+            self.prefix_sum_inclusive.encode(encoder, prefix_sum_input)
+        };
+
+        if let Some(min_length) = min_length {
+            self.min_length.update(&self.device, min_length);
+
+            if self.kept_runs.len() < data.len() {
+                self.kept_runs = self
+                    .device
+                    .create_slice_buffer_zeroed(data.len(), self.kept_runs.usage());
+            }
+
+            encoder = self.mark_kept_runs.encode(
+                encoder,
+                MarkKeptRunsResources {
+                    count: count.uniform(),
+                    min_length: self.min_length.uniform(),
+                    run_mapping: run_mapping.storage(),
+                    kept: self.kept_runs.storage(),
+                },
+                dispatch_indirect,
+                self.dispatch.view(),
+                data.len() as u32,
+            );
+
+            // The kept runs buffer may be longer than the data, so the scan is always bounded by
+            // the count
+            encoder = self.prefix_sum_inclusive.encode(
+                encoder,
+                PrefixSumInput {
+                    data: self.kept_runs.view(),
+                    count: Some(count.uniform()),
+                    initial: None,
+                    order: ScanOrder::Ascending,
+                },
+            );
+
+            return self.collect_kept_runs.encode(
+                encoder,
+                CollectKeptRunsResources {
+                    count: count.uniform(),
+                    kept_offsets: self.kept_runs.storage(),
+                    run_starts: run_starts.storage(),
+                    run_count: run_count.storage(),
+                },
+                dispatch_indirect,
+                self.dispatch.view(),
+                data.len() as u32,
+            );
+        }
+
         encoder = self.collect_run_starts.encode(
             encoder,
             CollectRunStartsResources {
@@ -167,30 +508,70 @@ where
             },
         );
 
+        if let Some(intra_run_offsets) = intra_run_offsets {
+            encoder = self.resolve_intra_run_offsets.encode(
+                encoder,
+                ResolveIntraRunOffsetsResources {
+                    count: count.uniform(),
+                    run_starts: run_starts.storage(),
+                    run_mapping: run_mapping.storage(),
+                    intra_run_offsets,
+                },
+                dispatch_indirect,
+                self.dispatch.view(),
+                data.len() as u32,
+            );
+        }
+
         encoder
     }
 }
 
+impl<T> FindRuns<T>
+where
+    T: RadixKey,
+{
+    pub async fn init(device: Device) -> Self {
+        let init_mark_run_starts = MarkRunStarts::init(device.clone());
+
+        FindRuns::init_internal(device, init_mark_run_starts).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init(device))
+    }
+}
+
 impl FindRuns<u32> {
     pub async fn init_u32(device: Device) -> Self {
-        let init_mark_run_starts = MarkRunStarts::init_u32(device.clone());
+        Self::init(device).await
+    }
 
-        FindRuns::init_internal(device, init_mark_run_starts).await
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_u32(device))
     }
 }
 
 impl FindRuns<i32> {
     pub async fn init_i32(device: Device) -> Self {
-        let init_mark_run_starts = MarkRunStarts::init_i32(device.clone());
+        Self::init(device).await
+    }
 
-        FindRuns::init_internal(device, init_mark_run_starts).await
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_i32(device))
     }
 }
 
 impl FindRuns<f32> {
     pub async fn init_f32(device: Device) -> Self {
-        let init_mark_run_starts = MarkRunStarts::init_f32(device.clone());
+        Self::init(device).await
+    }
 
-        FindRuns::init_internal(device, init_mark_run_starts).await
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_f32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_f32(device))
     }
 }
@@ -0,0 +1,25 @@
+#include "../../dispatch.wgsl"
+
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<storage, read> run_starts: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read> run_mapping: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read_write> intra_run_offsets: array<u32>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let index = linear_workgroup_index(workgroup_id, num_workgroups) * 256u + local_index;
+
+    if index >= min(count, min(arrayLength(&run_mapping), arrayLength(&intra_run_offsets))) {
+        return;
+    }
+
+    // The first value always starts the first run, so its offset resolves to `0` like that of any other run start
+    intra_run_offsets[index] = index - run_starts[run_mapping[index]];
+}
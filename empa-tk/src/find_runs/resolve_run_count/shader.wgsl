@@ -9,5 +9,11 @@ var<storage, read_write> run_count: u32;
 
 @compute @workgroup_size(1, 1, 1)
 fn main() {
-    run_count = temporary_storage[count - 1] + 1;
+    let data_count = min(count, arrayLength(&temporary_storage));
+
+    if data_count == 0 {
+        run_count = 0u;
+    } else {
+        run_count = temporary_storage[data_count - 1] + 1;
+    }
 }
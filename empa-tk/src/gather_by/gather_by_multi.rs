@@ -0,0 +1,306 @@
+use std::fmt::Write;
+
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::ShaderSource;
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::generate_dispatch::{GenerateDispatch, GenerateDispatchResources};
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+use crate::write_value_type::{write_value_type, ValueTypeError};
+
+const SHADER_TEMPLATE: &str = concat!(
+    include_str!("../dispatch.wgsl"),
+    include_str!("multi_shader_template.wgsl")
+);
+
+const GROUP_SIZE: u32 = 256;
+
+/// The number of arrays a [GatherByMulti] gathers in a single pass.
+///
+/// More arrays may be provided; these are gathered in additional passes of up to this many arrays.
+pub const GATHER_BY_MULTI_ARRAYS_PER_PASS: usize = 3;
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a, B, V>
+where
+    B: abi::Sized,
+    V: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    gather_by: Storage<'a, [B]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    data_in_0: Storage<'a, [V]>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    data_in_1: Storage<'a, [V]>,
+    #[resource(binding = 4, visibility = "COMPUTE")]
+    data_in_2: Storage<'a, [V]>,
+    #[resource(binding = 5, visibility = "COMPUTE")]
+    data_out_0: Storage<'a, [V], ReadWrite>,
+    #[resource(binding = 6, visibility = "COMPUTE")]
+    data_out_1: Storage<'a, [V], ReadWrite>,
+    #[resource(binding = 7, visibility = "COMPUTE")]
+    data_out_2: Storage<'a, [V], ReadWrite>,
+    #[resource(binding = 8, visibility = "COMPUTE")]
+    array_count: Uniform<'a, u32>,
+}
+
+type ResourcesLayout<B, V> =
+    <Resources<'static, B, V> as empa::resource_binding::Resources>::Layout;
+
+pub struct GatherByMultiInput<'a, 'b, B, V, U0, U1> {
+    pub gather_by: buffer::View<'a, [B], U0>,
+    /// The arrays to gather from; the indices in `gather_by` index into each of these arrays.
+    pub data: &'b [buffer::View<'a, [V], U1>],
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// Applies the same gather to several arrays of values (e.g. the attribute arrays of a
+/// structure-of-arrays layout), loading each index only once for up to
+/// [GATHER_BY_MULTI_ARRAYS_PER_PASS] arrays.
+///
+/// For each output array `j` and each `i` in `0..gather_by.len()`, the value at
+/// `data[j][gather_by[i]]` is written to `output[j][i]`. To gather attributes of different types,
+/// consider combining them into a single value type for a [GatherBy](super::GatherBy) instead.
+pub struct GatherByMulti<B, V>
+where
+    B: abi::Sized,
+    V: abi::Sized,
+{
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<B, V>>,
+    pipeline: ComputePipeline<(ResourcesLayout<B, V>,)>,
+    generate_dispatch: GenerateDispatch,
+    group_size: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
+    dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+    array_counts: [Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>;
+        GATHER_BY_MULTI_ARRAYS_PER_PASS],
+    placeholder_in: Buffer<[V], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    placeholder_out: [Buffer<[V], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>;
+        GATHER_BY_MULTI_ARRAYS_PER_PASS],
+}
+
+impl<B, V> GatherByMulti<B, V>
+where
+    B: abi::Sized + 'static,
+    V: abi::Sized + 'static,
+{
+    pub fn requirements() -> Requirements {
+        Requirements::new(
+            GROUP_SIZE,
+            0,
+            1 + 2 * GATHER_BY_MULTI_ARRAYS_PER_PASS as u32,
+        )
+    }
+
+    async fn init_internal(device: Device, by_type: &str) -> Result<Self, ValueTypeError> {
+        let mut code = String::new();
+
+        write_value_type::<V>(&device, &mut code)?;
+
+        write!(code, "alias BY_TYPE = {};\n\n{}", by_type, SHADER_TEMPLATE).unwrap();
+
+        let shader_source = ShaderSource::unparsed(code);
+        let shader = device.create_shader_module(&shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<B, V>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let create_pipeline = unsafe {
+            device.create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute_unchecked(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+        };
+        let init_generate_dispatch = GenerateDispatch::init(device.clone());
+
+        let (pipeline, generate_dispatch) = join!(create_pipeline, init_generate_dispatch);
+
+        let group_size = device.create_buffer(GROUP_SIZE, buffer::Usages::uniform_binding());
+        let dispatch = device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+        let array_counts = [1, 2, 3].map(|array_count| {
+            device.create_buffer(array_count, buffer::Usages::uniform_binding())
+        });
+        let placeholder_in =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+
+        // Writable bindings must not alias, so each unused output needs its own placeholder
+        let placeholder_out = [(); GATHER_BY_MULTI_ARRAYS_PER_PASS]
+            .map(|_| device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding()));
+
+        let fallback_count = UniformCache::new(&device, 0);
+
+        Ok(GatherByMulti {
+            device,
+            fallback_count,
+            bind_group_layout,
+            pipeline,
+            generate_dispatch,
+            group_size,
+            dispatch,
+            array_counts,
+            placeholder_in,
+            placeholder_out,
+        })
+    }
+
+    /// Encodes the gather of each of the `input.data` arrays into the corresponding `output`
+    /// array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of `output` arrays does not match the number of `input.data` arrays,
+    /// or if an `output` array holds fewer elements than `input.gather_by`.
+    pub fn encode<U0, U1, U2>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: GatherByMultiInput<B, V, U0, U1>,
+        output: &[buffer::View<[V], U2>],
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        let GatherByMultiInput {
+            gather_by,
+            data,
+            count,
+        } = input;
+
+        assert_eq!(
+            data.len(),
+            output.len(),
+            "there must be an `output` array for each `data` array"
+        );
+        assert!(
+            output.iter().all(|output| output.len() >= gather_by.len()),
+            "each `output` array must hold at least as many elements as `gather_by`"
+        );
+
+        if is_empty_input(gather_by.len()) || data.iter().any(|data| is_empty_input(data.len())) {
+            return encoder;
+        }
+
+        let dispatch_indirect = count.is_some();
+        let fallback_count = gather_by.len() as u32;
+
+        self.fallback_count.update(&self.device, fallback_count);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        if dispatch_indirect {
+            encoder = self.generate_dispatch.encode(
+                encoder,
+                GenerateDispatchResources {
+                    group_size: self.group_size.uniform(),
+                    count: count.uniform(),
+                    dispatch: self.dispatch.storage(),
+                    max_count: None,
+                },
+            );
+        }
+
+        for (data, output) in data
+            .chunks(GATHER_BY_MULTI_ARRAYS_PER_PASS)
+            .zip(output.chunks(GATHER_BY_MULTI_ARRAYS_PER_PASS))
+        {
+            let data_in = |i: usize| {
+                data.get(i)
+                    .map(|data| data.storage())
+                    .unwrap_or_else(|| self.placeholder_in.storage())
+            };
+            let data_out = |i: usize| {
+                output
+                    .get(i)
+                    .map(|output| output.storage())
+                    .unwrap_or_else(|| self.placeholder_out[i].storage())
+            };
+
+            let bind_group = self.device.create_bind_group(
+                &self.bind_group_layout,
+                Resources {
+                    count: count.uniform(),
+                    gather_by: gather_by.storage(),
+                    data_in_0: data_in(0),
+                    data_in_1: data_in(1),
+                    data_in_2: data_in(2),
+                    data_out_0: data_out(0),
+                    data_out_1: data_out(1),
+                    data_out_2: data_out(2),
+                    array_count: self.array_counts[data.len() - 1].uniform(),
+                },
+            );
+
+            let pass = encoder
+                .begin_compute_pass()
+                .set_pipeline(&self.pipeline)
+                .set_bind_groups(&bind_group);
+
+            encoder = if dispatch_indirect {
+                pass.dispatch_workgroups_indirect(self.dispatch.view())
+                    .end()
+            } else {
+                pass.dispatch_workgroups(DispatchWorkgroups {
+                    count_x: div_ceil(fallback_count, GROUP_SIZE),
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end()
+            };
+        }
+
+        encoder
+    }
+}
+
+impl<V> GatherByMulti<u32, V>
+where
+    V: abi::Sized + 'static,
+{
+    pub async fn init_u32(device: Device) -> Result<Self, ValueTypeError> {
+        Self::init_internal(device, "u32").await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_u32(device))
+    }
+}
+
+impl<V> GatherByMulti<i32, V>
+where
+    V: abi::Sized + 'static,
+{
+    pub async fn init_i32(device: Device) -> Result<Self, ValueTypeError> {
+        Self::init_internal(device, "i32").await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_i32(device))
+    }
+}
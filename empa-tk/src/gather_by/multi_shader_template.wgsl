@@ -0,0 +1,49 @@
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<storage, read> gather_by: array<BY_TYPE>;
+
+@group(0) @binding(2)
+var<storage, read> data_in_0: array<VALUE_TYPE>;
+
+@group(0) @binding(3)
+var<storage, read> data_in_1: array<VALUE_TYPE>;
+
+@group(0) @binding(4)
+var<storage, read> data_in_2: array<VALUE_TYPE>;
+
+@group(0) @binding(5)
+var<storage, read_write> data_out_0: array<VALUE_TYPE>;
+
+@group(0) @binding(6)
+var<storage, read_write> data_out_1: array<VALUE_TYPE>;
+
+@group(0) @binding(7)
+var<storage, read_write> data_out_2: array<VALUE_TYPE>;
+
+// The number of the arrays above that are in use; the bindings of unused arrays hold placeholder buffers.
+@group(0) @binding(8)
+var<uniform> array_count: u32;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let index = linear_workgroup_index(workgroup_id, num_workgroups) * 256u + local_index;
+
+    if index >= min(count, arrayLength(&gather_by)) {
+        return;
+    }
+
+    // The index is loaded once for all arrays
+    let src_index = u32(gather_by[index]);
+
+    data_out_0[index] = data_in_0[src_index];
+
+    if array_count > 1u {
+        data_out_1[index] = data_in_1[src_index];
+    }
+
+    if array_count > 2u {
+        data_out_2[index] = data_in_2[src_index];
+    }
+}
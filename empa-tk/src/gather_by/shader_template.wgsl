@@ -1,6 +1,15 @@
+struct Range {
+    src_offset: u32,
+    dst_offset: u32,
+    len: u32,
+}
+
 @group(0) @binding(0)
 var<uniform> count: u32;
 
+@group(0) @binding(4)
+var<uniform> range: Range;
+
 @group(0) @binding(1)
 var<storage, read> gather_by: array<BY_TYPE>;
 
@@ -11,10 +20,22 @@ var<storage, read> data_in: array<VALUE_TYPE>;
 var<storage, read_write> data_out: array<VALUE_TYPE>;
 
 @compute @workgroup_size(256, 1, 1)
-fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
-    let index = global_id.x;
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let thread_index = linear_workgroup_index(workgroup_id, num_workgroups) * 256u + local_index;
+
+    // Each invocation processes a contiguous tile of the output
+    for (var i = 0u; i < VALUES_PER_THREAD; i += 1u) {
+        let index = thread_index * VALUES_PER_THREAD + i;
+        let src_index = range.src_offset + index;
+        let dst_index = range.dst_offset + index;
+
+        if index < min(count, range.len) && src_index < arrayLength(&gather_by) && dst_index < arrayLength(&data_out) {
+            let data_index = transform_index(gather_by[src_index]);
 
-    if index < count {
-        data_out[index] = data_in[gather_by[index]];
+            // Indices outside of the bound data (only possible for 64-bit indices) are skipped
+            if data_index.y == 0u {
+                data_out[dst_index] = data_in[data_index.x];
+            }
+        }
     }
 }
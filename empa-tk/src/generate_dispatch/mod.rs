@@ -1,39 +1,63 @@
 use empa::access_mode::ReadWrite;
-use empa::buffer::{Storage, Uniform};
+use empa::buffer;
+use empa::buffer::{Buffer, Storage, Uniform};
 use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
 use empa::compute_pipeline::{
     ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
 };
 use empa::device::Device;
-use empa::resource_binding::BindGroupLayout;
+use empa::resource_binding::{BindGroup, BindGroupLayout};
 use empa::shader_module::{shader_source, ShaderSource};
+use empa::type_flag::{O, X};
 
 const SHADER: ShaderSource = shader_source!("shader.wgsl");
 
-#[derive(empa::resource_binding::Resources)]
 pub struct GenerateDispatchResources<'a> {
-    #[resource(binding = 0, visibility = "COMPUTE")]
     pub group_size: Uniform<'a, u32>,
-    #[resource(binding = 1, visibility = "COMPUTE")]
     pub count: Uniform<'a, u32>,
-    #[resource(binding = 2, visibility = "COMPUTE")]
     pub dispatch: Storage<'a, DispatchWorkgroups, ReadWrite>,
+    /// An upper bound on the count, if the count may exceed the number of values the dispatched
+    /// kernel can process (e.g. the length of the data it is bound to). When specified, no
+    /// workgroups are generated for the excess count.
+    pub max_count: Option<Uniform<'a, u32>>,
 }
 
-type ResourcesLayout =
-    <GenerateDispatchResources<'static> as empa::resource_binding::Resources>::Layout;
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    group_size: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    count: Uniform<'a, u32>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    dispatch: Storage<'a, DispatchWorkgroups, ReadWrite>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    max_workgroups: Uniform<'a, u32>,
+    #[resource(binding = 4, visibility = "COMPUTE")]
+    max_count: Uniform<'a, u32>,
+}
+
+pub type GenerateDispatchResourcesLayout =
+    <Resources<'static> as empa::resource_binding::Resources>::Layout;
 
+/// Generates the workgroup counts for an indirect dispatch from a count on the device.
+///
+/// Workgroup counts that exceed the device's `max_compute_workgroups_per_dimension` limit are
+/// spilled into the `y` dimension, so kernels dispatched with the generated dispatch must obtain
+/// their workgroup index with the `linear_workgroup_index` function of `dispatch.wgsl`.
 pub struct GenerateDispatch {
     device: Device,
-    bind_group_layout: BindGroupLayout<ResourcesLayout>,
-    pipeline: ComputePipeline<(ResourcesLayout,)>,
+    bind_group_layout: BindGroupLayout<GenerateDispatchResourcesLayout>,
+    pipeline: ComputePipeline<(GenerateDispatchResourcesLayout,)>,
+    max_workgroups: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
+    unbounded_max_count: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
 }
 
 impl GenerateDispatch {
     pub async fn init(device: Device) -> Self {
         let shader = device.create_shader_module(&SHADER);
 
-        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let bind_group_layout =
+            device.create_bind_group_layout::<GenerateDispatchResourcesLayout>();
         let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
 
         let pipeline = device
@@ -45,10 +69,18 @@ impl GenerateDispatch {
             )
             .await;
 
+        let max_workgroups = device.create_buffer(
+            device.limits().max_compute_workgroups_per_dimension,
+            buffer::Usages::uniform_binding(),
+        );
+        let unbounded_max_count = device.create_buffer(u32::MAX, buffer::Usages::uniform_binding());
+
         GenerateDispatch {
             device,
             bind_group_layout,
             pipeline,
+            max_workgroups,
+            unbounded_max_count,
         }
     }
 
@@ -57,14 +89,74 @@ impl GenerateDispatch {
         encoder: CommandEncoder,
         resources: GenerateDispatchResources,
     ) -> CommandEncoder {
-        let bind_group = self
-            .device
-            .create_bind_group(&self.bind_group_layout, resources);
+        self.prepare(resources).encode(encoder)
+    }
 
+    /// Prepares the dispatch generation for the `resources` as a [GenerateDispatchPlan], which
+    /// can be encoded repeatedly without recreating its bindings.
+    pub fn prepare(&self, resources: GenerateDispatchResources) -> GenerateDispatchPlan {
+        let GenerateDispatchResources {
+            group_size,
+            count,
+            dispatch,
+            max_count,
+        } = resources;
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                group_size,
+                count,
+                dispatch,
+                max_workgroups: self.max_workgroups.uniform(),
+                max_count: max_count.unwrap_or_else(|| self.unbounded_max_count.uniform()),
+            },
+        );
+
+        GenerateDispatchPlan {
+            pipeline: self.pipeline.clone(),
+            bind_group,
+        }
+    }
+
+    /// Prepares an indirect [PlanDispatch] for the `count`, which generates its workgroup counts
+    /// into a dispatch buffer owned by the plan.
+    pub fn prepare_indirect(&self, group_size: Uniform<u32>, count: Uniform<u32>) -> PlanDispatch {
+        let dispatch = self.device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+        let generate_dispatch = self.prepare(GenerateDispatchResources {
+            group_size,
+            count,
+            dispatch: dispatch.storage(),
+            max_count: None,
+        });
+
+        PlanDispatch::Indirect {
+            generate_dispatch,
+            dispatch,
+        }
+    }
+}
+
+/// A [GenerateDispatch] with its bindings prepared for particular resources; see
+/// [GenerateDispatch::prepare].
+pub struct GenerateDispatchPlan {
+    pipeline: ComputePipeline<(GenerateDispatchResourcesLayout,)>,
+    bind_group: BindGroup<GenerateDispatchResourcesLayout>,
+}
+
+impl GenerateDispatchPlan {
+    pub fn encode(&self, encoder: CommandEncoder) -> CommandEncoder {
         encoder
             .begin_compute_pass()
             .set_pipeline(&self.pipeline)
-            .set_bind_groups(&bind_group)
+            .set_bind_groups(&self.bind_group)
             .dispatch_workgroups(DispatchWorkgroups {
                 count_x: 1,
                 count_y: 1,
@@ -73,3 +165,14 @@ impl GenerateDispatch {
             .end()
     }
 }
+
+/// The dispatch of a prepared operation.
+pub enum PlanDispatch {
+    /// A fixed number of workgroups.
+    Direct(u32),
+    /// Workgroups generated on the device from a count.
+    Indirect {
+        generate_dispatch: GenerateDispatchPlan,
+        dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+    },
+}
@@ -1,3 +1,5 @@
+#include "../dispatch.wgsl"
+
 struct DispatchWorkgroups {
     x: u32,
     y: u32,
@@ -13,13 +15,20 @@ var<uniform> count: u32;
 @group(0) @binding(2)
 var<storage, read_write> dispatch: DispatchWorkgroups;
 
-fn div_ceil(a: u32, b: u32) -> u32 {
-    return (a + b - 1) / b;
-}
+@group(0) @binding(3)
+var<uniform> max_workgroups: u32;
+
+@group(0) @binding(4)
+var<uniform> max_count: u32;
 
 @compute @workgroup_size(1, 1, 1)
 fn main() {
-    let workgroups = div_ceil(count, group_size);
+    let workgroups = div_ceil(min(count, max_count), group_size);
+
+    // Spill into the `y` dimension (see `dispatch.wgsl`). Balancing `x` against `y` keeps the number of excess
+    // workgroups (which find no data to process) below `y`.
+    let y = max(div_ceil(workgroups, max_workgroups), 1u);
+    let x = div_ceil(workgroups, y);
 
-    dispatch = DispatchWorkgroups(workgroups, 1, 1);
+    dispatch = DispatchWorkgroups(x, y, 1);
 }
@@ -0,0 +1,289 @@
+use empa::buffer::{Buffer, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups};
+use empa::device::Device;
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count::Count;
+use crate::count_buffer::CountBuffer;
+use crate::find_runs::{FindRuns, FindRunsInput, FindRunsOutput};
+use crate::generate_dispatch::{GenerateDispatch, GenerateDispatchResources};
+use crate::group_by::resolve_run_lengths::{ResolveRunLengths, ResolveRunLengthsResources};
+use crate::radix_key::RadixKey;
+use crate::radix_sort::{RadixSortBy, RadixSortByInput};
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::is_empty_input;
+use crate::write_value_type::ValueTypeError;
+
+mod resolve_run_lengths;
+
+const GROUP_SIZE: u32 = 256;
+
+pub struct GroupByInput<'a, K, V, U0, U1> {
+    /// The keys to group by; sorted in place.
+    pub keys: buffer::View<'a, [K], U0>,
+    /// The values to group; sorted in place into the order of the keys. Must hold at least as
+    /// many elements as `keys`.
+    pub values: buffer::View<'a, [V], U1>,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+pub struct GroupByOutput<'a, U0, U1, U2> {
+    /// Receives the number of groups; `0` if the input is empty.
+    pub group_count: buffer::View<'a, u32, U0>,
+    /// Receives the index at which each group starts in the sorted keys and values; must hold at
+    /// least as many elements as the input keys (every key may start a new group).
+    pub group_starts: buffer::View<'a, [u32], U1>,
+    /// Receives the number of values in each group; must hold at least as many elements as the
+    /// input keys.
+    pub group_lengths: buffer::View<'a, [u32], U2>,
+}
+
+/// Groups values by key: sorts the values by key and then finds the runs of equal keys.
+///
+/// This chains a [RadixSortBy] and a [FindRuns]; the intermediate buffers these require are
+/// managed internally.
+pub struct GroupBy<K, V>
+where
+    K: abi::Sized,
+    V: abi::Sized,
+{
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    radix_sort_by: RadixSortBy<K, V>,
+    find_runs: FindRuns<K>,
+    resolve_run_lengths: ResolveRunLengths,
+    generate_dispatch: GenerateDispatch,
+    group_size: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
+    dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+    temporary_keys: Buffer<[K], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    temporary_values: Buffer<[V], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    run_mapping: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+}
+
+impl<K, V> GroupBy<K, V>
+where
+    K: RadixKey,
+    V: abi::Sized + 'static,
+{
+    pub fn requirements() -> Requirements {
+        RadixSortBy::<K, V>::requirements()
+            .max(FindRuns::<K>::requirements())
+            .max(Requirements::new(GROUP_SIZE, 0, 4))
+    }
+
+    pub async fn init(device: Device) -> Result<Self, ValueTypeError> {
+        let (radix_sort_by, find_runs, resolve_run_lengths, generate_dispatch) = join!(
+            RadixSortBy::init(device.clone()),
+            FindRuns::init(device.clone()),
+            ResolveRunLengths::init(device.clone()),
+            GenerateDispatch::init(device.clone()),
+        );
+
+        let radix_sort_by = radix_sort_by?;
+
+        let group_size = device.create_buffer(GROUP_SIZE, buffer::Usages::uniform_binding());
+        let dispatch = device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+
+        let temporary_keys =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+        let temporary_values =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+        let run_mapping =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding().and_copy_dst());
+
+        let fallback_count = UniformCache::new(&device, 0);
+
+        Ok(GroupBy {
+            device,
+            fallback_count,
+            radix_sort_by,
+            find_runs,
+            resolve_run_lengths,
+            generate_dispatch,
+            group_size,
+            dispatch,
+            temporary_keys,
+            temporary_values,
+            run_mapping,
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init(device))
+    }
+
+    /// Encodes the sort of the `input.keys` and `input.values` by key, and the search for the
+    /// groups of equal keys in the sorted keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input.values`, `output.group_starts` or `output.group_lengths` hold fewer
+    /// elements than `input.keys`.
+    pub fn encode<U0, U1, U2, U3, U4>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: GroupByInput<K, V, U0, U1>,
+        output: GroupByOutput<U2, U3, U4>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+        U3: buffer::StorageBinding,
+        U4: buffer::StorageBinding,
+    {
+        let GroupByInput {
+            keys,
+            values,
+            count,
+        } = input;
+
+        let GroupByOutput {
+            group_count,
+            group_starts,
+            group_lengths,
+        } = output;
+
+        assert!(
+            values.len() >= keys.len(),
+            "`values` must hold at least as many elements as `keys`"
+        );
+        assert!(
+            group_lengths.len() >= keys.len(),
+            "`group_lengths` must hold at least as many elements as `keys`"
+        );
+
+        let len = keys.len();
+
+        if self.temporary_keys.len() < len {
+            self.temporary_keys = self
+                .device
+                .create_slice_buffer_zeroed(len, self.temporary_keys.usage());
+            self.temporary_values = self
+                .device
+                .create_slice_buffer_zeroed(len, self.temporary_values.usage());
+        }
+
+        // The run mapping must match the length of the keys exactly, as it also bounds the
+        // resolution of the group lengths
+        if len > 0 && self.run_mapping.len() != len {
+            self.run_mapping = self
+                .device
+                .create_slice_buffer_zeroed(len, self.run_mapping.usage());
+        }
+
+        let dispatch_indirect = count.is_some();
+
+        encoder = self.radix_sort_by.encode(
+            encoder,
+            RadixSortByInput {
+                keys,
+                values,
+                temporary_key_storage: self.temporary_keys.view(),
+                temporary_value_storage: self.temporary_values.view(),
+                count: count.clone().map(Count::Indirect),
+            },
+        );
+
+        // For an empty input, the run search still resolves the group count to `0`
+        encoder = self.find_runs.encode(
+            encoder,
+            FindRunsInput {
+                data: keys,
+                count: count.clone(),
+            },
+            FindRunsOutput {
+                run_count: group_count,
+                run_starts: group_starts,
+                run_mapping: self.run_mapping.view(),
+            },
+        );
+
+        if is_empty_input(len) {
+            return encoder;
+        }
+
+        self.fallback_count.update(&self.device, len as u32);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        if dispatch_indirect {
+            encoder = self.generate_dispatch.encode(
+                encoder,
+                GenerateDispatchResources {
+                    group_size: self.group_size.uniform(),
+                    count: count.uniform(),
+                    dispatch: self.dispatch.storage(),
+                    max_count: None,
+                },
+            );
+        }
+
+        self.resolve_run_lengths.encode(
+            encoder,
+            ResolveRunLengthsResources {
+                count: count.uniform(),
+                run_count: group_count.storage(),
+                run_starts: group_starts.storage(),
+                run_mapping: self.run_mapping.storage(),
+                run_lengths: group_lengths.storage(),
+            },
+            dispatch_indirect,
+            self.dispatch.view(),
+            len as u32,
+        )
+    }
+}
+
+impl<V> GroupBy<u32, V>
+where
+    V: abi::Sized + 'static,
+{
+    pub async fn init_u32(device: Device) -> Result<Self, ValueTypeError> {
+        Self::init(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_u32(device))
+    }
+}
+
+impl<V> GroupBy<i32, V>
+where
+    V: abi::Sized + 'static,
+{
+    pub async fn init_i32(device: Device) -> Result<Self, ValueTypeError> {
+        Self::init(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_i32(device))
+    }
+}
+
+impl<V> GroupBy<f32, V>
+where
+    V: abi::Sized + 'static,
+{
+    pub async fn init_f32(device: Device) -> Result<Self, ValueTypeError> {
+        Self::init(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_f32_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_f32(device))
+    }
+}
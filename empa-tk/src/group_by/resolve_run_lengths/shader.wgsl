@@ -0,0 +1,35 @@
+#include "../../dispatch.wgsl"
+
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<storage, read> run_count: u32;
+
+@group(0) @binding(2)
+var<storage, read> run_starts: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read> run_mapping: array<u32>;
+
+@group(0) @binding(4)
+var<storage, read_write> run_lengths: array<u32>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let index = linear_workgroup_index(workgroup_id, num_workgroups) * 256u + local_index;
+    let value_count = min(count, arrayLength(&run_mapping));
+
+    if index >= min(run_count, arrayLength(&run_lengths)) {
+        return;
+    }
+
+    // The last run ends at the end of the data, all other runs end where the next run starts
+    var end = value_count;
+
+    if index + 1 < run_count {
+        end = run_starts[index + 1];
+    }
+
+    run_lengths[index] = end - run_starts[index];
+}
@@ -0,0 +1,281 @@
+use std::fmt::Write;
+
+use bytemuck::Zeroable;
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::ShaderSource;
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::generate_dispatch::{GenerateDispatch, GenerateDispatchResources};
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+
+const SHADER_TEMPLATE: &str = concat!(
+    include_str!("../dispatch.wgsl"),
+    include_str!("shader_template.wgsl")
+);
+
+const GROUP_SIZE: u32 = 256;
+const VALUES_PER_THREAD: u32 = 8;
+
+const SEGMENT_SIZE: u32 = GROUP_SIZE * VALUES_PER_THREAD;
+
+/// The maximum number of bins a [Histogram] supports.
+///
+/// Each workgroup keeps a private copy of the histogram in workgroup memory, which is limited to
+/// 16KiB by the default device limits.
+pub const MAX_BIN_COUNT: u32 = 4096;
+
+/// The value range that is mapped onto the bins of a [Histogram].
+///
+/// The range is inclusive: both `min` and `max` are mapped into a bin. The range is divided into
+/// `bin_count` bins of equal width (rounded up), so if the size of the range is not a multiple
+/// of the bin count, the last bins may cover fewer values (or none at all).
+///
+/// Values below `min` are clamped into the first bin; values above `max` are clamped into the
+/// last bin.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HistogramRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug, Zeroable)]
+#[repr(C)]
+struct RangeUniform {
+    min: u32,
+    max: u32,
+    bin_width: u32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    range: Uniform<'a, RangeUniform>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    data: Storage<'a, [u32]>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    bins: Storage<'a, [u32], ReadWrite>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+pub struct HistogramInput<'a, U> {
+    pub data: buffer::View<'a, [u32], U>,
+    pub range: HistogramRange,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// Counts the number of values in a `[u32]` buffer that fall into each of a configurable number
+/// of equal-width bins.
+pub struct Histogram {
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    range: UniformCache<RangeUniform>,
+    bin_count: u32,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: ComputePipeline<(ResourcesLayout,)>,
+    generate_dispatch: GenerateDispatch,
+    group_size: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
+    dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+}
+
+impl Histogram {
+    /// The device limits a histogram with the given `bin_count` requires; the bins are privatized
+    /// in workgroup memory.
+    pub fn requirements(bin_count: u32) -> Requirements {
+        Requirements::new(GROUP_SIZE, bin_count * 4, 2)
+    }
+
+    /// Initializes a new histogram operator with the given `bin_count`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bin_count` is `0` or greater than [MAX_BIN_COUNT].
+    pub async fn init(device: Device, bin_count: u32) -> Self {
+        assert!(
+            bin_count > 0 && bin_count <= MAX_BIN_COUNT,
+            "`bin_count` must be in the range `1..={}` (got {})",
+            MAX_BIN_COUNT,
+            bin_count
+        );
+
+        let mut code = String::new();
+
+        write!(
+            code,
+            "const BIN_COUNT = {}u;\n\n{}",
+            bin_count, SHADER_TEMPLATE
+        )
+        .unwrap();
+
+        let shader_source = ShaderSource::unparsed(code);
+        let shader = device.create_shader_module(&shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let create_pipeline = unsafe {
+            device.create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute_unchecked(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+        };
+        let init_generate_dispatch = GenerateDispatch::init(device.clone());
+
+        let (pipeline, generate_dispatch) = join!(create_pipeline, init_generate_dispatch);
+
+        let group_size = device.create_buffer(SEGMENT_SIZE, buffer::Usages::uniform_binding());
+        let dispatch = device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+
+        let fallback_count = UniformCache::new(&device, 0);
+        let range = UniformCache::new(
+            &device,
+            RangeUniform {
+                min: 0,
+                max: 0,
+                bin_width: 0,
+            },
+        );
+
+        Histogram {
+            device,
+            fallback_count,
+            range,
+            bin_count,
+            bind_group_layout,
+            pipeline,
+            generate_dispatch,
+            group_size,
+            dispatch,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device, bin_count: u32) -> Self {
+        pollster::block_on(Self::init(device, bin_count))
+    }
+
+    /// The number of bins this histogram operator counts into.
+    pub fn bin_count(&self) -> u32 {
+        self.bin_count
+    }
+
+    /// Encodes the histogram computation.
+    ///
+    /// The `output_bins` are cleared before counting.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `output_bins` holds fewer than [bin_count](Self::bin_count) elements.
+    pub fn encode<U0, U1>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: HistogramInput<U0>,
+        output_bins: buffer::View<[u32], U1>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding + buffer::CopyDst + 'static,
+    {
+        let HistogramInput { data, range, count } = input;
+
+        assert!(
+            output_bins.len() >= self.bin_count as usize,
+            "`output_bins` must hold at least as many elements as the number of bins ({})",
+            self.bin_count
+        );
+        assert!(
+            range.min <= range.max,
+            "the histogram range's `min` must not be greater than its `max`"
+        );
+
+        // No values to count, so every bin is empty
+        if is_empty_input(data.len()) {
+            return encoder.clear_buffer_slice(output_bins);
+        }
+
+        let dispatch_indirect = count.is_some();
+
+        self.fallback_count.update(&self.device, data.len() as u32);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        // The range spans at most 2^32 values, so rounding up cannot overflow a u64
+        let range_size = range.max as u64 - range.min as u64 + 1;
+        let bin_count = self.bin_count as u64;
+        let bin_width = ((range_size + bin_count - 1) / bin_count) as u32;
+
+        self.range.update(
+            &self.device,
+            RangeUniform {
+                min: range.min,
+                max: range.max,
+                bin_width,
+            },
+        );
+
+        if dispatch_indirect {
+            encoder = self.generate_dispatch.encode(
+                encoder,
+                GenerateDispatchResources {
+                    group_size: self.group_size.uniform(),
+                    count: count.uniform(),
+                    dispatch: self.dispatch.storage(),
+                    max_count: None,
+                },
+            );
+        }
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                count: count.uniform(),
+                range: self.range.uniform(),
+                data: data.storage(),
+                bins: output_bins.storage(),
+            },
+        );
+
+        let encoder = encoder
+            .clear_buffer_slice(output_bins)
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group);
+
+        if dispatch_indirect {
+            encoder
+                .dispatch_workgroups_indirect(self.dispatch.view())
+                .end()
+        } else {
+            encoder
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: div_ceil(data.len() as u32, SEGMENT_SIZE),
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end()
+        }
+    }
+}
@@ -0,0 +1,56 @@
+const GROUP_SIZE = 256u;
+const VALUES_PER_THREAD = 8u;
+const SEGMENT_SIZE = 2048u; // GROUP_SIZE * VALUES_PER_THREAD;
+
+struct Range {
+    min: u32,
+    max: u32,
+    bin_width: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<uniform> range: Range;
+
+@group(0) @binding(2)
+var<storage, read> data: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read_write> bins: array<atomic<u32>>;
+
+var<workgroup> local_bins: array<atomic<u32>, BIN_COUNT>;
+
+fn bin_index(value: u32) -> u32 {
+    // Values outside of the range are clamped into the first and last bins respectively.
+    let clamped = clamp(value, range.min, range.max);
+
+    return min((clamped - range.min) / range.bin_width, BIN_COUNT - 1);
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let segment_offset = linear_workgroup_index(workgroup_id, num_workgroups) * SEGMENT_SIZE;
+    let data_count = min(count, arrayLength(&data));
+
+    for (var i = local_index; i < SEGMENT_SIZE; i += GROUP_SIZE) {
+        let data_index = segment_offset + i;
+
+        if data_index < data_count {
+            atomicAdd(&local_bins[bin_index(data[data_index])], 1u);
+        }
+    }
+
+    workgroupBarrier();
+
+    // Merge the privatized workgroup histogram into the global histogram; skip empty bins to avoid needless global
+    // atomic traffic.
+    for (var i = local_index; i < BIN_COUNT; i += GROUP_SIZE) {
+        let local_bin_count = atomicLoad(&local_bins[i]);
+
+        if local_bin_count > 0 {
+            atomicAdd(&bins[i], local_bin_count);
+        }
+    }
+}
@@ -0,0 +1,13 @@
+struct IndexTransform {
+    stride: u32,
+    base: u32,
+}
+
+@group(0) @binding(5)
+var<uniform> index_transform: IndexTransform;
+
+// Returns the transformed index as a (lo, hi) pair, where a non-zero high word marks an index that lies outside of the
+// bound data. A 32-bit index always lies within the bound data.
+fn transform_index(index: BY_TYPE) -> vec2<u32> {
+    return vec2(u32(index) * index_transform.stride + index_transform.base, 0u);
+}
@@ -0,0 +1,47 @@
+struct IndexTransform {
+    stride: u32,
+    base: u32,
+}
+
+struct IndexWindow {
+    offset_lo: u32,
+    offset_hi: u32,
+}
+
+@group(0) @binding(5)
+var<uniform> index_transform: IndexTransform;
+
+@group(0) @binding(6)
+var<uniform> index_window: IndexWindow;
+
+// The full 64-bit product of two 32-bit values as a (lo, hi) pair, computed from their 16-bit halves.
+fn mul_wide(a: u32, b: u32) -> vec2<u32> {
+    let a_lo = a & 0xFFFFu;
+    let a_hi = a >> 16u;
+    let b_lo = b & 0xFFFFu;
+    let b_hi = b >> 16u;
+
+    let lo_lo = a_lo * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_lo = a_hi * b_lo;
+    let hi_hi = a_hi * b_hi;
+
+    let mid = (lo_lo >> 16u) + (lo_hi & 0xFFFFu) + (hi_lo & 0xFFFFu);
+
+    return vec2((lo_lo & 0xFFFFu) | (mid << 16u), hi_hi + (lo_hi >> 16u) + (hi_lo >> 16u) + (mid >> 16u));
+}
+
+// Returns the transformed index relative to the start of the bound data as a (lo, hi) pair, where a non-zero high
+// word marks an index that lies outside of the bound data. All arithmetic wraps at 64 bits.
+fn transform_index(index: BY_TYPE) -> vec2<u32> {
+    let product = mul_wide(index[0], index_transform.stride);
+    let hi = product.y + index[1] * index_transform.stride;
+
+    let sum = product.x + index_transform.base;
+    let sum_hi = hi + select(0u, 1u, sum < product.x);
+
+    let difference = sum - index_window.offset_lo;
+    let difference_hi = sum_hi - index_window.offset_hi - select(0u, 1u, sum < index_window.offset_lo);
+
+    return vec2(difference, difference_hi);
+}
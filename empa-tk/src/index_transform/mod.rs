@@ -0,0 +1,47 @@
+use bytemuck::Zeroable;
+use empa::abi;
+
+/// Defines `transform_index` for 32-bit (`u32` or `i32`) indices.
+pub(crate) const INDEX_32: &str = include_str!("index_32.wgsl");
+
+/// Defines `transform_index` for 64-bit indices, represented as `[u32; 2]` (lo, hi) pairs.
+pub(crate) const INDEX_64: &str = include_str!("index_64.wgsl");
+
+/// An affine transform applied to the indices of a [GatherBy](crate::gather_by::GatherBy) or
+/// [ScatterBy](crate::scatter_by::ScatterBy) before they are used to address the data.
+///
+/// An index `i` is mapped to `i * stride + base` (with wrapping arithmetic, at 64 bits for 64-bit
+/// indices). The default transform
+/// (`stride = 1`, `base = 0`) leaves the indices unchanged.
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug, Zeroable)]
+#[repr(C)]
+pub struct IndexTransform {
+    pub stride: u32,
+    pub base: u32,
+}
+
+impl Default for IndexTransform {
+    fn default() -> Self {
+        IndexTransform { stride: 1, base: 0 }
+    }
+}
+
+/// The position of the bound data within a larger buffer, for operations with 64-bit indices.
+///
+/// 64-bit indices address the larger buffer; an index is made relative to the bound data by
+/// subtracting the `offset` of the data's first element.
+#[derive(abi::Sized, Clone, Copy, PartialEq, Default, Debug, Zeroable)]
+#[repr(C)]
+pub(crate) struct IndexWindow {
+    offset_lo: u32,
+    offset_hi: u32,
+}
+
+impl IndexWindow {
+    pub(crate) fn new(offset: u64) -> Self {
+        IndexWindow {
+            offset_lo: offset as u32,
+            offset_hi: (offset >> 32) as u32,
+        }
+    }
+}
@@ -1,11 +1,62 @@
-#![feature(future_join, int_roundings)]
-
+pub mod bitonic;
+#[cfg(feature = "value-types")]
+pub mod bucket;
+pub mod counting_sort;
+#[cfg(feature = "cpu")]
+pub mod cpu;
 pub mod find_runs;
+#[cfg(feature = "value-types")]
 pub mod gather_by;
+#[cfg(feature = "value-types")]
+pub mod group_by;
+pub mod histogram;
+pub mod merge;
+pub mod nth_element;
 pub mod prefix_sum;
+pub mod prelude;
 pub mod radix_sort;
+#[cfg(feature = "value-types")]
+pub mod reduce_by_key;
+#[cfg(feature = "value-types")]
+pub mod run_length_decode;
+#[cfg(feature = "value-types")]
 pub mod scatter_by;
+pub mod search;
+pub mod segmented_reduce;
+pub mod set_ops;
+pub mod stream_compaction;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod top_k;
 
+mod aliasing;
+mod cost_estimate;
+mod count;
 mod count_buffer;
 mod generate_dispatch;
+#[cfg(feature = "value-types")]
+mod index_transform;
+mod profiler;
+mod radix_key;
+mod readback;
+mod requirements;
+mod scratch_pool;
+mod uniform_cache;
+mod util;
+mod validate;
+#[cfg(feature = "value-types")]
 mod write_value_type;
+
+pub use self::cost_estimate::CostEstimate;
+pub use self::count::Count;
+#[cfg(feature = "value-types")]
+pub use self::index_transform::IndexTransform;
+pub use self::profiler::Profiler;
+pub use self::radix_key::RadixKey;
+pub use self::readback::{read_back, read_back_vec};
+pub use self::requirements::Requirements;
+pub use self::scratch_pool::ScratchPool;
+#[cfg(feature = "value-types")]
+pub use self::write_value_type::{
+    check_value_type, value_type_declaration, value_type_words, ValueTypeError, MAX_VALUE_TYPE_SIZE,
+};
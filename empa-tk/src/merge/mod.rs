@@ -0,0 +1,175 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer::Storage;
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::{abi, buffer};
+
+use crate::requirements::Requirements;
+use crate::util::{div_ceil, is_empty_input};
+
+const SHADER_U32: ShaderSource = shader_source!("shader_u32.wgsl");
+const SHADER_I32: ShaderSource = shader_source!("shader_i32.wgsl");
+const SHADER_F32: ShaderSource = shader_source!("shader_f32.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+const VALUES_PER_THREAD: u32 = 8;
+
+const SEGMENT_SIZE: u32 = GROUP_SIZE * VALUES_PER_THREAD;
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a, T>
+where
+    T: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    a: Storage<'a, [T]>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    b: Storage<'a, [T]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    output: Storage<'a, [T], ReadWrite>,
+}
+
+type ResourcesLayout<T> = <Resources<'static, T> as empa::resource_binding::Resources>::Layout;
+
+pub struct MergeInput<'a, T, U0, U1> {
+    pub a: buffer::View<'a, [T], U0>,
+    pub b: buffer::View<'a, [T], U1>,
+}
+
+/// Merges two sorted buffers into a single sorted output buffer.
+///
+/// The merge is stable: when an element in `a` and an element in `b` compare equal, the element
+/// from `a` is output first.
+///
+/// The output is partitioned along the "merge path", such that every workgroup merges an
+/// equally sized part of the output independently, regardless of how the lengths of `a` and `b`
+/// relate.
+pub struct Merge<T>
+where
+    T: abi::Sized,
+{
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
+    pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+}
+
+impl<T> Merge<T>
+where
+    T: abi::Sized + 'static,
+{
+    pub fn requirements() -> Requirements {
+        // The segment is stored in workgroup memory, alongside the bounds of the segment's range
+        // in the first input
+        Requirements::new(GROUP_SIZE, (SEGMENT_SIZE + 2) * 4, 3)
+    }
+
+    async fn init_internal(device: Device, shader_source: &ShaderSource) -> Self {
+        let shader = device.create_shader_module(shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        Merge {
+            device,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    /// Encodes the merge of `input.a` and `input.b` into `output`.
+    ///
+    /// The `output` must hold at least `a.len() + b.len()` elements and must not alias either
+    /// of the inputs. If both inputs are empty, nothing is encoded; an empty view cannot be bound,
+    /// so a single empty input is not supported.
+    pub fn encode<U0, U1, U2>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: MergeInput<T, U0, U1>,
+        output: buffer::View<[T], U2>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        let MergeInput { a, b } = input;
+
+        let total = (a.len() + b.len()) as u32;
+
+        assert!(
+            output.len() >= total as usize,
+            "the output must be able to hold all elements from `a` and `b`"
+        );
+
+        if is_empty_input(total as usize) {
+            return encoder;
+        }
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                a: a.storage(),
+                b: b.storage(),
+                output: output.storage(),
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: div_ceil(total, SEGMENT_SIZE),
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
+
+impl Merge<u32> {
+    pub async fn init_u32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_U32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_u32(device))
+    }
+}
+
+impl Merge<i32> {
+    pub async fn init_i32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_I32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_i32(device))
+    }
+}
+
+impl Merge<f32> {
+    pub async fn init_f32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_F32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_f32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_f32(device))
+    }
+}
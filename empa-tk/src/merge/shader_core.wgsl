@@ -0,0 +1,113 @@
+const GROUP_SIZE = 256u;
+const VALUES_PER_THREAD = 8u;
+const SEGMENT_SIZE = 2048u; // GROUP_SIZE * VALUES_PER_THREAD;
+
+@group(0) @binding(0)
+var<storage, read> a: array<DATA_TYPE>;
+
+@group(0) @binding(1)
+var<storage, read> b: array<DATA_TYPE>;
+
+@group(0) @binding(2)
+var<storage, read_write> output: array<DATA_TYPE>;
+
+// Holds the segment's part of `a` followed by the segment's part of `b`.
+var<workgroup> local_data: array<DATA_TYPE, SEGMENT_SIZE>;
+
+var<workgroup> a_start: u32;
+var<workgroup> a_end: u32;
+
+// Finds the number of elements from `a` that precede the given `diagonal` on the merge path. Ties resolve in favor of
+// `a`: an element from `a` is merged before an equal element from `b`.
+fn merge_path_global(diagonal: u32) -> u32 {
+    let a_len = arrayLength(&a);
+    let b_len = arrayLength(&b);
+
+    var lo = select(0u, diagonal - b_len, diagonal > b_len);
+    var hi = min(diagonal, a_len);
+
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+
+        if a[mid] <= b[diagonal - mid - 1] {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    return lo;
+}
+
+// Same as `merge_path_global`, but searches the segment-local copies in `local_data`.
+fn merge_path_local(diagonal: u32, local_a_len: u32, local_b_len: u32) -> u32 {
+    var lo = select(0u, diagonal - local_b_len, diagonal > local_b_len);
+    var hi = min(diagonal, local_a_len);
+
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+
+        if local_data[mid] <= local_data[local_a_len + diagonal - mid - 1] {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    return lo;
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let total = arrayLength(&a) + arrayLength(&b);
+    let diagonal_start = workgroup_id.x * SEGMENT_SIZE;
+    let diagonal_end = min(diagonal_start + SEGMENT_SIZE, total);
+
+    if local_index == 0 {
+        a_start = merge_path_global(diagonal_start);
+        a_end = merge_path_global(diagonal_end);
+    }
+
+    workgroupBarrier();
+
+    let local_a_start = a_start;
+    let local_a_len = a_end - local_a_start;
+    let local_b_start = diagonal_start - local_a_start;
+    let local_b_len = (diagonal_end - diagonal_start) - local_a_len;
+
+    for (var i = local_index; i < local_a_len + local_b_len; i += GROUP_SIZE) {
+        if i < local_a_len {
+            local_data[i] = a[local_a_start + i];
+        } else {
+            local_data[i] = b[local_b_start + i - local_a_len];
+        }
+    }
+
+    workgroupBarrier();
+
+    let segment_len = diagonal_end - diagonal_start;
+    let thread_diagonal = min(local_index * VALUES_PER_THREAD, segment_len);
+
+    var i = merge_path_local(thread_diagonal, local_a_len, local_b_len);
+    var j = thread_diagonal - i;
+
+    for (var k = 0u; k < VALUES_PER_THREAD; k += 1u) {
+        let local_output_index = thread_diagonal + k;
+
+        if local_output_index >= segment_len {
+            break;
+        }
+
+        var value: DATA_TYPE;
+
+        if j >= local_b_len || (i < local_a_len && local_data[i] <= local_data[local_a_len + j]) {
+            value = local_data[i];
+            i += 1u;
+        } else {
+            value = local_data[local_a_len + j];
+            j += 1u;
+        }
+
+        output[diagonal_start + local_output_index] = value;
+    }
+}
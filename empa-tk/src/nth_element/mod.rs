@@ -0,0 +1,285 @@
+use bytemuck::Zeroable;
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+
+const SHADER_U32: ShaderSource = shader_source!("shader_u32.wgsl");
+const SHADER_I32: ShaderSource = shader_source!("shader_i32.wgsl");
+const SHADER_F32: ShaderSource = shader_source!("shader_f32.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+const VALUES_PER_THREAD: u32 = 8;
+
+const SEGMENT_SIZE: u32 = GROUP_SIZE * VALUES_PER_THREAD;
+
+const RADIX_SIZE: u32 = 8;
+const RADIX_DIGITS: usize = 256;
+const RADIX_GROUPS: usize = 4;
+
+#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+#[repr(C)]
+struct State {
+    prefix: u32,
+    prefix_mask: u32,
+    remaining: u32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a, T>
+where
+    T: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    max_count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    k: Uniform<'a, u32>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    radix_offset: Uniform<'a, u32>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    data: Storage<'a, [T]>,
+    #[resource(binding = 4, visibility = "COMPUTE")]
+    state: Storage<'a, State, ReadWrite>,
+    #[resource(binding = 5, visibility = "COMPUTE")]
+    histogram: Storage<'a, [u32; RADIX_DIGITS], ReadWrite>,
+    #[resource(binding = 6, visibility = "COMPUTE")]
+    output: Storage<'a, T, ReadWrite>,
+}
+
+type ResourcesLayout<T> = <Resources<'static, T> as empa::resource_binding::Resources>::Layout;
+
+pub struct NthElementInput<'a, T, U> {
+    pub data: buffer::View<'a, [T], U>,
+    /// The (zero-based) rank of the value to select; e.g. `count / 2` selects the median.
+    pub k: u32,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// Selects the value that would be at index `k` if the buffer were sorted in ascending order,
+/// without sorting the buffer.
+///
+/// Uses the same radix-select as [TopK](crate::top_k::TopK): for each 8-bit digit, starting at
+/// the most significant digit, the values that match the digits resolved so far are
+/// histogrammed, and a scan over the histogram finds the digit bucket that holds the value of
+/// rank `k`. After all digits are resolved the selected value is known exactly; values that
+/// compare equal always share a bucket, so duplicates at rank `k` are handled correctly.
+///
+/// If `k` is not smaller than the count, then the largest value is selected. If the count is
+/// `0`, the output is left untouched.
+pub struct NthElement<T>
+where
+    T: abi::Sized,
+{
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    k: UniformCache<u32>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
+    digit_histogram_pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+    select_digit_pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+    resolve_pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+    state: Buffer<State, buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    histogram: Buffer<[u32; RADIX_DIGITS], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    radix_offsets: [Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>; RADIX_GROUPS],
+}
+
+impl<T> NthElement<T>
+where
+    T: abi::Sized + 'static,
+{
+    pub fn requirements() -> Requirements {
+        Requirements::new(GROUP_SIZE, RADIX_DIGITS as u32 * 4, 4)
+    }
+
+    async fn init_internal(device: Device, shader_source: &ShaderSource) -> Self {
+        let shader = device.create_shader_module(shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let create_digit_histogram_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&shader, "digit_histogram").finish())
+                .finish(),
+        );
+        let create_select_digit_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&shader, "select_digit").finish())
+                .finish(),
+        );
+        let create_resolve_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&shader, "resolve").finish())
+                .finish(),
+        );
+
+        let (digit_histogram_pipeline, select_digit_pipeline, resolve_pipeline) = join!(
+            create_digit_histogram_pipeline,
+            create_select_digit_pipeline,
+            create_resolve_pipeline
+        );
+
+        let state = device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_dst());
+        let histogram =
+            device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_dst());
+
+        // Radix-select resolves the most significant digit first
+        let radix_offsets = [3, 2, 1, 0].map(|radix_group| {
+            device.create_buffer(radix_group * RADIX_SIZE, buffer::Usages::uniform_binding())
+        });
+
+        let fallback_count = UniformCache::new(&device, 0);
+        let k = UniformCache::new(&device, 0);
+
+        NthElement {
+            device,
+            fallback_count,
+            k,
+            bind_group_layout,
+            digit_histogram_pipeline,
+            select_digit_pipeline,
+            resolve_pipeline,
+            state,
+            histogram,
+            radix_offsets,
+        }
+    }
+
+    /// Encodes the selection of the value of rank `input.k` in `input.data` into `output_value`.
+    pub fn encode<U0, U1>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: NthElementInput<T, U0>,
+        output_value: buffer::View<T, U1>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        let NthElementInput { data, k, count } = input;
+
+        if is_empty_input(data.len()) {
+            return encoder;
+        }
+
+        let fallback_count = data.len() as u32;
+
+        self.fallback_count.update(&self.device, fallback_count);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        self.k.update(&self.device, k);
+
+        encoder = encoder
+            .clear_buffer(self.state.view())
+            .clear_buffer(self.histogram.view());
+
+        for radix_offset in &self.radix_offsets {
+            let bind_group = self.device.create_bind_group(
+                &self.bind_group_layout,
+                Resources {
+                    max_count: count.uniform(),
+                    k: self.k.uniform(),
+                    radix_offset: radix_offset.uniform(),
+                    data: data.storage(),
+                    state: self.state.storage(),
+                    histogram: self.histogram.storage(),
+                    output: output_value.storage(),
+                },
+            );
+
+            encoder = encoder
+                .begin_compute_pass()
+                .set_pipeline(&self.digit_histogram_pipeline)
+                .set_bind_groups(&bind_group)
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: div_ceil(fallback_count, SEGMENT_SIZE),
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end();
+            encoder = encoder
+                .begin_compute_pass()
+                .set_pipeline(&self.select_digit_pipeline)
+                .set_bind_groups(&bind_group)
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: 1,
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end();
+        }
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                max_count: count.uniform(),
+                k: self.k.uniform(),
+                radix_offset: self.radix_offsets[0].uniform(),
+                data: data.storage(),
+                state: self.state.storage(),
+                histogram: self.histogram.storage(),
+                output: output_value.storage(),
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.resolve_pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
+
+impl NthElement<u32> {
+    pub async fn init_u32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_U32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_u32(device))
+    }
+}
+
+impl NthElement<i32> {
+    pub async fn init_i32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_I32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_i32(device))
+    }
+}
+
+impl NthElement<f32> {
+    pub async fn init_f32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_F32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_f32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_f32(device))
+    }
+}
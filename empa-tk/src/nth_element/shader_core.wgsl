@@ -0,0 +1,124 @@
+const GROUP_SIZE = 256u;
+const VALUES_PER_THREAD = 8u;
+const SEGMENT_SIZE = 2048u; // GROUP_SIZE * VALUES_PER_THREAD;
+const RADIX_SIZE = 8u;
+const RADIX_DIGITS = 256u; // 1 << RADIX_SIZE;
+
+struct State {
+    // The key bits that have been resolved so far; the selected value shares these bits.
+    prefix: u32,
+    prefix_mask: u32,
+    // The rank of the selected value amongst the values that match the prefix.
+    remaining: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> max_count: u32;
+
+@group(0) @binding(1)
+var<uniform> k: u32;
+
+@group(0) @binding(2)
+var<uniform> radix_offset: u32;
+
+@group(0) @binding(3)
+var<storage, read> data: array<DATA_TYPE>;
+
+@group(0) @binding(4)
+var<storage, read_write> state: State;
+
+@group(0) @binding(5)
+var<storage, read_write> histogram: array<atomic<u32>, RADIX_DIGITS>;
+
+@group(0) @binding(6)
+var<storage, read_write> output: DATA_TYPE;
+
+var<workgroup> local_histogram: array<atomic<u32>, RADIX_DIGITS>;
+
+fn resolve_count() -> u32 {
+    return min(max_count, arrayLength(&data));
+}
+
+// Histograms the digit at the current `radix_offset` for all values that match the prefix resolved by prior passes.
+@compute @workgroup_size(256, 1, 1)
+fn digit_histogram(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let count = resolve_count();
+    let segment_offset = workgroup_id.x * SEGMENT_SIZE;
+    let prefix = state.prefix;
+    let prefix_mask = state.prefix_mask;
+
+    for (var i = local_index; i < SEGMENT_SIZE; i += GROUP_SIZE) {
+        let data_index = segment_offset + i;
+
+        if data_index < count {
+            let key = to_key(data[data_index]);
+
+            if (key & prefix_mask) == prefix {
+                let digit = (key >> radix_offset) & (RADIX_DIGITS - 1);
+
+                atomicAdd(&local_histogram[digit], 1u);
+            }
+        }
+    }
+
+    workgroupBarrier();
+
+    let local_digit_count = atomicLoad(&local_histogram[local_index]);
+
+    if local_digit_count > 0 {
+        atomicAdd(&histogram[local_index], local_digit_count);
+    }
+}
+
+var<workgroup> digit_counts: array<u32, RADIX_DIGITS>;
+
+// Finds the digit bucket that contains the value of rank `k`, appends the digit to the prefix, and clears the
+// histogram for the next pass. Must be dispatched with a single workgroup.
+@compute @workgroup_size(256, 1, 1)
+fn select_digit(@builtin(local_invocation_index) local_index: u32) {
+    digit_counts[local_index] = atomicLoad(&histogram[local_index]);
+
+    atomicStore(&histogram[local_index], 0u);
+
+    workgroupBarrier();
+
+    let count = resolve_count();
+
+    if local_index == 0 && count > 0 {
+        var remaining = state.remaining;
+
+        // The first pass starts from a cleared state; a rank past the end selects the largest value
+        if state.prefix_mask == 0 {
+            remaining = min(k, count - 1);
+        }
+
+        var accumulated = 0u;
+        var digit = 0u;
+
+        loop {
+            let digit_count = digit_counts[digit];
+
+            // Values that equal the selected value all fall into the same bucket, so duplicates at rank `k` need no
+            // special treatment
+            if accumulated + digit_count > remaining || digit == RADIX_DIGITS - 1 {
+                break;
+            }
+
+            accumulated += digit_count;
+            digit += 1u;
+        }
+
+        state.prefix |= digit << radix_offset;
+        state.prefix_mask |= (RADIX_DIGITS - 1) << radix_offset;
+        state.remaining = remaining - accumulated;
+    }
+}
+
+// After all digits have been resolved, the prefix is the key of the selected value. Must be dispatched with a single
+// invocation.
+@compute @workgroup_size(1, 1, 1)
+fn resolve() {
+    if resolve_count() > 0 {
+        output = from_key(state.prefix);
+    }
+}
@@ -0,0 +1,23 @@
+alias DATA_TYPE = f32;
+
+// Flip all bits for negative values (reversing their order) and only the sign bit for positive values, so that the
+// resulting unsigned integer order matches the floating point order.
+fn to_key(value: f32) -> u32 {
+    let bits = bitcast<u32>(value);
+
+    if (bits & 0x80000000u) != 0 {
+        return ~bits;
+    } else {
+        return bits | 0x80000000u;
+    }
+}
+
+fn from_key(key: u32) -> f32 {
+    if (key & 0x80000000u) != 0 {
+        return bitcast<f32>(key & 0x7FFFFFFFu);
+    } else {
+        return bitcast<f32>(~key);
+    }
+}
+
+#include "shader_core.wgsl"
@@ -1,3 +1,6 @@
 const OUTPUT_EXCLUSIVE = true;
 
-#include "shader_core.wgsl"
+const GROUP_SIZE = 256u;
+const VALUES_PER_THREAD = 8u;
+
+#include "shader_template.wgsl"
@@ -1,3 +1,4 @@
 alias DATA_TYPE = f32;
 
+#include "operator_sum.wgsl"
 #include "exclusive_shader_core.wgsl"
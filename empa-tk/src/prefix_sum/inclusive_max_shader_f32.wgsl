@@ -0,0 +1,4 @@
+alias DATA_TYPE = f32;
+
+#include "operator_max_f32.wgsl"
+#include "inclusive_shader_core.wgsl"
@@ -0,0 +1,4 @@
+alias DATA_TYPE = u32;
+
+#include "operator_saturating_sum.wgsl"
+#include "inclusive_shader_core.wgsl"
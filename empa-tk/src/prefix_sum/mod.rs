@@ -1,2 +1,11 @@
 mod prefix_sum;
-pub use prefix_sum::{PrefixSum, PrefixSumInput};
+pub(crate) use prefix_sum::Initial;
+pub use prefix_sum::{
+    PrefixSum, PrefixSumBuilder, PrefixSumInput, ScanOrder, PREFIX_SUM_SEGMENT_SIZE,
+};
+
+mod prefix_sum_stream;
+pub use prefix_sum_stream::PrefixSumStream;
+
+mod scan_max;
+pub use scan_max::{ScanMax, ScanMaxInput};
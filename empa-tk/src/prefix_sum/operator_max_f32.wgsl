@@ -0,0 +1,20 @@
+// Negative infinity
+const IDENTITY = bitcast<f32>(0xFF800000u);
+
+// NaN is detected by its bit pattern, as WGSL implementations may assume that floating point values are never NaN.
+fn is_nan(value: f32) -> bool {
+    return (bitcast<u32>(value) & 0x7FFFFFFFu) > 0x7F800000u;
+}
+
+// Ignores NaN operands, so that a NaN value does not affect the running maximum.
+fn combine(a: f32, b: f32) -> f32 {
+    if is_nan(a) {
+        return b;
+    }
+
+    if is_nan(b) {
+        return a;
+    }
+
+    return select(a, b, b > a);
+}
@@ -0,0 +1,13 @@
+const IDENTITY = DATA_TYPE(0);
+
+// Only used for `u32` data; saturating at the maximum value keeps the addition associative for unsigned values, so the
+// scan remains valid.
+fn combine(a: DATA_TYPE, b: DATA_TYPE) -> DATA_TYPE {
+    let sum = a + b;
+
+    if sum < a {
+        return DATA_TYPE(0xFFFFFFFFu);
+    }
+
+    return sum;
+}
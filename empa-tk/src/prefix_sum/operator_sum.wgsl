@@ -0,0 +1,5 @@
+const IDENTITY = DATA_TYPE(0);
+
+fn combine(a: DATA_TYPE, b: DATA_TYPE) -> DATA_TYPE {
+    return a + b;
+}
@@ -1,4 +1,5 @@
-use std::future::join;
+use std::fmt::Write;
+use std::{marker, mem};
 
 use bytemuck::Zeroable;
 use empa::access_mode::ReadWrite;
@@ -12,14 +13,20 @@ use empa::resource_binding::BindGroupLayout;
 use empa::shader_module::{shader_source, ShaderSource};
 use empa::type_flag::{O, X};
 use empa::{abi, buffer};
+use futures::join;
 
+use crate::cost_estimate::CostEstimate;
 use crate::count_buffer::CountBuffer;
 use crate::generate_dispatch::{GenerateDispatch, GenerateDispatchResources};
+use crate::prefix_sum::PrefixSumStream;
+use crate::radix_key::sealed::KeyType;
+use crate::radix_key::RadixKey;
+use crate::requirements::Requirements;
+use crate::scratch_pool::ScratchPool;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input, ShaderCode};
 
-const GROUPS_SIZE: u32 = 256;
-const VALUES_PER_THREAD: u32 = 8;
-
-const SEGMENT_SIZE: u32 = GROUPS_SIZE * VALUES_PER_THREAD;
+const SHADER_TEMPLATE: &str = include_str!("shader_template.wgsl");
 
 const EXCLUSIVE_SHADER_U32: ShaderSource = shader_source!("exclusive_shader_u32.wgsl");
 const EXCLUSIVE_SHADER_I32: ShaderSource = shader_source!("exclusive_shader_i32.wgsl");
@@ -27,12 +34,36 @@ const EXCLUSIVE_SHADER_F32: ShaderSource = shader_source!("exclusive_shader_f32.
 const INCLUSIVE_SHADER_U32: ShaderSource = shader_source!("inclusive_shader_u32.wgsl");
 const INCLUSIVE_SHADER_I32: ShaderSource = shader_source!("inclusive_shader_i32.wgsl");
 const INCLUSIVE_SHADER_F32: ShaderSource = shader_source!("inclusive_shader_f32.wgsl");
+const EXCLUSIVE_SATURATING_SHADER_U32: ShaderSource =
+    shader_source!("exclusive_saturating_shader_u32.wgsl");
+const INCLUSIVE_SATURATING_SHADER_U32: ShaderSource =
+    shader_source!("inclusive_saturating_shader_u32.wgsl");
+pub(crate) const EXCLUSIVE_MAX_SHADER_F32: ShaderSource =
+    shader_source!("exclusive_max_shader_f32.wgsl");
+pub(crate) const INCLUSIVE_MAX_SHADER_F32: ShaderSource =
+    shader_source!("inclusive_max_shader_f32.wgsl");
+
+pub(crate) const OPERATOR_SUM: &str = include_str!("operator_sum.wgsl");
+pub(crate) const OPERATOR_SATURATING_SUM: &str = include_str!("operator_saturating_sum.wgsl");
+
+pub(crate) const DEFAULT_GROUP_SIZE: u32 = 256;
+pub(crate) const DEFAULT_VALUES_PER_THREAD: u32 = 8;
+
+/// The number of values each workgroup of a [PrefixSum] with the default configuration processes.
+///
+/// A prefix sum over `n` values keeps per-workgroup state for
+/// `n.div_ceil(PREFIX_SUM_SEGMENT_SIZE)` workgroups.
+pub const PREFIX_SUM_SEGMENT_SIZE: u32 = DEFAULT_GROUP_SIZE * DEFAULT_VALUES_PER_THREAD;
 
-#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+/// The number of `u32` words of state the prefix sum keeps per workgroup.
+pub(crate) const GROUP_STATE_WORDS: usize = 2;
+
+/// The initial value of the scan, as the bit pattern of a value of the scan's data type.
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug, Zeroable)]
 #[repr(C)]
-pub struct GroupState {
-    state_0: u32,
-    state_1: u32,
+pub(crate) struct Initial {
+    enabled: u32,
+    bits: u32,
 }
 
 #[derive(empa::resource_binding::Resources)]
@@ -45,55 +76,139 @@ where
     #[resource(binding = 1, visibility = "COMPUTE")]
     data: Storage<'a, [T], ReadWrite>,
     #[resource(binding = 2, visibility = "COMPUTE")]
-    group_state: Storage<'a, [GroupState], ReadWrite>,
+    group_state: Storage<'a, [u32], ReadWrite>,
     #[resource(binding = 3, visibility = "COMPUTE")]
     group_counter: Storage<'a, u32, ReadWrite>,
+    #[resource(binding = 4, visibility = "COMPUTE")]
+    initial: Uniform<'a, Initial>,
+    #[resource(binding = 5, visibility = "COMPUTE")]
+    block_sums: Storage<'a, [T], ReadWrite>,
+    #[resource(binding = 6, visibility = "COMPUTE")]
+    other_output: Storage<'a, [T], ReadWrite>,
+    #[resource(binding = 7, visibility = "COMPUTE")]
+    descending: Uniform<'a, u32>,
 }
 
 type ResourcesLayout<T> = <Resources<'static, T> as empa::resource_binding::Resources>::Layout;
 
 pub struct PrefixSumInput<'a, T, U> {
     pub data: buffer::View<'a, [T], U>,
+    /// The number of values to scan, if it is only known on the device (e.g. the output of a
+    /// prior compaction).
+    ///
+    /// When specified, the workgroups are dispatched indirectly based on the count, rather than on
+    /// the length of `data`; the count is clamped to the length of `data`. When not specified, all
+    /// of `data` is scanned.
     pub count: Option<Uniform<'a, u32>>,
+    /// A value that is combined into every output value, as if the scan continued from a prior
+    /// scan that ended in `initial` (e.g. when scanning a large buffer in chunks, the total of the
+    /// preceding chunks). Equivalent to the identity (`0` for a sum) when not specified.
+    pub initial: Option<T>,
+    /// The direction in which the values are scanned; see [ScanOrder].
+    pub order: ScanOrder,
+}
+
+/// The direction in which a [PrefixSum] scans its data.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ScanOrder {
+    /// Scans from the first value to the last value: the output value at index `i` combines the
+    /// values at the indices up to `i`.
+    #[default]
+    Ascending,
+    /// Scans from the last value to the first value (a suffix sum): the output value at index `i`
+    /// combines the values at the indices from `i` up to the end of the data (or the count).
+    ///
+    /// For an exclusive scan, the output value at index `i` thus excludes the value at `i`, but
+    /// includes the values after `i`.
+    Descending,
 }
 
+/// Computes the inclusive or exclusive prefix sum of a buffer in place.
+///
+/// This single type covers both variants: initialize it with one of the `init_exclusive*` or
+/// `init_inclusive*` constructors (or a [PrefixSumBuilder]). Both variants support an indirect
+/// count (see [PrefixSumInput::count]).
 pub struct PrefixSum<T>
 where
     T: abi::Sized,
 {
     device: Device,
+    fallback_count: UniformCache<u32>,
+    initial: UniformCache<Initial>,
+    descending: UniformCache<u32>,
+    exclusive: bool,
+    segment_size: u32,
     bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
     pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
-    group_state: Buffer<[GroupState], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    group_state: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
     group_counter: Buffer<u32, buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
     generate_dispatch: GenerateDispatch,
     group_size: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
     dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+    empty_block_sums: Buffer<[T], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    empty_other_output: Buffer<[T], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
 }
 
 impl<T> PrefixSum<T>
 where
-    T: abi::Sized + 'static,
+    T: RadixKey,
 {
-    async fn init_internal(device: Device, shader_source: &ShaderSource) -> Self {
-        let shader = device.create_shader_module(shader_source);
+    /// The device limits a prefix sum with the default configuration requires; see
+    /// [PrefixSumBuilder::requirements] for a custom configuration.
+    pub fn requirements() -> Requirements {
+        segment_requirements(DEFAULT_GROUP_SIZE, DEFAULT_VALUES_PER_THREAD)
+    }
 
+    pub(crate) async fn init_internal(
+        device: Device,
+        shader_code: ShaderCode,
+        exclusive: bool,
+        segment_size: u32,
+    ) -> Self {
         let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
         let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
 
-        let create_pipeline = device.create_compute_pipeline(
-            &ComputePipelineDescriptorBuilder::begin()
-                .layout(&pipeline_layout)
-                .compute(ComputeStageBuilder::begin(&shader, "main").finish())
-                .finish(),
+        let create_pipeline = async {
+            match shader_code {
+                ShaderCode::Validated(shader_source) => {
+                    let shader = device.create_shader_module(shader_source);
+
+                    device
+                        .create_compute_pipeline(
+                            &ComputePipelineDescriptorBuilder::begin()
+                                .layout(&pipeline_layout)
+                                .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                                .finish(),
+                        )
+                        .await
+                }
+                ShaderCode::Template(code) => {
+                    let shader = device.create_shader_module(&ShaderSource::unparsed(code));
+
+                    unsafe {
+                        device
+                            .create_compute_pipeline(
+                                &ComputePipelineDescriptorBuilder::begin()
+                                    .layout(&pipeline_layout)
+                                    .compute_unchecked(
+                                        ComputeStageBuilder::begin(&shader, "main").finish(),
+                                    )
+                                    .finish(),
+                            )
+                            .await
+                    }
+                }
+            }
+        };
+        let group_state = device.create_slice_buffer_zeroed(
+            GROUP_STATE_WORDS,
+            buffer::Usages::storage_binding().and_copy_dst(),
         );
-        let group_state =
-            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding().and_copy_dst());
         let group_counter =
             device.create_buffer(0, buffer::Usages::storage_binding().and_copy_dst());
 
         let init_generate_dispatch = GenerateDispatch::init(device.clone());
-        let group_size = device.create_buffer(SEGMENT_SIZE, buffer::Usages::uniform_binding());
+        let group_size = device.create_buffer(segment_size, buffer::Usages::uniform_binding());
         let dispatch = device.create_buffer(
             DispatchWorkgroups {
                 count_x: 1,
@@ -103,10 +218,34 @@ where
             buffer::Usages::storage_binding().and_indirect(),
         );
 
-        let (pipeline, generate_dispatch) = join!(create_pipeline, init_generate_dispatch).await;
+        // Bound in place of the block sums when they are not requested
+        let empty_block_sums =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+
+        // Bound in place of the other variant of the scan when it is not requested
+        let empty_other_output =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+
+        let (pipeline, generate_dispatch) = join!(create_pipeline, init_generate_dispatch);
+
+        let fallback_count = UniformCache::new(&device, 0);
+        let initial = UniformCache::new(
+            &device,
+            Initial {
+                enabled: 0,
+                bits: 0,
+            },
+        );
+
+        let descending = UniformCache::new(&device, 0);
 
         PrefixSum {
             device,
+            fallback_count,
+            initial,
+            descending,
+            exclusive,
+            segment_size,
             bind_group_layout,
             pipeline,
             group_state,
@@ -114,36 +253,334 @@ where
             generate_dispatch,
             group_size,
             dispatch,
+            empty_block_sums,
+            empty_other_output,
+        }
+    }
+
+    /// The number of values each workgroup processes.
+    ///
+    /// Equals [PREFIX_SUM_SEGMENT_SIZE] unless configured otherwise with a [PrefixSumBuilder].
+    pub fn segment_size(&self) -> u32 {
+        self.segment_size
+    }
+
+    /// The number of workgroups the prefix sum dispatches to scan `count` values; this is also the
+    /// number of workgroups for which the prefix sum keeps per-workgroup state.
+    pub fn workgroups(&self, count: u32) -> u32 {
+        div_ceil(count, self.segment_size)
+    }
+
+    /// Estimates the cost of scanning `count` values; the scratch memory is the per-workgroup state
+    /// of the scan.
+    pub fn plan_cost(&self, count: u32) -> CostEstimate {
+        if count == 0 {
+            return CostEstimate::default();
         }
+
+        let group_state_bytes =
+            self.workgroups(count) as u64 * (GROUP_STATE_WORDS * mem::size_of::<u32>()) as u64;
+
+        CostEstimate::new(group_state_bytes, 1, 1)
+    }
+
+    /// Shrinks the internal temporary buffers to the size required to process up to `max_count`
+    /// values, releasing the memory retained after processing larger inputs.
+    ///
+    /// The buffers grow again as needed if larger inputs are encoded later.
+    pub fn shrink_to_fit(&mut self, max_count: u32) {
+        let len = self.workgroups(max_count).max(1) as usize * GROUP_STATE_WORDS;
+
+        if self.group_state.len() > len {
+            self.group_state = self
+                .device
+                .create_slice_buffer_zeroed(len, self.group_state.usage());
+        }
+    }
+
+    /// Grows the internal temporary buffers to the size required to process up to `max_count`
+    /// values, so that encoding inputs of up to that size does not allocate.
+    ///
+    /// The buffers otherwise grow on the first encounter of a larger input; reserving up front
+    /// avoids the allocation stall of that first encounter, e.g. in a latency-sensitive loop.
+    pub fn reserve(&mut self, max_count: u32) {
+        let len = self.workgroups(max_count) as usize * GROUP_STATE_WORDS;
+
+        if self.group_state.len() < len {
+            self.group_state = self
+                .device
+                .create_slice_buffer_zeroed(len, self.group_state.usage());
+        }
+    }
+
+    /// Encodes commands that zero the prefix sum's persistent internal buffers.
+    ///
+    /// Each encoded prefix sum already clears the state it uses before it runs; this makes the
+    /// reset explicit, e.g. to guarantee that no state from a preceding, unrelated prefix sum
+    /// remains in the buffers.
+    pub fn reset(&mut self, encoder: CommandEncoder) -> CommandEncoder {
+        encoder
+            .clear_buffer(self.group_counter.view())
+            .clear_buffer_slice(self.group_state.view())
     }
 
     pub fn encode<U>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: PrefixSumInput<T, U>,
+    ) -> CommandEncoder
+    where
+        U: buffer::StorageBinding,
+    {
+        self.encode_input(encoder, input, None, None, None, true)
+    }
+
+    /// Encodes the prefix sum, using the temporary buffers in the `scratch_pool` rather than the
+    /// prefix sum's own temporary buffers.
+    pub fn encode_with_scratch_pool<U>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: PrefixSumInput<T, U>,
+        scratch_pool: &mut ScratchPool,
+    ) -> CommandEncoder
+    where
+        U: buffer::StorageBinding,
+    {
+        self.encode_input(encoder, input, None, None, Some(scratch_pool), true)
+    }
+
+    /// Encodes the prefix sum without first clearing its internal state.
+    ///
+    /// The state must be zero when the prefix sum executes: encode a [reset](Self::reset) after the
+    /// previous prefix sum and before this one. This allows the resets of several prefix sums to be
+    /// batched ahead of a sequence of prefix sums, rather than clearing the state before each of
+    /// them. A prefix sum that executes with non-zero state produces undefined results.
+    pub fn encode_after_reset<U>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: PrefixSumInput<T, U>,
+    ) -> CommandEncoder
+    where
+        U: buffer::StorageBinding,
+    {
+        self.encode_input(encoder, input, None, None, None, false)
+    }
+
+    /// Encodes the prefix sum, additionally writing the aggregate of each segment (of
+    /// [segment_size](Self::segment_size) values) to `block_sums`.
+    ///
+    /// The aggregate of a segment combines only the input values in that segment: it includes
+    /// neither the values in preceding segments, nor the `initial` value. Combining the block sums
+    /// of the preceding segments thus yields a segment's offset into the scan. For a
+    /// [ScanOrder::Descending] scan, the segments are counted from the end of the scanned values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_sums` holds fewer elements than the number of
+    /// [workgroups](Self::workgroups) for the length of the input data.
+    pub fn encode_with_block_sums<U0, U1>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: PrefixSumInput<T, U0>,
+        block_sums: buffer::View<[T], U1>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        assert!(
+            block_sums.len() >= self.workgroups(input.data.len() as u32) as usize,
+            "`block_sums` must hold at least one element for each segment of `data`"
+        );
+
+        // For an empty input there are no segments
+        let block_sums = if !is_empty_input(input.data.len()) {
+            Some(block_sums.storage())
+        } else {
+            None
+        };
+
+        self.encode_input(encoder, input, block_sums, None, None, true)
+    }
+
+    /// Encodes a single scan that writes both the inclusive prefix sum of the `input` data to
+    /// `inclusive_out` and the exclusive prefix sum to `exclusive_out`, leaving the input data
+    /// intact.
+    ///
+    /// The two variants only differ by each value's own contribution, so this costs a single
+    /// scan; this works for both an exclusive and an inclusive prefix sum.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `inclusive_out` or `exclusive_out` does not have the same length as the input
+    /// data.
+    pub fn encode_both<U0, U1, U2>(
         &mut self,
         mut encoder: CommandEncoder,
+        input: PrefixSumInput<T, U0>,
+        inclusive_out: buffer::View<[T], U1>,
+        exclusive_out: buffer::View<[T], U2>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::CopySrc + 'static,
+        U1: buffer::StorageBinding + buffer::CopyDst + 'static,
+        U2: buffer::StorageBinding + buffer::CopyDst + 'static,
+    {
+        assert_eq!(
+            inclusive_out.len(),
+            input.data.len(),
+            "`inclusive_out` must have the same length as the input data"
+        );
+        assert_eq!(
+            exclusive_out.len(),
+            input.data.len(),
+            "`exclusive_out` must have the same length as the input data"
+        );
+
+        if is_empty_input(input.data.len()) {
+            return encoder;
+        }
+
+        let PrefixSumInput {
+            data,
+            count,
+            initial,
+            order,
+        } = input;
+
+        // The scan runs in place on the output for the variant this prefix sum computes, and the
+        // other variant is emitted alongside it
+        if self.exclusive {
+            encoder = encoder.copy_buffer_to_buffer_slice(data, exclusive_out);
+
+            self.encode_input(
+                encoder,
+                PrefixSumInput {
+                    data: exclusive_out,
+                    count,
+                    initial,
+                    order,
+                },
+                None,
+                Some(inclusive_out.storage()),
+                None,
+                true,
+            )
+        } else {
+            encoder = encoder.copy_buffer_to_buffer_slice(data, inclusive_out);
+
+            self.encode_input(
+                encoder,
+                PrefixSumInput {
+                    data: inclusive_out,
+                    count,
+                    initial,
+                    order,
+                },
+                None,
+                Some(exclusive_out.storage()),
+                None,
+                true,
+            )
+        }
+    }
+
+    fn encode_input<U>(
+        &mut self,
+        encoder: CommandEncoder,
         input: PrefixSumInput<T, U>,
+        block_sums: Option<Storage<[T], ReadWrite>>,
+        other_output: Option<Storage<[T], ReadWrite>>,
+        scratch_pool: Option<&mut ScratchPool>,
+        clear_state: bool,
     ) -> CommandEncoder
     where
         U: buffer::StorageBinding,
     {
-        let PrefixSumInput { data, count } = input;
+        let PrefixSumInput {
+            data,
+            count,
+            initial,
+            order,
+        } = input;
 
-        let dispatch_indirect = count.is_some();
-        let count = CountBuffer::new(count, &self.device, data.len() as u32);
-        let workgroups = (data.len() as u32).div_ceil(SEGMENT_SIZE);
+        self.initial.update(
+            &self.device,
+            Initial {
+                enabled: initial.is_some() as u32,
+                bits: initial.map(|initial| initial.to_bits()).unwrap_or(0),
+            },
+        );
 
-        if self.group_state.len() < workgroups as usize {
-            self.group_state = self
-                .device
-                .create_slice_buffer_zeroed(workgroups as usize, self.group_state.usage());
+        self.encode_internal(
+            encoder,
+            data,
+            count,
+            order,
+            None,
+            block_sums,
+            other_output,
+            scratch_pool,
+            clear_state,
+        )
+    }
+
+    /// Encodes the prefix sum with the initial value read from the `initial_buffer` (e.g. a value
+    /// a prior operation wrote on the device), rather than from the prefix sum's own buffer.
+    pub(crate) fn encode_internal<U>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        data: buffer::View<[T], U>,
+        count: Option<Uniform<u32>>,
+        order: ScanOrder,
+        initial_buffer: Option<Uniform<Initial>>,
+        block_sums: Option<Storage<[T], ReadWrite>>,
+        other_output: Option<Storage<[T], ReadWrite>>,
+        scratch_pool: Option<&mut ScratchPool>,
+        clear_state: bool,
+    ) -> CommandEncoder
+    where
+        U: buffer::StorageBinding,
+    {
+        if is_empty_input(data.len()) {
+            return encoder;
         }
 
+        let dispatch_indirect = count.is_some();
+
+        self.fallback_count.update(&self.device, data.len() as u32);
+        self.descending
+            .update(&self.device, (order == ScanOrder::Descending) as u32);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        let workgroups = self.workgroups(data.len() as u32);
+
+        let group_state_len = workgroups as usize * GROUP_STATE_WORDS;
+
+        let group_state = if let Some(scratch_pool) = scratch_pool {
+            scratch_pool.state(group_state_len)
+        } else {
+            if self.group_state.len() < group_state_len {
+                self.group_state = self
+                    .device
+                    .create_slice_buffer_zeroed(group_state_len, self.group_state.usage());
+            }
+
+            &self.group_state
+        };
+
         let bind_group = self.device.create_bind_group(
             &self.bind_group_layout,
             Resources {
                 count: count.uniform(),
                 data: data.storage(),
-                group_state: self.group_state.storage(),
+                group_state: group_state.storage(),
                 group_counter: self.group_counter.storage(),
+                initial: initial_buffer.unwrap_or_else(|| self.initial.uniform()),
+                block_sums: block_sums.unwrap_or_else(|| self.empty_block_sums.storage()),
+                other_output: other_output.unwrap_or_else(|| self.empty_other_output.storage()),
+                descending: self.descending.uniform(),
             },
         );
 
@@ -154,13 +591,20 @@ where
                     group_size: self.group_size.uniform(),
                     count: count.uniform(),
                     dispatch: self.dispatch.storage(),
+                    // The scan is clamped to the length of the data, so workgroups for a count
+                    // in excess of the length would find no data to process
+                    max_count: Some(self.fallback_count.uniform()),
                 },
             );
         }
 
+        if clear_state {
+            encoder = encoder
+                .clear_buffer(self.group_counter.view())
+                .clear_buffer_slice(group_state.view());
+        }
+
         let encoder = encoder
-            .clear_buffer(self.group_counter.view())
-            .clear_buffer_slice(self.group_state.view())
             .begin_compute_pass()
             .set_pipeline(&self.pipeline)
             .set_bind_groups(&bind_group);
@@ -181,29 +625,334 @@ where
     }
 }
 
+impl<T> PrefixSum<T>
+where
+    T: RadixKey,
+{
+    pub async fn init_exclusive(device: Device) -> Self {
+        PrefixSumBuilder::exclusive(device).finish().await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_exclusive_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_exclusive(device))
+    }
+
+    pub async fn init_inclusive(device: Device) -> Self {
+        PrefixSumBuilder::inclusive(device).finish().await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_inclusive_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_inclusive(device))
+    }
+}
+
 impl PrefixSum<u32> {
     pub async fn init_exclusive_u32(device: Device) -> Self {
-        Self::init_internal(device, &EXCLUSIVE_SHADER_U32).await
+        Self::init_exclusive(device).await
     }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_exclusive_u32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_exclusive_u32(device))
+    }
+
     pub async fn init_inclusive_u32(device: Device) -> Self {
-        Self::init_internal(device, &INCLUSIVE_SHADER_U32).await
+        Self::init_inclusive(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_inclusive_u32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_inclusive_u32(device))
+    }
+
+    /// Initializes an exclusive prefix sum for which the running sum saturates at `u32::MAX`
+    /// rather than wrapping around on overflow.
+    pub async fn init_exclusive_u32_saturating(device: Device) -> Self {
+        PrefixSumBuilder::exclusive(device)
+            .saturating()
+            .finish()
+            .await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_exclusive_u32_saturating_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_exclusive_u32_saturating(device))
+    }
+
+    /// Initializes an inclusive prefix sum for which the running sum saturates at `u32::MAX`
+    /// rather than wrapping around on overflow.
+    pub async fn init_inclusive_u32_saturating(device: Device) -> Self {
+        PrefixSumBuilder::inclusive(device)
+            .saturating()
+            .finish()
+            .await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_inclusive_u32_saturating_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_inclusive_u32_saturating(device))
     }
 }
 
 impl PrefixSum<i32> {
     pub async fn init_exclusive_i32(device: Device) -> Self {
-        Self::init_internal(device, &EXCLUSIVE_SHADER_I32).await
+        Self::init_exclusive(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_exclusive_i32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_exclusive_i32(device))
     }
+
     pub async fn init_inclusive_i32(device: Device) -> Self {
-        Self::init_internal(device, &INCLUSIVE_SHADER_I32).await
+        Self::init_inclusive(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_inclusive_i32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_inclusive_i32(device))
     }
 }
 
 impl PrefixSum<f32> {
     pub async fn init_exclusive_f32(device: Device) -> Self {
-        Self::init_internal(device, &EXCLUSIVE_SHADER_F32).await
+        Self::init_exclusive(device).await
     }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_exclusive_f32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_exclusive_f32(device))
+    }
+
     pub async fn init_inclusive_f32(device: Device) -> Self {
-        Self::init_internal(device, &INCLUSIVE_SHADER_F32).await
+        Self::init_inclusive(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_inclusive_f32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_inclusive_f32(device))
+    }
+}
+
+/// Builds a [PrefixSum] with a custom workgroup configuration.
+///
+/// The defaults match the configuration used by [PrefixSum::init_exclusive] and
+/// [PrefixSum::init_inclusive]: a group size of `256` with `8` values per thread. Smaller groups
+/// may perform better on devices with narrower SIMD units (e.g. many mobile GPUs).
+pub struct PrefixSumBuilder<T> {
+    device: Device,
+    exclusive: bool,
+    saturating: bool,
+    group_size: u32,
+    values_per_thread: u32,
+    max_count: u32,
+    _marker: marker::PhantomData<T>,
+}
+
+impl<T> PrefixSumBuilder<T>
+where
+    T: RadixKey,
+{
+    /// The device limits the prefix sum requires with the current configuration.
+    pub fn requirements(&self) -> Requirements {
+        segment_requirements(self.group_size, self.values_per_thread)
     }
+
+    /// Begins building an exclusive prefix sum.
+    pub fn exclusive(device: Device) -> Self {
+        PrefixSumBuilder {
+            device,
+            exclusive: true,
+            saturating: false,
+            group_size: DEFAULT_GROUP_SIZE,
+            values_per_thread: DEFAULT_VALUES_PER_THREAD,
+            max_count: 0,
+            _marker: marker::PhantomData,
+        }
+    }
+
+    /// Begins building an inclusive prefix sum.
+    pub fn inclusive(device: Device) -> Self {
+        PrefixSumBuilder {
+            exclusive: false,
+            ..Self::exclusive(device)
+        }
+    }
+
+    /// The number of invocations in each workgroup.
+    pub fn group_size(mut self, group_size: u32) -> Self {
+        self.group_size = group_size;
+
+        self
+    }
+
+    /// The number of values each invocation processes.
+    pub fn values_per_thread(mut self, values_per_thread: u32) -> Self {
+        self.values_per_thread = values_per_thread;
+
+        self
+    }
+
+    /// The number of values the prefix sum is expected to scan at most; see [PrefixSum::reserve].
+    ///
+    /// Not a limit: larger inputs may still be scanned, at the cost of growing the prefix sum's
+    /// internal state on their first encounter.
+    pub fn max_count(mut self, max_count: u32) -> Self {
+        self.max_count = max_count;
+
+        self
+    }
+
+    /// Initializes the prefix sum.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group size is `0` or exceeds the device's
+    /// `max_compute_invocations_per_workgroup` limit, if the number of values per thread is `0`, or
+    /// if a segment (`group_size * values_per_thread` values) does not fit in workgroup memory.
+    pub async fn finish(self) -> PrefixSum<T> {
+        let PrefixSumBuilder {
+            device,
+            exclusive,
+            saturating,
+            group_size,
+            values_per_thread,
+            max_count,
+            ..
+        } = self;
+
+        let limits = device.limits();
+
+        assert!(
+            group_size > 0 && group_size <= limits.max_compute_invocations_per_workgroup,
+            "`group_size` must be in the range `1..={}` (got {})",
+            limits.max_compute_invocations_per_workgroup,
+            group_size
+        );
+        assert!(values_per_thread > 0, "`values_per_thread` must not be `0`");
+
+        // The segment is stored in workgroup memory, alongside 3 additional 4-byte values
+        let workgroup_storage_size = (group_size * values_per_thread + 3) * 4;
+
+        assert!(
+            workgroup_storage_size <= limits.max_compute_workgroup_storage_size,
+            "a segment of {} values does not fit in workgroup memory",
+            group_size * values_per_thread
+        );
+
+        let shader_code =
+            if group_size == DEFAULT_GROUP_SIZE && values_per_thread == DEFAULT_VALUES_PER_THREAD {
+                ShaderCode::Validated(default_shader(T::KEY_TYPE, exclusive, saturating))
+            } else {
+                ShaderCode::Template(template_code(
+                    T::KEY_TYPE.wgsl_type(),
+                    exclusive,
+                    operator(saturating),
+                    group_size,
+                    values_per_thread,
+                ))
+            };
+
+        let mut prefix_sum = PrefixSum::init_internal(
+            device,
+            shader_code,
+            exclusive,
+            group_size * values_per_thread,
+        )
+        .await;
+
+        prefix_sum.reserve(max_count);
+
+        prefix_sum
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn finish_blocking(self) -> PrefixSum<T> {
+        pollster::block_on(self.finish())
+    }
+
+    /// Initializes a [PrefixSumStream] that scans chunks with the prefix sum this builder
+    /// configures.
+    ///
+    /// # Panics
+    ///
+    /// Panics for the same configurations as [finish](Self::finish).
+    pub async fn finish_stream(self) -> PrefixSumStream<T> {
+        let device = self.device.clone();
+        let exclusive = self.exclusive;
+        let operator = operator(self.saturating);
+        let prefix_sum = self.finish().await;
+
+        PrefixSumStream::init_internal(device, prefix_sum, exclusive, operator).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn finish_stream_blocking(self) -> PrefixSumStream<T> {
+        pollster::block_on(self.finish_stream())
+    }
+}
+
+impl PrefixSumBuilder<u32> {
+    /// Makes the additions saturate at `u32::MAX` rather than wrap around on overflow.
+    pub fn saturating(mut self) -> Self {
+        self.saturating = true;
+
+        self
+    }
+}
+
+fn default_shader(key_type: KeyType, exclusive: bool, saturating: bool) -> &'static ShaderSource {
+    match (key_type, exclusive, saturating) {
+        (KeyType::U32, true, false) => &EXCLUSIVE_SHADER_U32,
+        (KeyType::I32, true, false) => &EXCLUSIVE_SHADER_I32,
+        (KeyType::F32, true, false) => &EXCLUSIVE_SHADER_F32,
+        (KeyType::U32, false, false) => &INCLUSIVE_SHADER_U32,
+        (KeyType::I32, false, false) => &INCLUSIVE_SHADER_I32,
+        (KeyType::F32, false, false) => &INCLUSIVE_SHADER_F32,
+        (KeyType::U32, true, true) => &EXCLUSIVE_SATURATING_SHADER_U32,
+        (KeyType::U32, false, true) => &INCLUSIVE_SATURATING_SHADER_U32,
+        _ => unreachable!("only `u32` prefix sums saturate"),
+    }
+}
+
+/// Prepends the data type, the scan variant, the workgroup configuration and the scan `operator`
+/// to the shader template.
+fn template_code(
+    data_type: &str,
+    exclusive: bool,
+    operator: &str,
+    group_size: u32,
+    values_per_thread: u32,
+) -> String {
+    let mut code = String::new();
+
+    write!(
+        code,
+        "alias DATA_TYPE = {};\n\n\
+        const OUTPUT_EXCLUSIVE = {};\n\n\
+        const GROUP_SIZE = {}u;\n\
+        const VALUES_PER_THREAD = {}u;\n\n\
+        {}\n\
+        {}",
+        data_type, exclusive, group_size, values_per_thread, operator, SHADER_TEMPLATE
+    )
+    .unwrap();
+
+    code
+}
+
+fn operator(saturating: bool) -> &'static str {
+    if saturating {
+        OPERATOR_SATURATING_SUM
+    } else {
+        OPERATOR_SUM
+    }
+}
+
+// The segment is stored in workgroup memory, alongside 3 additional 4-byte values. The pipeline
+// binds `data`, `group_state`, `group_counter`, `block_sums` and `other_output` as storage buffers.
+fn segment_requirements(group_size: u32, values_per_thread: u32) -> Requirements {
+    Requirements::new(group_size, (group_size * values_per_thread + 3) * 4, 5)
 }
@@ -0,0 +1,237 @@
+use std::fmt::Write;
+
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::ShaderSource;
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::prefix_sum::{Initial, PrefixSum, PrefixSumBuilder, ScanOrder};
+use crate::radix_key::RadixKey;
+use crate::requirements::Requirements;
+use crate::util::is_empty_input;
+
+const SHADER_TEMPLATE: &str = include_str!("stream_template.wgsl");
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a, T>
+where
+    T: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    data: Storage<'a, [T]>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    last_value: Storage<'a, u32, ReadWrite>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    carry: Storage<'a, Initial, ReadWrite>,
+}
+
+type ResourcesLayout<T> = <Resources<'static, T> as empa::resource_binding::Resources>::Layout;
+
+/// Scans a long sequence of values in chunks, continuing each chunk's scan from the total of the
+/// preceding chunks.
+///
+/// The running total (the carry) stays on the device: each [encode_chunk](Self::encode_chunk)
+/// reads the carry, scans the chunk in place with the carry combined into every output (see
+/// [PrefixSumInput::initial](crate::prefix_sum::PrefixSumInput::initial)), and then updates the
+/// carry with the chunk's total. Concatenating the scanned chunks thus yields the scan of the
+/// whole sequence, while each dispatch only covers a single chunk. Use [reset](Self::reset) to
+/// begin a new, independent sequence.
+///
+/// Initialize a stream with one of the `init_*` constructors, or with
+/// [PrefixSumBuilder::finish_stream] for a custom configuration.
+pub struct PrefixSumStream<T>
+where
+    T: abi::Sized,
+{
+    device: Device,
+    prefix_sum: PrefixSum<T>,
+    exclusive: bool,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
+    capture_last_pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+    update_carry_pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+    last_value: Buffer<u32, buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    carry: Buffer<Initial, buffer::Usages<O, O, X, O, O, O, X, X, O, O>>,
+    initial: Buffer<Initial, buffer::Usages<O, O, O, X, O, O, X, O, O, O>>,
+}
+
+impl<T> PrefixSumStream<T>
+where
+    T: RadixKey,
+{
+    pub fn requirements() -> Requirements {
+        PrefixSum::<T>::requirements().max(Requirements::new(1, 0, 3))
+    }
+
+    pub(crate) async fn init_internal(
+        device: Device,
+        prefix_sum: PrefixSum<T>,
+        exclusive: bool,
+        operator: &str,
+    ) -> Self {
+        let mut code = String::new();
+
+        write!(
+            code,
+            "alias DATA_TYPE = {};\n\n\
+            const OUTPUT_EXCLUSIVE = {};\n\n\
+            {}\n\
+            {}",
+            T::KEY_TYPE.wgsl_type(),
+            exclusive,
+            operator,
+            SHADER_TEMPLATE
+        )
+        .unwrap();
+
+        let shader_source = ShaderSource::unparsed(code);
+        let shader = device.create_shader_module(&shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let create_capture_last_pipeline = unsafe {
+            device.create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute_unchecked(ComputeStageBuilder::begin(&shader, "capture_last").finish())
+                    .finish(),
+            )
+        };
+        let create_update_carry_pipeline = unsafe {
+            device.create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute_unchecked(ComputeStageBuilder::begin(&shader, "update_carry").finish())
+                    .finish(),
+            )
+        };
+
+        let (capture_last_pipeline, update_carry_pipeline) =
+            join!(create_capture_last_pipeline, create_update_carry_pipeline);
+
+        let last_value = device.create_buffer(0, buffer::Usages::storage_binding());
+
+        // A zeroed carry is disabled, so the first chunk is scanned without an initial value
+        let carry = device.create_buffer(
+            Initial {
+                enabled: 0,
+                bits: 0,
+            },
+            buffer::Usages::storage_binding()
+                .and_copy_dst()
+                .and_copy_src(),
+        );
+        let initial = device.create_buffer(
+            Initial {
+                enabled: 0,
+                bits: 0,
+            },
+            buffer::Usages::uniform_binding().and_copy_dst(),
+        );
+
+        PrefixSumStream {
+            device,
+            prefix_sum,
+            exclusive,
+            bind_group_layout,
+            capture_last_pipeline,
+            update_carry_pipeline,
+            last_value,
+            carry,
+            initial,
+        }
+    }
+
+    pub async fn init_exclusive(device: Device) -> Self {
+        PrefixSumBuilder::exclusive(device).finish_stream().await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_exclusive_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_exclusive(device))
+    }
+
+    pub async fn init_inclusive(device: Device) -> Self {
+        PrefixSumBuilder::inclusive(device).finish_stream().await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_inclusive_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_inclusive(device))
+    }
+
+    /// Resets the carry, so that the next chunk begins a new sequence.
+    pub fn reset(&mut self, encoder: CommandEncoder) -> CommandEncoder {
+        encoder.clear_buffer(self.carry.view())
+    }
+
+    /// Encodes the scan of the next `chunk` of the sequence, in place.
+    ///
+    /// An empty chunk leaves the carry unchanged.
+    pub fn encode_chunk<U>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        chunk: buffer::View<[T], U>,
+    ) -> CommandEncoder
+    where
+        U: buffer::StorageBinding,
+    {
+        if is_empty_input(chunk.len()) {
+            return encoder;
+        }
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                data: chunk.storage(),
+                last_value: self.last_value.storage(),
+                carry: self.carry.storage(),
+            },
+        );
+
+        if self.exclusive {
+            encoder = encoder
+                .begin_compute_pass()
+                .set_pipeline(&self.capture_last_pipeline)
+                .set_bind_groups(&bind_group)
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: 1,
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end();
+        }
+
+        encoder = encoder.copy_buffer_to_buffer(self.carry.view(), self.initial.view());
+        encoder = self.prefix_sum.encode_internal(
+            encoder,
+            chunk,
+            None,
+            ScanOrder::Ascending,
+            Some(self.initial.uniform()),
+            None,
+            None,
+            None,
+            true,
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.update_carry_pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
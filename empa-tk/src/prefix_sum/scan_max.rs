@@ -0,0 +1,102 @@
+use empa::buffer::Uniform;
+use empa::command::CommandEncoder;
+use empa::device::Device;
+use empa::{abi, buffer};
+
+use crate::prefix_sum::prefix_sum::{
+    DEFAULT_GROUP_SIZE, DEFAULT_VALUES_PER_THREAD, EXCLUSIVE_MAX_SHADER_F32,
+    INCLUSIVE_MAX_SHADER_F32,
+};
+use crate::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
+use crate::radix_key::RadixKey;
+use crate::requirements::Requirements;
+use crate::util::ShaderCode;
+
+pub struct ScanMaxInput<'a, T, U> {
+    pub data: buffer::View<'a, [T], U>,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// Replaces each value with the running maximum of the values up to it.
+///
+/// Uses the same single-pass decoupled look-back as [PrefixSum], with `max` as the operator. An
+/// inclusive scan includes the value itself in its running maximum; an exclusive scan only
+/// includes the values before it, such that the first value becomes negative infinity.
+///
+/// NaN values are ignored: they do not affect the running maximum, and are replaced by the
+/// running maximum in the output. If all values up to (and for an inclusive scan, including) a
+/// value are NaN, the value becomes negative infinity.
+pub struct ScanMax<T>
+where
+    T: abi::Sized,
+{
+    scan: PrefixSum<T>,
+}
+
+impl<T> ScanMax<T>
+where
+    T: RadixKey,
+{
+    pub fn requirements() -> Requirements {
+        PrefixSum::<T>::requirements()
+    }
+
+    pub fn encode<U>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: ScanMaxInput<T, U>,
+    ) -> CommandEncoder
+    where
+        U: buffer::StorageBinding,
+    {
+        let ScanMaxInput { data, count } = input;
+
+        self.scan.encode(
+            encoder,
+            PrefixSumInput {
+                data,
+                count,
+                initial: None,
+                order: ScanOrder::Ascending,
+            },
+        )
+    }
+}
+
+impl ScanMax<f32> {
+    async fn init_f32_internal(device: Device, exclusive: bool) -> Self {
+        let shader_source = if exclusive {
+            &EXCLUSIVE_MAX_SHADER_F32
+        } else {
+            &INCLUSIVE_MAX_SHADER_F32
+        };
+
+        let scan = PrefixSum::init_internal(
+            device,
+            ShaderCode::Validated(shader_source),
+            exclusive,
+            DEFAULT_GROUP_SIZE * DEFAULT_VALUES_PER_THREAD,
+        )
+        .await;
+
+        ScanMax { scan }
+    }
+
+    pub async fn init_exclusive_f32(device: Device) -> Self {
+        Self::init_f32_internal(device, true).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_exclusive_f32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_exclusive_f32(device))
+    }
+
+    pub async fn init_inclusive_f32(device: Device) -> Self {
+        Self::init_f32_internal(device, false).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_inclusive_f32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_inclusive_f32(device))
+    }
+}
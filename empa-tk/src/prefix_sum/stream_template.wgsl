@@ -0,0 +1,36 @@
+// `DATA_TYPE`, `OUTPUT_EXCLUSIVE` and the scan operator are prepended when the shader is initialized; they match
+// the configuration of the prefix sum that scans the chunks.
+
+struct Carry {
+    enabled: u32,
+    bits: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read> data: array<DATA_TYPE>;
+
+@group(0) @binding(1)
+var<storage, read_write> last_value: u32;
+
+@group(0) @binding(2)
+var<storage, read_write> carry: Carry;
+
+// An exclusive scan overwrites the chunk's last value, which the total of the chunk includes, so it must be
+// captured before the chunk is scanned.
+@compute @workgroup_size(1, 1, 1)
+fn capture_last() {
+    last_value = bitcast<u32>(data[arrayLength(&data) - 1u]);
+}
+
+// The scan already combined the prior carry into every output, so the last output (combined with the captured last
+// value for an exclusive scan) is the new carry.
+@compute @workgroup_size(1, 1, 1)
+fn update_carry() {
+    var total = data[arrayLength(&data) - 1u];
+
+    if OUTPUT_EXCLUSIVE {
+        total = combine(total, bitcast<DATA_TYPE>(last_value));
+    }
+
+    carry = Carry(1u, bitcast<u32>(total));
+}
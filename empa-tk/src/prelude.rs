@@ -0,0 +1,48 @@
+//! Re-exports the operators and their input/output types.
+//!
+//! A single `use empa_tk::prelude::*;` covers the common cases.
+
+pub use crate::bitonic::{BitonicSort, BitonicSortInput};
+#[cfg(feature = "value-types")]
+pub use crate::bucket::{BucketBy, BucketByInput, BucketByOutput};
+pub use crate::counting_sort::{CountingSort, CountingSortInput};
+pub use crate::find_runs::{FindRuns, FindRunsInput, FindRunsOutput, FindRunsOutputBuffers};
+#[cfg(feature = "value-types")]
+pub use crate::gather_by::{
+    GatherBy, GatherByInput, GatherByMulti, GatherByMultiInput, GatherByPlan, GatherByRange,
+};
+#[cfg(feature = "value-types")]
+pub use crate::group_by::{GroupBy, GroupByInput, GroupByOutput};
+pub use crate::histogram::{Histogram, HistogramInput, HistogramRange};
+pub use crate::merge::{Merge, MergeInput};
+pub use crate::nth_element::{NthElement, NthElementInput};
+pub use crate::prefix_sum::{
+    PrefixSum, PrefixSumBuilder, PrefixSumInput, PrefixSumStream, ScanMax, ScanMaxInput, ScanOrder,
+};
+#[cfg(feature = "value-types")]
+pub use crate::radix_sort::{
+    KeyFunction, RadixSortAndGather, RadixSortAndGatherInput, RadixSortBy, RadixSortByInput,
+    RadixSortByKey, RadixSortByKeyInput, RadixSortMultiWord, RadixSortMultiWordInput,
+};
+pub use crate::radix_sort::{
+    RadixSort, RadixSortBuilder, RadixSortInput, RadixSortPacked16, RadixSortPacked16Input,
+    RadixSortPartitionInput, RadixSortPartitionOutput,
+};
+#[cfg(feature = "value-types")]
+pub use crate::reduce_by_key::{ReduceByKey, ReduceByKeyInput, ReduceByKeyOutput};
+#[cfg(feature = "value-types")]
+pub use crate::run_length_decode::{RunLengthDecode, RunLengthDecodeInput};
+#[cfg(feature = "value-types")]
+pub use crate::scatter_by::{ScatterBy, ScatterByInput, ScatterByPlan, ScatterByRange};
+pub use crate::search::{LowerBound, LowerBoundInput};
+pub use crate::segmented_reduce::{ReduceOperator, SegmentedReduce, SegmentedReduceInput};
+pub use crate::set_ops::{SetOperation, SetOps, SetOpsInput, SetOpsOutput};
+pub use crate::stream_compaction::{
+    StreamCompaction, StreamCompactionInput, StreamCompactionOutput,
+};
+pub use crate::top_k::{TopK, TopKInput};
+pub use crate::{
+    read_back, read_back_vec, CostEstimate, Count, Profiler, RadixKey, Requirements, ScratchPool,
+};
+#[cfg(feature = "value-types")]
+pub use crate::{IndexTransform, ValueTypeError};
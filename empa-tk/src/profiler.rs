@@ -0,0 +1,101 @@
+use empa::adapter::{Adapter, Feature};
+use empa::command::CommandEncoder;
+use empa::device::Device;
+use empa::query::TimestampQuerySet;
+
+/// Records timestamps between the internal passes of an operation.
+///
+/// Pass a profiler to an operation's `encode_with_profiler` method (e.g.
+/// [RadixSort::encode_with_profiler](crate::radix_sort::RadixSort::encode_with_profiler)) to have
+/// the operation write a timestamp into the profiler's [query_set](Profiler::query_set) at the
+/// start of the operation and after each of its stages. The timestamp at query index `i` (for
+/// `i > 0`) marks the end of the stage named by [stage_name](Profiler::stage_name), so that stage
+/// took `timestamps[i] - timestamps[i - 1]` nanoseconds.
+///
+/// Requires the device to have been created with the [TimestampQuery] and
+/// [TimestampQueryInsideEncoders] features.
+///
+/// [TimestampQuery]: empa::adapter::Feature::TimestampQuery
+/// [TimestampQueryInsideEncoders]: empa::adapter::Feature::TimestampQueryInsideEncoders
+pub struct Profiler {
+    query_set: TimestampQuerySet,
+    capacity: u32,
+    stages: Vec<&'static str>,
+}
+
+impl Profiler {
+    /// Creates a new profiler that can record up to `capacity` timestamps.
+    pub fn new(device: &Device, capacity: u32) -> Self {
+        Profiler {
+            query_set: device.create_timestamp_query_set(capacity),
+            capacity,
+            stages: Vec::new(),
+        }
+    }
+
+    /// Whether the `adapter` supports the timestamp query features a profiler requires.
+    pub fn supported(adapter: &Adapter) -> bool {
+        let features = adapter.features();
+
+        features.contains(Feature::TimestampQuery)
+            && features.contains(Feature::TimestampQueryInsideEncoders)
+    }
+
+    /// The query set the timestamps are written to; resolve the first [len](Profiler::len)
+    /// queries to read the timestamps back.
+    pub fn query_set(&self) -> &TimestampQuerySet {
+        &self.query_set
+    }
+
+    /// The number of timestamps recorded since the profiler was created or last
+    /// [reset](Profiler::reset).
+    pub fn len(&self) -> u32 {
+        self.stages.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// The name of the stage that ends at the timestamp with the given `query_index`.
+    ///
+    /// The first timestamp an operation records is named `"start"`. Returns `None` if no timestamp
+    /// was recorded at the `query_index`.
+    pub fn stage_name(&self, query_index: u32) -> Option<&'static str> {
+        self.stages.get(query_index as usize).copied()
+    }
+
+    /// Clears the recorded stages, so that the next operation records its timestamps starting at
+    /// query index `0`.
+    pub fn reset(&mut self) {
+        self.stages.clear();
+    }
+
+    /// Writes a timestamp marking the end of the `stage` if a `profiler` is provided; does nothing
+    /// otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the profiler's query set is full.
+    pub(crate) fn record(
+        profiler: Option<&mut Profiler>,
+        encoder: CommandEncoder,
+        stage: &'static str,
+    ) -> CommandEncoder {
+        if let Some(profiler) = profiler {
+            let index = profiler.stages.len() as u32;
+
+            assert!(
+                index < profiler.capacity,
+                "the profiler can record at most {} timestamps",
+                profiler.capacity
+            );
+
+            profiler.stages.push(stage);
+
+            encoder.write_timestamp(&profiler.query_set, index)
+        } else {
+            encoder
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use empa::abi;
+
+pub(crate) mod sealed {
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    pub enum KeyType {
+        U32,
+        I32,
+        F32,
+    }
+
+    impl KeyType {
+        pub fn wgsl_type(&self) -> &'static str {
+            match self {
+                KeyType::U32 => "u32",
+                KeyType::I32 => "i32",
+                KeyType::F32 => "f32",
+            }
+        }
+    }
+
+    pub trait Sealed {
+        const KEY_TYPE: KeyType;
+
+        /// The bit pattern of the value, as a shader reinterprets it with a `bitcast`.
+        fn to_bits(self) -> u32;
+    }
+}
+
+/// Marker trait for the key types supported by the operations in this crate, allowing these
+/// operations to be initialized generically over the key type (e.g. `RadixSort::<f32>::init`).
+///
+/// Implemented for `u32`, `i32` and `f32`. Signed integers and floating point values are mapped
+/// to unsigned integers with the same ordering inside the shaders; this trait cannot be
+/// implemented outside of this crate.
+pub trait RadixKey: abi::Sized + sealed::Sealed + 'static {}
+
+impl sealed::Sealed for u32 {
+    const KEY_TYPE: sealed::KeyType = sealed::KeyType::U32;
+
+    fn to_bits(self) -> u32 {
+        self
+    }
+}
+
+impl RadixKey for u32 {}
+
+impl sealed::Sealed for i32 {
+    const KEY_TYPE: sealed::KeyType = sealed::KeyType::I32;
+
+    fn to_bits(self) -> u32 {
+        self as u32
+    }
+}
+
+impl RadixKey for i32 {}
+
+impl sealed::Sealed for f32 {
+    const KEY_TYPE: sealed::KeyType = sealed::KeyType::F32;
+
+    fn to_bits(self) -> u32 {
+        self.to_bits()
+    }
+}
+
+impl RadixKey for f32 {}
@@ -0,0 +1,110 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer::Storage;
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::ShaderSource;
+use empa::{abi, buffer};
+
+use crate::util::div_ceil;
+use crate::write_value_type::{write_opaque_value_type, ValueTypeError};
+
+const SHADER_TEMPLATE: &str = include_str!("shader_template.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a, V>
+where
+    V: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    permutation: Storage<'a, [u32]>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    values: Storage<'a, [V]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    output: Storage<'a, [V], ReadWrite>,
+}
+
+type ResourcesLayout<V> = <Resources<'static, V> as empa::resource_binding::Resources>::Layout;
+
+/// Moves values into the order of a permutation of indices: `output[i] = values[permutation[i]]`.
+pub struct ApplyPermutation<V>
+where
+    V: abi::Sized,
+{
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<V>>,
+    pipeline: ComputePipeline<(ResourcesLayout<V>,)>,
+}
+
+impl<V> ApplyPermutation<V>
+where
+    V: abi::Sized + 'static,
+{
+    pub async fn init(device: Device) -> Result<Self, ValueTypeError> {
+        let mut code = String::new();
+
+        write_opaque_value_type::<V>(&device, &mut code)?;
+
+        code.push_str(SHADER_TEMPLATE);
+
+        let shader_source = ShaderSource::unparsed(code);
+        let shader = device.create_shader_module(&shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<V>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = unsafe {
+            device.create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute_unchecked(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+        }
+        .await;
+
+        Ok(ApplyPermutation {
+            device,
+            bind_group_layout,
+            pipeline,
+        })
+    }
+
+    pub fn encode<U0, U1, U2>(
+        &mut self,
+        encoder: CommandEncoder,
+        permutation: buffer::View<[u32], U0>,
+        values: buffer::View<[V], U1>,
+        output: buffer::View<[V], U2>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                permutation: permutation.storage(),
+                values: values.storage(),
+                output: output.storage(),
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: div_ceil(permutation.len() as u32, GROUP_SIZE),
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
@@ -0,0 +1,25 @@
+// The `VALUE_TYPE` is prepended when the shader is initialized. The values are moved as opaque values, as in the
+// scatter passes of the sort.
+
+@group(0) @binding(0)
+var<storage, read> permutation: array<u32>;
+
+@group(0) @binding(1)
+var<storage, read> values: array<VALUE_TYPE>;
+
+@group(0) @binding(2)
+var<storage, read_write> output: array<VALUE_TYPE>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+
+    if index < arrayLength(&permutation) {
+        let source_index = permutation[index];
+
+        // Guards against an out of bounds read for a malformed permutation
+        if source_index < arrayLength(&values) {
+            output[index] = values[source_index];
+        }
+    }
+}
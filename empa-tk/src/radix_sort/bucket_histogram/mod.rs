@@ -1,5 +1,5 @@
 use empa::access_mode::ReadWrite;
-use empa::buffer::{Storage, Uniform};
+use empa::buffer::{Buffer, Storage, Uniform};
 use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
 use empa::compute_pipeline::{
     ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
@@ -7,66 +7,200 @@ use empa::compute_pipeline::{
 use empa::device::Device;
 use empa::resource_binding::{BindGroupLayout, Resources};
 use empa::shader_module::{shader_source, ShaderSource};
+use empa::type_flag::{O, X};
 use empa::{abi, buffer};
 
-use crate::radix_sort::{RADIX_DIGITS, RADIX_GROUPS};
+use crate::radix_key::sealed::KeyType;
+use crate::radix_key::RadixKey;
+use crate::radix_sort::{configured_shader, key_source_shader_code, RADIX_DIGITS, RADIX_GROUPS};
+#[cfg(feature = "value-types")]
+use crate::radix_sort::{multi_word_shader_code, value_key_shader_code};
+use crate::util::{div_ceil, ShaderCode};
+#[cfg(feature = "value-types")]
+use crate::write_value_type::ValueTypeError;
+
+const SHADER_TEMPLATE: &str = concat!(
+    include_str!("../../dispatch.wgsl"),
+    include_str!("shader_template.wgsl")
+);
 
 const SHADER_U32: ShaderSource = shader_source!("shader_u32.wgsl");
+const SHADER_I32: ShaderSource = shader_source!("shader_i32.wgsl");
+const SHADER_F32: ShaderSource = shader_source!("shader_f32.wgsl");
 
-const GROUP_SIZE: u32 = 256;
-const GROUP_ITERATIONS: u32 = 4;
-pub const BUCKET_HISTOGRAM_SEGMENT_SIZE: u32 = GROUP_SIZE * GROUP_ITERATIONS;
+pub struct BucketHistogramResources<'a, T>
+where
+    T: abi::Sized,
+{
+    pub max_count: Uniform<'a, u32>,
+    pub data: Storage<'a, [T]>,
+    pub global_histograms: Storage<'a, [[u32; RADIX_DIGITS]; RADIX_GROUPS], ReadWrite>,
+}
 
 #[derive(empa::resource_binding::Resources)]
-pub struct BucketHistogramResources<'a, T>
+struct ResourcesInternal<'a, T>
 where
     T: abi::Sized,
 {
     #[resource(binding = 0, visibility = "COMPUTE")]
-    pub max_count: Uniform<'a, u32>,
+    max_count: Uniform<'a, u32>,
     #[resource(binding = 1, visibility = "COMPUTE")]
-    pub data: Storage<'a, [T]>,
+    data: Storage<'a, [T]>,
     #[resource(binding = 2, visibility = "COMPUTE")]
-    pub global_histograms: Storage<'a, [[u32; RADIX_DIGITS]; RADIX_GROUPS], ReadWrite>,
+    global_histograms: Storage<'a, [[u32; RADIX_DIGITS]; RADIX_GROUPS], ReadWrite>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    key_bounds: Storage<'a, [u32; 2], ReadWrite>,
 }
 
-type ResourcesLayout<T> = <BucketHistogramResources<'static, T> as Resources>::Layout;
+type ResourcesLayout<T> = <ResourcesInternal<'static, T> as Resources>::Layout;
+
+pub type KeyBoundsUsages = buffer::Usages<O, O, X, O, O, O, X, O, O, O>;
 
 pub struct BucketHistogram<T>
 where
     T: abi::Sized,
 {
     device: Device,
+    segment_size: u32,
     bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
     pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+    key_bounds: Buffer<[u32; 2], KeyBoundsUsages>,
 }
 
 impl<T> BucketHistogram<T>
 where
     T: abi::Sized + 'static,
 {
-    async fn init_internal(device: Device, shader_source: &ShaderSource) -> Self {
-        let shader = device.create_shader_module(shader_source);
-
+    async fn init_internal(device: Device, shader_code: ShaderCode, segment_size: u32) -> Self {
         let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
         let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
 
-        let pipeline = device
-            .create_compute_pipeline(
-                &ComputePipelineDescriptorBuilder::begin()
-                    .layout(&pipeline_layout)
-                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
-                    .finish(),
-            )
-            .await;
+        let pipeline = match shader_code {
+            ShaderCode::Validated(shader_source) => {
+                let shader = device.create_shader_module(shader_source);
+
+                device
+                    .create_compute_pipeline(
+                        &ComputePipelineDescriptorBuilder::begin()
+                            .layout(&pipeline_layout)
+                            .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                            .finish(),
+                    )
+                    .await
+            }
+            ShaderCode::Template(code) => {
+                let shader = device.create_shader_module(&ShaderSource::unparsed(code));
+
+                unsafe {
+                    device
+                        .create_compute_pipeline(
+                            &ComputePipelineDescriptorBuilder::begin()
+                                .layout(&pipeline_layout)
+                                .compute_unchecked(
+                                    ComputeStageBuilder::begin(&shader, "main").finish(),
+                                )
+                                .finish(),
+                        )
+                        .await
+                }
+            }
+        };
+
+        let key_bounds =
+            device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_dst());
 
         BucketHistogram {
             device,
+            segment_size,
             bind_group_layout,
             pipeline,
+            key_bounds,
         }
     }
 
+    /// Initializes a histogram over the keys that the definitions in `key_source` derive from the
+    /// data; see [key_source_shader_code].
+    #[cfg(feature = "value-types")]
+    pub async fn init_key_source(
+        device: Device,
+        key_source: &str,
+        group_size: u32,
+        values_per_thread: u32,
+    ) -> Self {
+        let code =
+            key_source_shader_code(SHADER_TEMPLATE, key_source, group_size, values_per_thread);
+
+        Self::init_internal(
+            device,
+            ShaderCode::Template(code),
+            group_size * values_per_thread,
+        )
+        .await
+    }
+
+    /// Initializes a histogram over the `word`-th `u32` word of a multi-word key.
+    #[cfg(feature = "value-types")]
+    pub async fn init_multi_word(
+        device: Device,
+        word: usize,
+        group_size: u32,
+        values_per_thread: u32,
+    ) -> Result<Self, ValueTypeError> {
+        let code = multi_word_shader_code::<T>(
+            &device,
+            SHADER_TEMPLATE,
+            word,
+            group_size,
+            values_per_thread,
+        )?;
+
+        Ok(Self::init_internal(
+            device,
+            ShaderCode::Template(code),
+            group_size * values_per_thread,
+        )
+        .await)
+    }
+
+    /// Initializes a histogram over the key that the `to_key` WGSL function derives from each
+    /// value.
+    #[cfg(feature = "value-types")]
+    pub async fn init_value_key(
+        device: Device,
+        to_key: &str,
+        group_size: u32,
+        values_per_thread: u32,
+    ) -> Result<Self, ValueTypeError> {
+        let code = value_key_shader_code::<T>(
+            &device,
+            SHADER_TEMPLATE,
+            to_key,
+            group_size,
+            values_per_thread,
+        )?;
+
+        Ok(Self::init_internal(
+            device,
+            ShaderCode::Template(code),
+            group_size * values_per_thread,
+        )
+        .await)
+    }
+
+    pub fn segment_size(&self) -> u32 {
+        self.segment_size
+    }
+
+    /// The minimum and maximum key (as the `u32` representation the sort orders by) the last
+    /// encoded histogram pass found, where the minimum is stored bitwise inverted.
+    pub fn key_bounds(&self) -> buffer::View<[u32; 2], KeyBoundsUsages> {
+        self.key_bounds.view()
+    }
+
+    pub fn reset(&self, encoder: CommandEncoder) -> CommandEncoder {
+        encoder.clear_buffer(self.key_bounds.view())
+    }
+
     pub fn encode<U>(
         &mut self,
         encoder: CommandEncoder,
@@ -78,11 +212,24 @@ where
     where
         U: buffer::Indirect,
     {
-        let bind_group = self
-            .device
-            .create_bind_group(&self.bind_group_layout, resources);
+        let BucketHistogramResources {
+            max_count,
+            data,
+            global_histograms,
+        } = resources;
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            ResourcesInternal {
+                max_count,
+                data,
+                global_histograms,
+                key_bounds: self.key_bounds.storage(),
+            },
+        );
 
         let encoder = encoder
+            .clear_buffer(self.key_bounds.view())
             .begin_compute_pass()
             .set_pipeline(&self.pipeline)
             .set_bind_groups(&bind_group);
@@ -92,7 +239,7 @@ where
         } else {
             encoder
                 .dispatch_workgroups(DispatchWorkgroups {
-                    count_x: fallback_count.div_ceil(BUCKET_HISTOGRAM_SEGMENT_SIZE),
+                    count_x: div_ceil(fallback_count, self.segment_size),
                     count_y: 1,
                     count_z: 1,
                 })
@@ -101,8 +248,24 @@ where
     }
 }
 
-impl BucketHistogram<u32> {
-    pub async fn init_u32(device: Device) -> Self {
-        Self::init_internal(device, &SHADER_U32).await
+impl<T> BucketHistogram<T>
+where
+    T: RadixKey,
+{
+    pub async fn init(device: Device, group_size: u32, values_per_thread: u32) -> Self {
+        let default_shader = match T::KEY_TYPE {
+            KeyType::U32 => &SHADER_U32,
+            KeyType::I32 => &SHADER_I32,
+            KeyType::F32 => &SHADER_F32,
+        };
+        let shader_code = configured_shader(
+            default_shader,
+            SHADER_TEMPLATE,
+            T::KEY_TYPE,
+            group_size,
+            values_per_thread,
+        );
+
+        Self::init_internal(device, shader_code, group_size * values_per_thread).await
     }
 }
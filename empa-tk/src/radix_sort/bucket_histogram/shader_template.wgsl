@@ -0,0 +1,72 @@
+const SEGMENT_SIZE = GROUP_SIZE * VALUES_PER_THREAD;
+
+const RADIX_SIZE = 8u;
+
+const RADIX_DIGITS = 256u;//1 << RADIX_SIZE;
+const RADIX_GROUPS = 4u;//32 / RADIX_SIZE;
+
+@group(0) @binding(0)
+var<uniform> max_count: u32;
+
+@group(0) @binding(1)
+var<storage, read> data: array<DATA_TYPE>;
+
+@group(0) @binding(2)
+var<storage, read_write> global_histograms: array<array<atomic<u32>, RADIX_DIGITS>, RADIX_GROUPS>;
+
+// The minimum and maximum key, where the minimum is stored inverted, so that both are resolved with `atomicMax` from a
+// zero-initialized buffer
+@group(0) @binding(3)
+var<storage, read_write> key_bounds: array<atomic<u32>, 2>;
+
+var<workgroup> local_histograms: array<array<atomic<u32>, RADIX_DIGITS>, RADIX_GROUPS>;
+
+var<workgroup> local_key_bounds: array<atomic<u32>, 2>;
+
+@compute @workgroup_size(GROUP_SIZE, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let group_index = linear_workgroup_index(workgroup_id, num_workgroups);
+    let count = min(max_count, arrayLength(&data));
+
+    let segment_offset = group_index * SEGMENT_SIZE;
+
+    var min_key_inverted = 0u;
+    var max_key = 0u;
+
+    for (var i = local_index; i < SEGMENT_SIZE; i += GROUP_SIZE) {
+        let data_index = segment_offset + i;
+
+        if data_index < count {
+            let value = to_key(data[data_index]);
+
+            min_key_inverted = max(min_key_inverted, ~value);
+            max_key = max(max_key, value);
+
+            for (var j = 0u; j < RADIX_GROUPS; j++) {
+                let digits = (value >> (j * RADIX_SIZE)) & (RADIX_DIGITS - 1);
+
+                atomicAdd(&local_histograms[j][digits], 1u);
+            }
+        }
+    }
+
+    atomicMax(&local_key_bounds[0], min_key_inverted);
+    atomicMax(&local_key_bounds[1], max_key);
+
+    workgroupBarrier();
+
+    if local_index == 0 {
+        atomicMax(&key_bounds[0], atomicLoad(&local_key_bounds[0]));
+        atomicMax(&key_bounds[1], atomicLoad(&local_key_bounds[1]));
+    }
+
+    for (var i = local_index; i < RADIX_DIGITS; i += GROUP_SIZE) {
+        for (var j = 0u; j < RADIX_GROUPS; j++) {
+            let local_bucket_count = atomicLoad(&local_histograms[j][i]);
+
+            if local_bucket_count > 0 {
+                atomicAdd(&global_histograms[j][i], local_bucket_count);
+            }
+        }
+    }
+}
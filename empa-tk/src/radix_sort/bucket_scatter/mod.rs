@@ -13,14 +13,17 @@ use empa::shader_module::{shader_source, ShaderSource};
 use empa::type_flag::{O, X};
 use empa::{abi, buffer};
 
-use crate::radix_sort::{RADIX_DIGITS, RADIX_GROUPS, RADIX_SIZE};
+use crate::radix_key::sealed::KeyType;
+use crate::radix_key::RadixKey;
+use crate::radix_sort::{configured_shader, RADIX_DIGITS, RADIX_GROUPS, RADIX_SIZE};
+use crate::scratch_pool::ScratchPool;
+use crate::util::{div_ceil, ShaderCode};
 
-const SHADER_U32: ShaderSource = shader_source!("shader_u32.wgsl");
-
-const GROUP_SIZE: u32 = 256;
-const VALUES_PER_THREAD: u32 = 4;
+const SHADER_TEMPLATE: &str = include_str!("shader_template.wgsl");
 
-pub const BUCKET_SCATTER_SEGMENT_SIZE: u32 = GROUP_SIZE * VALUES_PER_THREAD;
+const SHADER_U32: ShaderSource = shader_source!("shader_u32.wgsl");
+const SHADER_I32: ShaderSource = shader_source!("shader_i32.wgsl");
+const SHADER_F32: ShaderSource = shader_source!("shader_f32.wgsl");
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 #[repr(u32)]
@@ -32,7 +35,7 @@ enum GroupStatus {
 
 #[derive(abi::Sized, Clone, Copy, Zeroable)]
 #[repr(C)]
-struct GroupState {
+pub struct GroupState {
     packed_data: u32,
 }
 
@@ -78,7 +81,7 @@ where
     #[resource(binding = 4, visibility = "COMPUTE")]
     global_base_bucket_offsets: Storage<'a, [[u32; RADIX_DIGITS]; RADIX_GROUPS]>,
     #[resource(binding = 5, visibility = "COMPUTE")]
-    group_state: Storage<'a, [[GroupState; RADIX_DIGITS]], ReadWrite>,
+    group_state: Storage<'a, [u32], ReadWrite>,
     #[resource(binding = 6, visibility = "COMPUTE")]
     group_counter: Storage<'a, u32, ReadWrite>,
 }
@@ -101,48 +104,114 @@ where
     T: abi::Sized,
 {
     device: Device,
+    segment_size: u32,
     bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
     pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
-    group_state: Buffer<[[GroupState; RADIX_DIGITS]], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    group_state: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
     group_counter: Buffer<u32, buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    uniforms: [Buffer<Uniforms, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>; RADIX_GROUPS],
 }
 
 impl<T> BucketScatter<T>
 where
     T: abi::Sized + 'static,
 {
-    async fn init_internal(device: Device, shader_source: &ShaderSource) -> Self {
-        let shader = device.create_shader_module(shader_source);
-
+    async fn init_internal(device: Device, shader_code: ShaderCode, segment_size: u32) -> Self {
         let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
         let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
 
-        let pipeline = device
-            .create_compute_pipeline(
-                &ComputePipelineDescriptorBuilder::begin()
-                    .layout(&pipeline_layout)
-                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
-                    .finish(),
-            )
-            .await;
-        let group_state =
-            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding().and_copy_dst());
+        let pipeline = match shader_code {
+            ShaderCode::Validated(shader_source) => {
+                let shader = device.create_shader_module(shader_source);
+
+                device
+                    .create_compute_pipeline(
+                        &ComputePipelineDescriptorBuilder::begin()
+                            .layout(&pipeline_layout)
+                            .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                            .finish(),
+                    )
+                    .await
+            }
+            ShaderCode::Template(code) => {
+                let shader = device.create_shader_module(&ShaderSource::unparsed(code));
+
+                unsafe {
+                    device
+                        .create_compute_pipeline(
+                            &ComputePipelineDescriptorBuilder::begin()
+                                .layout(&pipeline_layout)
+                                .compute_unchecked(
+                                    ComputeStageBuilder::begin(&shader, "main").finish(),
+                                )
+                                .finish(),
+                        )
+                        .await
+                }
+            }
+        };
+        let group_state = device.create_slice_buffer_zeroed(
+            RADIX_DIGITS,
+            buffer::Usages::storage_binding().and_copy_dst(),
+        );
         let group_counter =
             device.create_buffer(0, buffer::Usages::storage_binding().and_copy_dst());
+        let uniforms = [0, 1, 2, 3].map(|radix_group| {
+            device.create_buffer(
+                Uniforms {
+                    radix_offset: RADIX_SIZE * radix_group,
+                    radix_group,
+                },
+                buffer::Usages::uniform_binding(),
+            )
+        });
 
         BucketScatter {
             device,
+            segment_size,
             bind_group_layout,
             pipeline,
             group_state,
             group_counter,
+            uniforms,
+        }
+    }
+
+    pub fn segment_size(&self) -> u32 {
+        self.segment_size
+    }
+
+    pub fn shrink_to_fit(&mut self, max_count: u32) {
+        let len = div_ceil(max_count, self.segment_size).max(1) as usize * RADIX_DIGITS;
+
+        if self.group_state.len() > len {
+            self.group_state = self
+                .device
+                .create_slice_buffer_zeroed(len, self.group_state.usage());
+        }
+    }
+
+    pub fn reserve(&mut self, max_count: u32) {
+        let len = div_ceil(max_count, self.segment_size) as usize * RADIX_DIGITS;
+
+        if self.group_state.len() < len {
+            self.group_state = self
+                .device
+                .create_slice_buffer_zeroed(len, self.group_state.usage());
         }
     }
 
+    pub fn reset(&self, encoder: CommandEncoder) -> CommandEncoder {
+        encoder
+            .clear_buffer(self.group_counter.view())
+            .clear_buffer_slice(self.group_state.view())
+    }
+
     pub fn encode<U0, U1, U2, U3>(
         &mut self,
         encoder: CommandEncoder,
         input: BucketScatterInput<T, U0, U1, U2, U3>,
+        scratch_pool: Option<&mut ScratchPool>,
     ) -> CommandEncoder
     where
         U0: buffer::StorageBinding,
@@ -161,40 +230,38 @@ where
             fallback_count,
         } = input;
 
-        let radix_offset = RADIX_SIZE * radix_group;
+        let fallback_groups = div_ceil(fallback_count, self.segment_size);
 
-        let fallback_groups = fallback_count.div_ceil(BUCKET_SCATTER_SEGMENT_SIZE);
+        let group_state_len = fallback_groups as usize * RADIX_DIGITS;
 
-        if self.group_state.len() < fallback_groups as usize {
-            self.group_state = self
-                .device
-                .create_slice_buffer_zeroed(fallback_groups as usize, self.group_state.usage());
-        }
+        let group_state = if let Some(scratch_pool) = scratch_pool {
+            scratch_pool.state(group_state_len)
+        } else {
+            if self.group_state.len() < group_state_len {
+                self.group_state = self
+                    .device
+                    .create_slice_buffer_zeroed(group_state_len, self.group_state.usage());
+            }
 
-        let uniforms = self.device.create_buffer(
-            Uniforms {
-                radix_offset,
-                radix_group,
-            },
-            buffer::Usages::uniform_binding(),
-        );
+            &self.group_state
+        };
 
         let bind_group = self.device.create_bind_group(
             &self.bind_group_layout,
             Resources {
                 max_count,
-                uniforms: uniforms.uniform(),
+                uniforms: self.uniforms[radix_group as usize].uniform(),
                 data_in: data_in.storage(),
                 data_out: data_out.storage(),
                 global_base_bucket_offsets: global_base_bucket_offsets.storage(),
-                group_state: self.group_state.storage(),
+                group_state: group_state.storage(),
                 group_counter: self.group_counter.storage(),
             },
         );
 
         let encoder = encoder
             .clear_buffer(self.group_counter.view())
-            .clear_buffer_slice(self.group_state.view())
+            .clear_buffer_slice(group_state.view())
             .begin_compute_pass()
             .set_pipeline(&self.pipeline)
             .set_bind_groups(&bind_group);
@@ -213,8 +280,24 @@ where
     }
 }
 
-impl BucketScatter<u32> {
-    pub async fn init_u32(device: Device) -> Self {
-        Self::init_internal(device, &SHADER_U32).await
+impl<T> BucketScatter<T>
+where
+    T: RadixKey,
+{
+    pub async fn init(device: Device, group_size: u32, values_per_thread: u32) -> Self {
+        let default_shader = match T::KEY_TYPE {
+            KeyType::U32 => &SHADER_U32,
+            KeyType::I32 => &SHADER_I32,
+            KeyType::F32 => &SHADER_F32,
+        };
+        let shader_code = configured_shader(
+            default_shader,
+            SHADER_TEMPLATE,
+            T::KEY_TYPE,
+            group_size,
+            values_per_thread,
+        );
+
+        Self::init_internal(device, shader_code, group_size * values_per_thread).await
     }
 }
@@ -1,5 +1,4 @@
-use std::fmt;
-use std::fmt::Write;
+use std::mem;
 
 use bytemuck::Zeroable;
 use empa::access_mode::ReadWrite;
@@ -13,50 +12,34 @@ use empa::resource_binding::BindGroupLayout;
 use empa::shader_module::ShaderSource;
 use empa::type_flag::{O, X};
 use empa::{abi, buffer};
+use futures::join;
 
-use crate::radix_sort::{RADIX_DIGITS, RADIX_GROUPS, RADIX_SIZE};
-use crate::write_value_type::write_value_type;
+use crate::radix_key::RadixKey;
+use crate::radix_sort::{
+    key_source_shader_code, shader_code, RADIX_DIGITS, RADIX_GROUPS, RADIX_SIZE,
+};
+use crate::scratch_pool::ScratchPool;
+use crate::util::div_ceil;
+use crate::write_value_type::{write_opaque_value_type, ValueTypeError};
+
+const SHADER_TEMPLATE: &str = include_str!("shader_template.wgsl");
+
+const LOAD_VALUE: &str = "fn load_value(index: u32) -> VALUE_TYPE { return values_in[index]; }\n\n";
 
-const SHADER_TEMPLATE_U32: &str = include_str!("shader_template_u32.wgsl");
+// Only valid for a 4-byte value type, which is a struct with a single `u32` field
+const GENERATE_VALUE: &str =
+    "fn load_value(index: u32) -> VALUE_TYPE { return VALUE_TYPE(index); }\n\n";
 
 const GROUP_SIZE: u32 = 256;
 const VALUES_PER_THREAD: u32 = 4;
 
+/// The number of values each workgroup processes in the scatter passes of a
+/// [RadixSortBy](crate::radix_sort::RadixSortBy).
+///
+/// A sort over `n` values keeps per-workgroup state for
+/// `n.div_ceil(BUCKET_SCATTER_BY_SEGMENT_SIZE)` workgroups.
 pub const BUCKET_SCATTER_BY_SEGMENT_SIZE: u32 = GROUP_SIZE * VALUES_PER_THREAD;
 
-#[derive(Clone, Copy, PartialEq, Debug)]
-#[repr(u32)]
-enum GroupStatus {
-    NotReady = 0,
-    LocalOffset = 1,
-    GlobalOffset = 2,
-}
-
-#[derive(abi::Sized, Clone, Copy, Zeroable)]
-#[repr(C)]
-struct GroupState {
-    packed_data: u32,
-}
-
-impl fmt::Debug for GroupState {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let status = self.packed_data >> 30;
-        let value = self.packed_data & 0x3FFFFFFF;
-
-        let status = match status {
-            0 => GroupStatus::NotReady,
-            1 => GroupStatus::LocalOffset,
-            2 => GroupStatus::GlobalOffset,
-            _ => unreachable!(),
-        };
-
-        f.debug_struct("GroupState")
-            .field("status", &status)
-            .field("value", &value)
-            .finish()
-    }
-}
-
 #[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
 #[repr(C)]
 pub struct Uniforms {
@@ -85,7 +68,7 @@ where
     #[resource(binding = 6, visibility = "COMPUTE")]
     global_base_bucket_offsets: Storage<'a, [[u32; RADIX_DIGITS]; RADIX_GROUPS]>,
     #[resource(binding = 7, visibility = "COMPUTE")]
-    group_state: Storage<'a, [[GroupState; RADIX_DIGITS]], ReadWrite>,
+    group_state: Storage<'a, [u32], ReadWrite>,
     #[resource(binding = 8, visibility = "COMPUTE")]
     group_counter: Storage<'a, u32, ReadWrite>,
 }
@@ -104,6 +87,11 @@ pub struct BucketScatterByInput<'a, K, V, U0, U1, U2, U3, U4, U5> {
     pub dispatch_indirect: bool,
     pub dispatch: buffer::View<'a, DispatchWorkgroups, U5>,
     pub fallback_count: u32,
+    /// Whether the value that belongs to each key is the key's index in `keys_in`, rather than
+    /// the value in `values_in` (which is then ignored).
+    ///
+    /// Requires a 4-byte value type.
+    pub generate_values: bool,
 }
 
 pub struct BucketScatterBy<K, V>
@@ -114,8 +102,11 @@ where
     device: Device,
     bind_group_layout: BindGroupLayout<ResourcesLayout<K, V>>,
     pipeline: ComputePipeline<(ResourcesLayout<K, V>,)>,
-    group_state: Buffer<[[GroupState; RADIX_DIGITS]], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    generate_values_pipeline: Option<ComputePipeline<(ResourcesLayout<K, V>,)>>,
+    empty_values: Buffer<[V], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    group_state: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
     group_counter: Buffer<u32, buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    uniforms: [Buffer<Uniforms, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>; RADIX_GROUPS],
 }
 
 impl<K, V> BucketScatterBy<K, V>
@@ -123,40 +114,92 @@ where
     K: abi::Sized + 'static,
     V: abi::Sized + 'static,
 {
-    async fn init_internal(device: Device, shader_template: &str) -> Self {
-        let mut code = String::new();
+    async fn init_internal(device: Device, source: &str) -> Result<Self, ValueTypeError> {
+        let mut value_type = String::new();
 
-        write_value_type::<V>(&mut code);
+        write_opaque_value_type::<V>(&device, &mut value_type)?;
 
-        write!(code, "{}", shader_template).unwrap();
-
-        let shader_source = ShaderSource::unparsed(code);
+        let shader_source =
+            ShaderSource::unparsed(format!("{}{}{}", value_type, LOAD_VALUE, source));
         let shader = device.create_shader_module(&shader_source);
 
         let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<K, V>>();
         let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
 
-        let pipeline = unsafe {
+        let create_pipeline = unsafe {
             device.create_compute_pipeline(
                 &ComputePipelineDescriptorBuilder::begin()
                     .layout(&pipeline_layout)
                     .compute_unchecked(ComputeStageBuilder::begin(&shader, "main").finish())
                     .finish(),
             )
-        }
-        .await;
+        };
 
-        let group_state =
-            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding().and_copy_dst());
+        // The values can only be generated as indices for a value type that is the size of an index
+        let create_generate_values_pipeline = async {
+            if mem::size_of::<V>() != 4 {
+                return None;
+            }
+
+            let shader_source =
+                ShaderSource::unparsed(format!("{}{}{}", value_type, GENERATE_VALUE, source));
+            let shader = device.create_shader_module(&shader_source);
+
+            let pipeline = unsafe {
+                device.create_compute_pipeline(
+                    &ComputePipelineDescriptorBuilder::begin()
+                        .layout(&pipeline_layout)
+                        .compute_unchecked(ComputeStageBuilder::begin(&shader, "main").finish())
+                        .finish(),
+                )
+            }
+            .await;
+
+            Some(pipeline)
+        };
+
+        let (pipeline, generate_values_pipeline) =
+            join!(create_pipeline, create_generate_values_pipeline);
+
+        // Bound in place of the input values when the values are generated
+        let empty_values = device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+
+        let group_state = device.create_slice_buffer_zeroed(
+            RADIX_DIGITS,
+            buffer::Usages::storage_binding().and_copy_dst(),
+        );
         let group_counter =
             device.create_buffer(0, buffer::Usages::storage_binding().and_copy_dst());
+        let uniforms = [0, 1, 2, 3].map(|radix_group| {
+            device.create_buffer(
+                Uniforms {
+                    radix_offset: RADIX_SIZE * radix_group,
+                    radix_group,
+                },
+                buffer::Usages::uniform_binding(),
+            )
+        });
 
-        BucketScatterBy {
+        Ok(BucketScatterBy {
             device,
             bind_group_layout,
             pipeline,
+            generate_values_pipeline,
+            empty_values,
             group_state,
             group_counter,
+            uniforms,
+        })
+    }
+
+    pub fn shrink_to_fit(&mut self, max_count: u32) {
+        let len =
+            div_ceil(max_count, BUCKET_SCATTER_BY_SEGMENT_SIZE).max(1) as usize * RADIX_DIGITS;
+
+        if self.group_state.len() > len {
+            self.group_state = self
+                .device
+                .create_slice_buffer_zeroed(len, self.group_state.usage());
         }
     }
 
@@ -164,6 +207,7 @@ where
         &mut self,
         encoder: CommandEncoder,
         input: BucketScatterByInput<K, V, U0, U1, U2, U3, U4, U5>,
+        scratch_pool: Option<&mut ScratchPool>,
     ) -> CommandEncoder
     where
         U0: buffer::StorageBinding,
@@ -184,46 +228,57 @@ where
             dispatch_indirect,
             dispatch,
             fallback_count,
+            generate_values,
         } = input;
 
-        let radix_offset = RADIX_SIZE * radix_group;
+        let fallback_groups = div_ceil(fallback_count, BUCKET_SCATTER_BY_SEGMENT_SIZE);
 
-        let fallback_groups = fallback_count.div_ceil(BUCKET_SCATTER_BY_SEGMENT_SIZE);
+        let group_state_len = fallback_groups as usize * RADIX_DIGITS;
 
-        if self.group_state.len() < fallback_groups as usize {
-            self.group_state = self
-                .device
-                .create_slice_buffer_zeroed(fallback_groups as usize, self.group_state.usage());
-        }
+        let group_state = if let Some(scratch_pool) = scratch_pool {
+            scratch_pool.state(group_state_len)
+        } else {
+            if self.group_state.len() < group_state_len {
+                self.group_state = self
+                    .device
+                    .create_slice_buffer_zeroed(group_state_len, self.group_state.usage());
+            }
 
-        let uniforms = self.device.create_buffer(
-            Uniforms {
-                radix_offset,
-                radix_group,
-            },
-            buffer::Usages::uniform_binding(),
-        );
+            &self.group_state
+        };
 
         let bind_group = self.device.create_bind_group(
             &self.bind_group_layout,
             Resources {
                 max_count,
-                uniforms: uniforms.uniform(),
+                uniforms: self.uniforms[radix_group as usize].uniform(),
                 keys_in: keys_in.storage(),
                 keys_out: keys_out.storage(),
-                values_in: values_in.storage(),
+                values_in: if generate_values {
+                    self.empty_values.storage()
+                } else {
+                    values_in.storage()
+                },
                 values_out: values_out.storage(),
                 global_base_bucket_offsets: global_base_bucket_offsets.storage(),
-                group_state: self.group_state.storage(),
+                group_state: group_state.storage(),
                 group_counter: self.group_counter.storage(),
             },
         );
 
+        let pipeline = if generate_values {
+            self.generate_values_pipeline
+                .as_ref()
+                .expect("values can only be generated for a 4-byte value type")
+        } else {
+            &self.pipeline
+        };
+
         let encoder = encoder
             .clear_buffer(self.group_counter.view())
-            .clear_buffer_slice(self.group_state.view())
+            .clear_buffer_slice(group_state.view())
             .begin_compute_pass()
-            .set_pipeline(&self.pipeline)
+            .set_pipeline(pipeline)
             .set_bind_groups(&bind_group);
 
         if dispatch_indirect {
@@ -240,11 +295,23 @@ where
     }
 }
 
-impl<V> BucketScatterBy<u32, V>
+impl<K, V> BucketScatterBy<K, V>
 where
+    K: RadixKey,
     V: abi::Sized + 'static,
 {
-    pub async fn init_u32(device: Device) -> Self {
-        Self::init_internal(device, SHADER_TEMPLATE_U32).await
+    pub async fn init(device: Device) -> Result<Self, ValueTypeError> {
+        let code = shader_code(SHADER_TEMPLATE, K::KEY_TYPE, GROUP_SIZE, VALUES_PER_THREAD);
+
+        Self::init_internal(device, &code).await
+    }
+
+    /// Initializes the scatter for the keys that the definitions in `key_source` derive from the
+    /// key data; see [key_source_shader_code].
+    pub async fn init_key_source(device: Device, key_source: &str) -> Result<Self, ValueTypeError> {
+        let code =
+            key_source_shader_code(SHADER_TEMPLATE, key_source, GROUP_SIZE, VALUES_PER_THREAD);
+
+        Self::init_internal(device, &code).await
     }
 }
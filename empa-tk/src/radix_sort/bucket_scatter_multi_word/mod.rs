@@ -0,0 +1,245 @@
+use bytemuck::Zeroable;
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::ShaderSource;
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+
+use crate::radix_sort::bucket_scatter::GroupState;
+use crate::radix_sort::{
+    key_word_count, multi_word_shader_code, value_key_shader_code, RADIX_DIGITS, RADIX_GROUPS,
+    RADIX_SIZE,
+};
+use crate::util::div_ceil;
+use crate::write_value_type::ValueTypeError;
+
+const SHADER_TEMPLATE: &str = include_str!("shader_template.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+const VALUES_PER_THREAD: u32 = 4;
+
+pub const BUCKET_SCATTER_MULTI_WORD_SEGMENT_SIZE: u32 = GROUP_SIZE * VALUES_PER_THREAD;
+
+#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+#[repr(C)]
+pub struct Uniforms {
+    radix_offset: u32,
+    radix_group: u32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a, T>
+where
+    T: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    max_count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    uniforms: Uniform<'a, Uniforms>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    data_in: Storage<'a, [T]>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    data_out: Storage<'a, [T], ReadWrite>,
+    #[resource(binding = 4, visibility = "COMPUTE")]
+    global_base_bucket_offsets: Storage<'a, [[u32; RADIX_DIGITS]; RADIX_GROUPS]>,
+    #[resource(binding = 5, visibility = "COMPUTE")]
+    group_state: Storage<'a, [[GroupState; RADIX_DIGITS]], ReadWrite>,
+    #[resource(binding = 6, visibility = "COMPUTE")]
+    group_counter: Storage<'a, u32, ReadWrite>,
+}
+
+type ResourcesLayout<T> = <Resources<'static, T> as empa::resource_binding::Resources>::Layout;
+
+pub struct BucketScatterMultiWordInput<'a, T, U0, U1, U2, U3> {
+    pub data_in: buffer::View<'a, [T], U0>,
+    pub data_out: buffer::View<'a, [T], U1>,
+    pub global_base_bucket_offsets: buffer::View<'a, [[u32; RADIX_DIGITS]; RADIX_GROUPS], U2>,
+    /// The key word to sort by, where word `0` is the most significant word; always `0` for a
+    /// scatter by a derived value key.
+    pub word: usize,
+    pub radix_group: u32,
+    pub max_count: Uniform<'a, u32>,
+    pub dispatch_indirect: bool,
+    pub dispatch: buffer::View<'a, DispatchWorkgroups, U3>,
+    pub fallback_count: u32,
+}
+
+/// Scatters multi-word keys by the digits of one of their `u32` words, or values by the digits of
+/// a key derived from each value.
+///
+/// Holds a pipeline for each key word, as the word is selected by a struct field access, which
+/// cannot be indexed dynamically.
+pub struct BucketScatterMultiWord<T>
+where
+    T: abi::Sized,
+{
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
+    pipelines: Vec<ComputePipeline<(ResourcesLayout<T>,)>>,
+    group_state: Buffer<[[GroupState; RADIX_DIGITS]], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    group_counter: Buffer<u32, buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    uniforms: [Buffer<Uniforms, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>; RADIX_GROUPS],
+}
+
+impl<T> BucketScatterMultiWord<T>
+where
+    T: abi::Sized + 'static,
+{
+    pub async fn init(device: Device) -> Result<Self, ValueTypeError> {
+        let mut codes = Vec::with_capacity(key_word_count::<T>());
+
+        for word in 0..key_word_count::<T>() {
+            codes.push(multi_word_shader_code::<T>(
+                &device,
+                SHADER_TEMPLATE,
+                word,
+                GROUP_SIZE,
+                VALUES_PER_THREAD,
+            )?);
+        }
+
+        Ok(Self::init_internal(device, codes).await)
+    }
+
+    /// Initializes a scatter by the key that the `to_key` WGSL function derives from each value;
+    /// encode with a `word` of `0`.
+    pub async fn init_value_key(device: Device, to_key: &str) -> Result<Self, ValueTypeError> {
+        let code = value_key_shader_code::<T>(
+            &device,
+            SHADER_TEMPLATE,
+            to_key,
+            GROUP_SIZE,
+            VALUES_PER_THREAD,
+        )?;
+
+        Ok(Self::init_internal(device, vec![code]).await)
+    }
+
+    async fn init_internal(device: Device, codes: Vec<String>) -> Self {
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let mut pipelines = Vec::with_capacity(codes.len());
+
+        for code in codes {
+            let shader_source = ShaderSource::unparsed(code);
+            let shader = device.create_shader_module(&shader_source);
+
+            let pipeline = unsafe {
+                device
+                    .create_compute_pipeline(
+                        &ComputePipelineDescriptorBuilder::begin()
+                            .layout(&pipeline_layout)
+                            .compute_unchecked(ComputeStageBuilder::begin(&shader, "main").finish())
+                            .finish(),
+                    )
+                    .await
+            };
+
+            pipelines.push(pipeline);
+        }
+
+        let group_state =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding().and_copy_dst());
+        let group_counter =
+            device.create_buffer(0, buffer::Usages::storage_binding().and_copy_dst());
+        let uniforms = [0, 1, 2, 3].map(|radix_group| {
+            device.create_buffer(
+                Uniforms {
+                    radix_offset: RADIX_SIZE * radix_group,
+                    radix_group,
+                },
+                buffer::Usages::uniform_binding(),
+            )
+        });
+
+        BucketScatterMultiWord {
+            device,
+            bind_group_layout,
+            pipelines,
+            group_state,
+            group_counter,
+            uniforms,
+        }
+    }
+
+    pub fn shrink_to_fit(&mut self, max_count: u32) {
+        let groups = div_ceil(max_count, BUCKET_SCATTER_MULTI_WORD_SEGMENT_SIZE).max(1) as usize;
+
+        if self.group_state.len() > groups {
+            self.group_state = self
+                .device
+                .create_slice_buffer_zeroed(groups, self.group_state.usage());
+        }
+    }
+
+    pub fn encode<U0, U1, U2, U3>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: BucketScatterMultiWordInput<T, U0, U1, U2, U3>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+        U3: buffer::Indirect,
+    {
+        let BucketScatterMultiWordInput {
+            data_in,
+            data_out,
+            global_base_bucket_offsets,
+            word,
+            radix_group,
+            max_count,
+            dispatch_indirect,
+            dispatch,
+            fallback_count,
+        } = input;
+
+        let fallback_groups = div_ceil(fallback_count, BUCKET_SCATTER_MULTI_WORD_SEGMENT_SIZE);
+
+        if self.group_state.len() < fallback_groups as usize {
+            self.group_state = self
+                .device
+                .create_slice_buffer_zeroed(fallback_groups as usize, self.group_state.usage());
+        }
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                max_count,
+                uniforms: self.uniforms[radix_group as usize].uniform(),
+                data_in: data_in.storage(),
+                data_out: data_out.storage(),
+                global_base_bucket_offsets: global_base_bucket_offsets.storage(),
+                group_state: self.group_state.storage(),
+                group_counter: self.group_counter.storage(),
+            },
+        );
+
+        let encoder = encoder
+            .clear_buffer(self.group_counter.view())
+            .clear_buffer_slice(self.group_state.view())
+            .begin_compute_pass()
+            .set_pipeline(&self.pipelines[word])
+            .set_bind_groups(&bind_group);
+
+        if dispatch_indirect {
+            encoder.dispatch_workgroups_indirect(dispatch).end()
+        } else {
+            encoder
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: fallback_groups,
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end()
+        }
+    }
+}
@@ -1,6 +1,6 @@
-const GROUP_SIZE = 256u;
-const VALUES_PER_THREAD = 4u;
-const SEGMENT_SIZE = 1024u; // GROUP_SIZE * VALUES_PER_THREAD;
+// The key type definitions (which select the key word this pass sorts by), `GROUP_SIZE` and `VALUES_PER_THREAD` are
+// prepended when the shader is initialized.
+const SEGMENT_SIZE = GROUP_SIZE * VALUES_PER_THREAD;
 const RADIX_SIZE = 8u;
 
 const RADIX_DIGITS = 256u;//1 << RADIX_SIZE;
@@ -22,31 +22,25 @@ var<uniform> max_count: u32;
 var<uniform> uniforms: Uniforms;
 
 @group(0) @binding(2)
-var<storage, read> keys_in: array<u32>;
+var<storage, read> data_in: array<DATA_TYPE>;
 
 @group(0) @binding(3)
-var<storage, read_write> keys_out: array<u32>;
+var<storage, read_write> data_out: array<DATA_TYPE>;
 
 @group(0) @binding(4)
-var<storage, read> values_in: array<VALUE_TYPE>;
-
-@group(0) @binding(5)
-var<storage, read_write> values_out: array<VALUE_TYPE>;
-
-@group(0) @binding(6)
 var<storage, read> global_base_bucket_offsets: array<array<u32, RADIX_DIGITS>, RADIX_GROUPS>;
 
-@group(0) @binding(7)
+@group(0) @binding(5)
 var<storage, read_write> group_state: array<array<atomic<u32>, RADIX_DIGITS>>;
 
-@group(0) @binding(8)
+@group(0) @binding(6)
 var<storage, read_write> group_counter: atomic<u32>;
 
 var<workgroup> segment_index: u32;
 
 var<workgroup> local_keys: array<u32, SEGMENT_SIZE>;
 
-var<workgroup> local_value_indices: array<u32, SEGMENT_SIZE>;
+var<workgroup> local_indices: array<u32, SEGMENT_SIZE>;
 
 var<workgroup> workspace: array<u32, SEGMENT_SIZE>;
 
@@ -101,7 +95,7 @@ fn sort_local_data(local_index: u32) {
 
         var output_indices: array<u32, VALUES_PER_THREAD>;
         var keys: array<u32, VALUES_PER_THREAD>;
-        var value_indices: array<u32, VALUES_PER_THREAD>;
+        var indices: array<u32, VALUES_PER_THREAD>;
 
         for (var j = 0u; j < VALUES_PER_THREAD; j += 1u) {
             let index = j * GROUP_SIZE + local_index;
@@ -119,7 +113,7 @@ fn sort_local_data(local_index: u32) {
             // Move the local_keys value to its new position. First let all threads read their current into `function`
             // memory, wait for all threads to be done reading, then all threads move their value to the new position.
             keys[j] = local_keys[index];
-            value_indices[j] = local_value_indices[index];
+            indices[j] = local_indices[index];
         }
 
         workgroupBarrier();
@@ -128,7 +122,7 @@ fn sort_local_data(local_index: u32) {
             let index = j * GROUP_SIZE + local_index;
 
             local_keys[output_indices[j]] = keys[j];
-            local_value_indices[output_indices[j]] = value_indices[j];
+            local_indices[output_indices[j]] = indices[j];
         }
 
         workgroupBarrier();
@@ -144,7 +138,7 @@ fn main(@builtin(local_invocation_index) local_index: u32) {
     let uniform_segment_index = workgroupUniformLoad(&segment_index);
     let segment_offset = uniform_segment_index * SEGMENT_SIZE;
 
-    let count = min(max_count, arrayLength(&keys_in));
+    let count = min(max_count, arrayLength(&data_in));
 
     if segment_offset >= count {
         return;
@@ -154,8 +148,8 @@ fn main(@builtin(local_invocation_index) local_index: u32) {
 
     for (var i = local_index; i < SEGMENT_SIZE; i += GROUP_SIZE) {
         if i < data_size {
-            local_keys[i] = keys_in[segment_offset + i];
-            local_value_indices[i] = i;
+            local_keys[i] = to_key(data_in[segment_offset + i]);
+            local_indices[i] = i;
         } else {
             local_keys[i] = 0xFFFFFFFFu;
         }
@@ -320,11 +314,11 @@ fn main(@builtin(local_invocation_index) local_index: u32) {
         let output_index = global_bucket_offset + within_bucket_index;
 
         if index < data_size {
-            keys_out[output_index] = local_keys[index];
-
-            let value_in_index = segment_offset + local_value_indices[index];
+            // Only the current key word is kept in workgroup memory, the full value is moved from its original
+            // position
+            let data_in_index = segment_offset + local_indices[index];
 
-            values_out[output_index] = values_in[value_in_index];
+            data_out[output_index] = data_in[data_in_index];
         }
     }
 }
@@ -0,0 +1,29 @@
+// `GROUP_SIZE` and `VALUES_PER_THREAD` are defined ahead of this template, to match the segments of the histogram
+// pass, so that this pass can be dispatched indirectly with the histogram pass's dispatch.
+const SEGMENT_SIZE = GROUP_SIZE * VALUES_PER_THREAD;
+
+@group(0) @binding(0)
+var<uniform> max_count: u32;
+
+@group(0) @binding(1)
+var<storage, read> data: array<DATA_TYPE>;
+
+@group(0) @binding(2)
+var<storage, read_write> descent: atomic<u32>;
+
+@compute @workgroup_size(GROUP_SIZE, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let group_index = linear_workgroup_index(workgroup_id, num_workgroups);
+    let count = min(max_count, arrayLength(&data));
+
+    let segment_offset = group_index * SEGMENT_SIZE;
+
+    for (var i = local_index; i < SEGMENT_SIZE; i += GROUP_SIZE) {
+        let index = segment_offset + i;
+
+        // Compare in the order the sort uses, so that e.g. `-0.0` after `0.0` counts as a descent
+        if index + 1 < count && to_key(data[index]) > to_key(data[index + 1]) {
+            atomicStore(&descent, 1u);
+        }
+    }
+}
@@ -0,0 +1,16 @@
+#include "../../dispatch.wgsl"
+
+alias DATA_TYPE = u32;
+
+fn to_key(value: u32) -> u32 {
+    return value;
+}
+
+fn from_key(key: u32) -> u32 {
+    return key;
+}
+
+const GROUP_SIZE = 256u;
+const VALUES_PER_THREAD = 4u;
+
+#include "mark_descents_template.wgsl"
@@ -0,0 +1,307 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::{BindGroupLayout, Resources};
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::radix_key::sealed::KeyType;
+use crate::radix_key::RadixKey;
+use crate::radix_sort::configured_shader;
+use crate::util::ShaderCode;
+
+const MARK_DESCENTS_TEMPLATE: &str = concat!(
+    include_str!("../../dispatch.wgsl"),
+    include_str!("mark_descents_template.wgsl")
+);
+const MARK_DESCENTS_SHADER_U32: ShaderSource = shader_source!("mark_descents_u32.wgsl");
+const MARK_DESCENTS_SHADER_I32: ShaderSource = shader_source!("mark_descents_i32.wgsl");
+const MARK_DESCENTS_SHADER_F32: ShaderSource = shader_source!("mark_descents_f32.wgsl");
+const RESOLVE_SHADER: ShaderSource = shader_source!("resolve.wgsl");
+const RESOLVE_VERIFY_SHADER: ShaderSource = shader_source!("resolve_verify.wgsl");
+
+#[derive(empa::resource_binding::Resources)]
+struct MarkDescentsResources<'a, T>
+where
+    T: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    max_count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    data: Storage<'a, [T]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    descent: Storage<'a, u32, ReadWrite>,
+}
+
+type MarkDescentsLayout<T> = <MarkDescentsResources<'static, T> as Resources>::Layout;
+
+#[derive(empa::resource_binding::Resources)]
+struct ResolveResources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    descent: Storage<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    is_sorted: Storage<'a, u32, ReadWrite>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    histogram_dispatch: Storage<'a, DispatchWorkgroups, ReadWrite>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    scatter_dispatch: Storage<'a, DispatchWorkgroups, ReadWrite>,
+}
+
+type ResolveLayout = <ResolveResources<'static> as Resources>::Layout;
+
+#[derive(empa::resource_binding::Resources)]
+struct ResolveVerifyResources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    descent: Storage<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    is_sorted: Storage<'a, u32, ReadWrite>,
+}
+
+type ResolveVerifyLayout = <ResolveVerifyResources<'static> as Resources>::Layout;
+
+pub struct CheckSortedInput<'a, T, U0, U1> {
+    pub max_count: Uniform<'a, u32>,
+    pub data: Storage<'a, [T]>,
+    pub is_sorted: Storage<'a, u32, ReadWrite>,
+    /// The dispatch of the histogram pass; also dispatches the search for descents.
+    pub histogram_dispatch: buffer::View<'a, DispatchWorkgroups, U0>,
+    pub scatter_dispatch: buffer::View<'a, DispatchWorkgroups, U1>,
+}
+
+/// Checks whether the data is already sorted, and if so, zeroes the indirect dispatches of the
+/// sort's passes.
+pub struct CheckSorted<T>
+where
+    T: abi::Sized,
+{
+    device: Device,
+    mark_descents_layout: BindGroupLayout<MarkDescentsLayout<T>>,
+    mark_descents_pipeline: ComputePipeline<(MarkDescentsLayout<T>,)>,
+    resolve_layout: BindGroupLayout<ResolveLayout>,
+    resolve_pipeline: ComputePipeline<(ResolveLayout,)>,
+    resolve_verify_layout: BindGroupLayout<ResolveVerifyLayout>,
+    resolve_verify_pipeline: ComputePipeline<(ResolveVerifyLayout,)>,
+    descent: Buffer<u32, buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+}
+
+impl<T> CheckSorted<T>
+where
+    T: RadixKey,
+{
+    pub async fn init(device: Device, group_size: u32, values_per_thread: u32) -> Self {
+        let default_mark_descents_shader = match T::KEY_TYPE {
+            KeyType::U32 => &MARK_DESCENTS_SHADER_U32,
+            KeyType::I32 => &MARK_DESCENTS_SHADER_I32,
+            KeyType::F32 => &MARK_DESCENTS_SHADER_F32,
+        };
+        let mark_descents_code = configured_shader(
+            default_mark_descents_shader,
+            MARK_DESCENTS_TEMPLATE,
+            T::KEY_TYPE,
+            group_size,
+            values_per_thread,
+        );
+        let resolve_shader = device.create_shader_module(&RESOLVE_SHADER);
+        let resolve_verify_shader = device.create_shader_module(&RESOLVE_VERIFY_SHADER);
+
+        let mark_descents_layout = device.create_bind_group_layout::<MarkDescentsLayout<T>>();
+        let mark_descents_pipeline_layout = device.create_pipeline_layout(&mark_descents_layout);
+
+        let resolve_layout = device.create_bind_group_layout::<ResolveLayout>();
+        let resolve_pipeline_layout = device.create_pipeline_layout(&resolve_layout);
+
+        let resolve_verify_layout = device.create_bind_group_layout::<ResolveVerifyLayout>();
+        let resolve_verify_pipeline_layout = device.create_pipeline_layout(&resolve_verify_layout);
+
+        let create_mark_descents_pipeline = async {
+            match mark_descents_code {
+                ShaderCode::Validated(shader_source) => {
+                    let shader = device.create_shader_module(shader_source);
+
+                    device
+                        .create_compute_pipeline(
+                            &ComputePipelineDescriptorBuilder::begin()
+                                .layout(&mark_descents_pipeline_layout)
+                                .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                                .finish(),
+                        )
+                        .await
+                }
+                ShaderCode::Template(code) => {
+                    let shader = device.create_shader_module(&ShaderSource::unparsed(code));
+
+                    unsafe {
+                        device
+                            .create_compute_pipeline(
+                                &ComputePipelineDescriptorBuilder::begin()
+                                    .layout(&mark_descents_pipeline_layout)
+                                    .compute_unchecked(
+                                        ComputeStageBuilder::begin(&shader, "main").finish(),
+                                    )
+                                    .finish(),
+                            )
+                            .await
+                    }
+                }
+            }
+        };
+        let create_resolve_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&resolve_pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&resolve_shader, "main").finish())
+                .finish(),
+        );
+
+        let create_resolve_verify_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&resolve_verify_pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&resolve_verify_shader, "main").finish())
+                .finish(),
+        );
+
+        let (mark_descents_pipeline, resolve_pipeline, resolve_verify_pipeline) = join!(
+            create_mark_descents_pipeline,
+            create_resolve_pipeline,
+            create_resolve_verify_pipeline
+        );
+
+        let descent = device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_dst());
+
+        CheckSorted {
+            device,
+            mark_descents_layout,
+            mark_descents_pipeline,
+            resolve_layout,
+            resolve_pipeline,
+            resolve_verify_layout,
+            resolve_verify_pipeline,
+            descent,
+        }
+    }
+}
+
+impl<T> CheckSorted<T>
+where
+    T: abi::Sized + 'static,
+{
+    /// Encodes the check; the dispatches must already have been generated for the count.
+    pub fn reset(&self, encoder: CommandEncoder) -> CommandEncoder {
+        encoder.clear_buffer(self.descent.view())
+    }
+
+    pub fn encode<U0, U1>(
+        &self,
+        encoder: CommandEncoder,
+        input: CheckSortedInput<T, U0, U1>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding + buffer::Indirect,
+        U1: buffer::StorageBinding,
+    {
+        let CheckSortedInput {
+            max_count,
+            data,
+            is_sorted,
+            histogram_dispatch,
+            scatter_dispatch,
+        } = input;
+
+        let mark_descents_bind_group = self.device.create_bind_group(
+            &self.mark_descents_layout,
+            MarkDescentsResources {
+                max_count,
+                data,
+                descent: self.descent.storage(),
+            },
+        );
+        let resolve_bind_group = self.device.create_bind_group(
+            &self.resolve_layout,
+            ResolveResources {
+                descent: self.descent.storage(),
+                is_sorted,
+                histogram_dispatch: histogram_dispatch.storage(),
+                scatter_dispatch: scatter_dispatch.storage(),
+            },
+        );
+
+        encoder
+            .clear_buffer(self.descent.view())
+            .begin_compute_pass()
+            .set_pipeline(&self.mark_descents_pipeline)
+            .set_bind_groups(&mark_descents_bind_group)
+            .dispatch_workgroups_indirect(histogram_dispatch)
+            .end()
+            .begin_compute_pass()
+            .set_pipeline(&self.resolve_pipeline)
+            .set_bind_groups(&resolve_bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+    /// Encodes a check that only writes the result to `is_sorted`, without modifying the
+    /// dispatches of a sort; the search for descents is dispatched directly with `workgroups`
+    /// workgroups.
+    ///
+    /// If `data` is `None` (for an empty input that cannot be bound), then the data is considered
+    /// sorted.
+    pub fn encode_verify(
+        &self,
+        mut encoder: CommandEncoder,
+        max_count: Uniform<u32>,
+        data: Option<Storage<[T]>>,
+        workgroups: u32,
+        is_sorted: Storage<u32, ReadWrite>,
+    ) -> CommandEncoder {
+        encoder = encoder.clear_buffer(self.descent.view());
+
+        if let Some(data) = data {
+            let mark_descents_bind_group = self.device.create_bind_group(
+                &self.mark_descents_layout,
+                MarkDescentsResources {
+                    max_count,
+                    data,
+                    descent: self.descent.storage(),
+                },
+            );
+
+            encoder = encoder
+                .begin_compute_pass()
+                .set_pipeline(&self.mark_descents_pipeline)
+                .set_bind_groups(&mark_descents_bind_group)
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: workgroups,
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end();
+        }
+
+        let resolve_bind_group = self.device.create_bind_group(
+            &self.resolve_verify_layout,
+            ResolveVerifyResources {
+                descent: self.descent.storage(),
+                is_sorted,
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.resolve_verify_pipeline)
+            .set_bind_groups(&resolve_bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
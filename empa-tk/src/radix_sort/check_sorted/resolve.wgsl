@@ -0,0 +1,30 @@
+struct DispatchWorkgroups {
+    x: u32,
+    y: u32,
+    z: u32
+}
+
+@group(0) @binding(0)
+var<storage, read> descent: u32;
+
+@group(0) @binding(1)
+var<storage, read_write> is_sorted: u32;
+
+@group(0) @binding(2)
+var<storage, read_write> histogram_dispatch: DispatchWorkgroups;
+
+@group(0) @binding(3)
+var<storage, read_write> scatter_dispatch: DispatchWorkgroups;
+
+@compute @workgroup_size(1, 1, 1)
+fn main() {
+    if descent == 0 {
+        is_sorted = 1u;
+
+        // Skip the sort's passes by dispatching zero workgroups
+        histogram_dispatch = DispatchWorkgroups(0, 1, 1);
+        scatter_dispatch = DispatchWorkgroups(0, 1, 1);
+    } else {
+        is_sorted = 0u;
+    }
+}
@@ -0,0 +1,14 @@
+@group(0) @binding(0)
+var<storage, read> descent: u32;
+
+@group(0) @binding(1)
+var<storage, read_write> is_sorted: u32;
+
+@compute @workgroup_size(1, 1, 1)
+fn main() {
+    if descent == 0 {
+        is_sorted = 1u;
+    } else {
+        is_sorted = 0u;
+    }
+}
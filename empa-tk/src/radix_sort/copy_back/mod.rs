@@ -0,0 +1,153 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::{BindGroupLayout, Resources};
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::{abi, buffer};
+
+use crate::radix_key::sealed::KeyType;
+use crate::radix_key::RadixKey;
+use crate::radix_sort::configured_shader;
+use crate::util::{div_ceil, ShaderCode};
+
+const SHADER_TEMPLATE: &str = concat!(
+    include_str!("../../dispatch.wgsl"),
+    include_str!("shader_template.wgsl")
+);
+
+const SHADER_U32: ShaderSource = shader_source!("shader_u32.wgsl");
+const SHADER_I32: ShaderSource = shader_source!("shader_i32.wgsl");
+const SHADER_F32: ShaderSource = shader_source!("shader_f32.wgsl");
+
+#[derive(empa::resource_binding::Resources)]
+pub struct CopyBackResources<'a, T>
+where
+    T: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    pub max_count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    pub data_in: Storage<'a, [T]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    pub data_out: Storage<'a, [T], ReadWrite>,
+}
+
+type ResourcesLayout<T> = <CopyBackResources<'static, T> as Resources>::Layout;
+
+/// Copies the values a sort with an odd number of scatter passes left in the temporary storage
+/// back to the data.
+///
+/// Only the first `max_count` values are copied, so that the values past the count are preserved.
+pub struct CopyBack<T>
+where
+    T: abi::Sized,
+{
+    device: Device,
+    segment_size: u32,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
+    pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+}
+
+impl<T> CopyBack<T>
+where
+    T: RadixKey,
+{
+    pub async fn init(device: Device, group_size: u32, values_per_thread: u32) -> Self {
+        let default_shader = match T::KEY_TYPE {
+            KeyType::U32 => &SHADER_U32,
+            KeyType::I32 => &SHADER_I32,
+            KeyType::F32 => &SHADER_F32,
+        };
+        let shader_code = configured_shader(
+            default_shader,
+            SHADER_TEMPLATE,
+            T::KEY_TYPE,
+            group_size,
+            values_per_thread,
+        );
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = match shader_code {
+            ShaderCode::Validated(shader_source) => {
+                let shader = device.create_shader_module(shader_source);
+
+                device
+                    .create_compute_pipeline(
+                        &ComputePipelineDescriptorBuilder::begin()
+                            .layout(&pipeline_layout)
+                            .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                            .finish(),
+                    )
+                    .await
+            }
+            ShaderCode::Template(code) => {
+                let shader = device.create_shader_module(&ShaderSource::unparsed(code));
+
+                unsafe {
+                    device
+                        .create_compute_pipeline(
+                            &ComputePipelineDescriptorBuilder::begin()
+                                .layout(&pipeline_layout)
+                                .compute_unchecked(
+                                    ComputeStageBuilder::begin(&shader, "main").finish(),
+                                )
+                                .finish(),
+                        )
+                        .await
+                }
+            }
+        };
+
+        CopyBack {
+            device,
+            segment_size: group_size * values_per_thread,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+}
+
+impl<T> CopyBack<T>
+where
+    T: abi::Sized + 'static,
+{
+    /// Encodes the copy; an indirect copy uses the dispatch of the scatter passes.
+    pub fn encode<U>(
+        &self,
+        encoder: CommandEncoder,
+        resources: CopyBackResources<T>,
+        dispatch_indirect: bool,
+        dispatch: buffer::View<DispatchWorkgroups, U>,
+        fallback_count: u32,
+    ) -> CommandEncoder
+    where
+        U: buffer::Indirect,
+    {
+        let bind_group = self
+            .device
+            .create_bind_group(&self.bind_group_layout, resources);
+
+        let encoder = encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group);
+
+        if dispatch_indirect {
+            encoder.dispatch_workgroups_indirect(dispatch).end()
+        } else {
+            encoder
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: div_ceil(fallback_count, self.segment_size),
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end()
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// The key type definitions, `GROUP_SIZE` and `VALUES_PER_THREAD` are defined ahead of this template. These match the
+// configuration of the scatter passes, so that the copy can share the scatter passes' dispatch.
+const SEGMENT_SIZE = GROUP_SIZE * VALUES_PER_THREAD;
+
+@group(0) @binding(0)
+var<uniform> max_count: u32;
+
+@group(0) @binding(1)
+var<storage, read> data_in: array<DATA_TYPE>;
+
+@group(0) @binding(2)
+var<storage, read_write> data_out: array<DATA_TYPE>;
+
+@compute @workgroup_size(GROUP_SIZE, 1, 1)
+fn main(
+    @builtin(local_invocation_index) local_index: u32,
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    let segment_index = linear_workgroup_index(workgroup_id, num_workgroups);
+    let segment_offset = segment_index * SEGMENT_SIZE;
+
+    let count = min(max_count, min(arrayLength(&data_in), arrayLength(&data_out)));
+
+    for (var i = local_index; i < SEGMENT_SIZE; i += GROUP_SIZE) {
+        let index = segment_offset + i;
+
+        if index < count {
+            data_out[index] = data_in[index];
+        }
+    }
+}
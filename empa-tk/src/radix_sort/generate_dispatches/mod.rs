@@ -9,13 +9,27 @@ use empa::device::Device;
 use empa::resource_binding::{BindGroupLayout, Resources};
 use empa::shader_module::{shader_source, ShaderSource};
 
-const SHADER: ShaderSource = shader_source!("shader.wgsl");
+use crate::radix_key::sealed::KeyType;
+use crate::radix_key::RadixKey;
+#[cfg(feature = "value-types")]
+use crate::write_value_type::{write_value_type, ValueTypeError};
+
+const SHADER_U32: ShaderSource = shader_source!("shader_u32.wgsl");
+const SHADER_I32: ShaderSource = shader_source!("shader_i32.wgsl");
+const SHADER_F32: ShaderSource = shader_source!("shader_f32.wgsl");
+const SHADER_CORE: &str = concat!(
+    include_str!("../../dispatch.wgsl"),
+    include_str!("shader_core.wgsl")
+);
 
 #[derive(abi::Sized, Clone, Copy)]
 #[repr(C)]
 pub struct SegmentSizes {
     pub histogram: u32,
     pub scatter: u32,
+    /// The device's `max_compute_workgroups_per_dimension` limit; larger dispatches spill into the
+    /// `y` dimension.
+    pub max_workgroups: u32,
 }
 
 #[derive(empa::resource_binding::Resources)]
@@ -48,10 +62,16 @@ where
 
 impl<T> GenerateDispatches<T>
 where
-    T: abi::Sized + 'static,
+    T: RadixKey,
 {
     pub async fn init(device: Device) -> Self {
-        let shader = device.create_shader_module(&SHADER);
+        let shader_source = match T::KEY_TYPE {
+            KeyType::U32 => &SHADER_U32,
+            KeyType::I32 => &SHADER_I32,
+            KeyType::F32 => &SHADER_F32,
+        };
+
+        let shader = device.create_shader_module(shader_source);
 
         let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
         let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
@@ -71,6 +91,46 @@ where
             pipeline,
         }
     }
+}
+
+impl<T> GenerateDispatches<T>
+where
+    T: abi::Sized + 'static,
+{
+    /// Initializes the dispatch generation for a data type that is not a [RadixKey] (e.g. a
+    /// multi-word key).
+    #[cfg(feature = "value-types")]
+    pub async fn init_value_type(device: Device) -> Result<Self, ValueTypeError> {
+        let mut code = String::new();
+
+        write_value_type::<T>(&device, &mut code)?;
+
+        code.push_str("alias DATA_TYPE = VALUE_TYPE;\n\n");
+        code.push_str(SHADER_CORE);
+
+        let shader_source = ShaderSource::unparsed(code);
+        let shader = device.create_shader_module(&shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = unsafe {
+            device
+                .create_compute_pipeline(
+                    &ComputePipelineDescriptorBuilder::begin()
+                        .layout(&pipeline_layout)
+                        .compute_unchecked(ComputeStageBuilder::begin(&shader, "main").finish())
+                        .finish(),
+                )
+                .await
+        };
+
+        Ok(GenerateDispatches {
+            device,
+            bind_group_layout,
+            pipeline,
+        })
+    }
 
     pub fn encode(
         &self,
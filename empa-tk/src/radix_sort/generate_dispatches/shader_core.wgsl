@@ -7,6 +7,7 @@ struct DispatchWorkgroups {
 struct SegmentSizes {
     histogram: u32,
     scatter: u32,
+    max_workgroups: u32,
 }
 
 @group(0) @binding(0)
@@ -16,7 +17,7 @@ var<uniform> segment_sizes: SegmentSizes;
 var<uniform> max_count: u32;
 
 @group(0) @binding(2)
-var<storage, read> data: array<u32>;
+var<storage, read> data: array<DATA_TYPE>;
 
 @group(0) @binding(3)
 var<storage, read_write> histogram_dispatch: DispatchWorkgroups;
@@ -24,8 +25,11 @@ var<storage, read_write> histogram_dispatch: DispatchWorkgroups;
 @group(0) @binding(4)
 var<storage, read_write> scatter_dispatch: DispatchWorkgroups;
 
-fn div_ceil(a: u32, b: u32) -> u32 {
-    return (a + b - 1) / b;
+// Spills into the `y` dimension like a `GenerateDispatch` (see `dispatch.wgsl`).
+fn dispatch_for(workgroups: u32) -> DispatchWorkgroups {
+    let y = max(div_ceil(workgroups, segment_sizes.max_workgroups), 1u);
+
+    return DispatchWorkgroups(div_ceil(workgroups, y), y, 1);
 }
 
 @compute @workgroup_size(1, 1, 1)
@@ -34,9 +38,9 @@ fn main() {
 
     let histogram_workgroups = div_ceil(count, segment_sizes.histogram);
 
-    histogram_dispatch = DispatchWorkgroups(histogram_workgroups, 1, 1);
+    histogram_dispatch = dispatch_for(histogram_workgroups);
 
     let scatter_workgroups = div_ceil(count, segment_sizes.scatter);
 
-    scatter_dispatch = DispatchWorkgroups(scatter_workgroups, 1, 1);
+    scatter_dispatch = dispatch_for(scatter_workgroups);
 }
@@ -0,0 +1,5 @@
+#include "../../dispatch.wgsl"
+
+alias DATA_TYPE = f32;
+
+#include "shader_core.wgsl"
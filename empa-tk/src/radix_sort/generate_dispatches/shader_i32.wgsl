@@ -0,0 +1,5 @@
+#include "../../dispatch.wgsl"
+
+alias DATA_TYPE = i32;
+
+#include "shader_core.wgsl"
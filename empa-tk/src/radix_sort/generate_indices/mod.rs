@@ -0,0 +1,82 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer;
+use empa::buffer::Storage;
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+
+use crate::util::div_ceil;
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    indices: Storage<'a, [u32], ReadWrite>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+/// Fills a buffer with the sequence `0, 1, 2, ...`.
+pub struct GenerateIndices {
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: ComputePipeline<(ResourcesLayout,)>,
+}
+
+impl GenerateIndices {
+    pub async fn init(device: Device) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        GenerateIndices {
+            device,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    pub fn encode<U0>(
+        &mut self,
+        encoder: CommandEncoder,
+        indices: buffer::View<[u32], U0>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+    {
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                indices: indices.storage(),
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: div_ceil(indices.len() as u32, GROUP_SIZE),
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
@@ -0,0 +1,11 @@
+@group(0) @binding(0)
+var<storage, read_write> indices: array<u32>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+
+    if index < arrayLength(&indices) {
+        indices[index] = index;
+    }
+}
@@ -1,6 +1,6 @@
 use empa::access_mode::ReadWrite;
 use empa::buffer;
-use empa::buffer::Storage;
+use empa::buffer::{Buffer, Storage};
 use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
 use empa::compute_pipeline::{
     ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
@@ -8,6 +8,7 @@ use empa::compute_pipeline::{
 use empa::device::Device;
 use empa::resource_binding::BindGroupLayout;
 use empa::shader_module::{shader_source, ShaderSource};
+use empa::type_flag::{O, X};
 
 use crate::radix_sort::{RADIX_DIGITS, RADIX_GROUPS};
 
@@ -17,6 +18,8 @@ const SHADER: ShaderSource = shader_source!("shader.wgsl");
 struct Resources<'a> {
     #[resource(binding = 0, visibility = "COMPUTE")]
     global_data: Storage<'a, [[u32; RADIX_DIGITS]; RADIX_GROUPS], ReadWrite>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    digit_counts: Storage<'a, [u32; RADIX_GROUPS], ReadWrite>,
 }
 
 type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
@@ -25,6 +28,7 @@ pub struct GlobalBucketOffsets {
     device: Device,
     bind_group_layout: BindGroupLayout<ResourcesLayout>,
     pipeline: ComputePipeline<(ResourcesLayout,)>,
+    digit_counts: Buffer<[u32; RADIX_GROUPS], buffer::Usages<O, O, X, O, O, O, O, X, O, O>>,
 }
 
 impl GlobalBucketOffsets {
@@ -43,13 +47,24 @@ impl GlobalBucketOffsets {
             )
             .await;
 
+        let digit_counts =
+            device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_src());
+
         GlobalBucketOffsets {
             device,
             bind_group_layout,
             pipeline,
+            digit_counts,
         }
     }
 
+    /// The number of non-empty buckets in each radix group, as counted by the most recent encode.
+    pub fn digit_counts(
+        &self,
+    ) -> buffer::View<[u32; RADIX_GROUPS], buffer::Usages<O, O, X, O, O, O, O, X, O, O>> {
+        self.digit_counts.view()
+    }
+
     pub fn encode<U0>(
         &mut self,
         encoder: CommandEncoder,
@@ -62,6 +77,7 @@ impl GlobalBucketOffsets {
             &self.bind_group_layout,
             Resources {
                 global_data: global_data.storage(),
+                digit_counts: self.digit_counts.storage(),
             },
         );
 
@@ -7,18 +7,33 @@ const RADIX_GROUPS = 4u; //32 / RADIX_SIZE;
 @group(0) @binding(0)
 var<storage, read_write> global_data: array<array<u32, RADIX_DIGITS>, RADIX_GROUPS>;
 
+@group(0) @binding(1)
+var<storage, read_write> digit_counts: array<u32, RADIX_GROUPS>;
+
 var<workgroup> local_data: array<u32, GROUP_SIZE>;
 
+var<workgroup> non_empty_buckets: atomic<u32>;
+
 @compute @workgroup_size(256, 1, 1)
 fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
     let group_index = workgroup_id.x;
 
     if local_index < RADIX_DIGITS {
-        local_data[local_index] = global_data[group_index][local_index];
+        let bucket_size = global_data[group_index][local_index];
+
+        local_data[local_index] = bucket_size;
+
+        if bucket_size != 0 {
+            atomicAdd(&non_empty_buckets, 1u);
+        }
     }
 
     workgroupBarrier();
 
+    if local_index == 0 {
+        digit_counts[group_index] = atomicLoad(&non_empty_buckets);
+    }
+
     for (var i = 1u; i < GROUP_SIZE; i <<= 1u) {
         var value: u32;
 
@@ -0,0 +1,86 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer;
+use empa::buffer::Storage;
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+
+use crate::util::div_ceil;
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    permutation: Storage<'a, [u32]>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    inverse: Storage<'a, [u32], ReadWrite>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+/// Writes the inverse of a permutation of indices: `inverse[permutation[i]] = i`.
+pub struct InvertPermutation {
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: ComputePipeline<(ResourcesLayout,)>,
+}
+
+impl InvertPermutation {
+    pub async fn init(device: Device) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        InvertPermutation {
+            device,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    pub fn encode<U>(
+        &mut self,
+        encoder: CommandEncoder,
+        permutation: buffer::View<[u32], U>,
+        inverse: Storage<[u32], ReadWrite>,
+    ) -> CommandEncoder
+    where
+        U: buffer::StorageBinding,
+    {
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                permutation: permutation.storage(),
+                inverse,
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: div_ceil(permutation.len() as u32, GROUP_SIZE),
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
@@ -0,0 +1,25 @@
+alias DATA_TYPE = f32;
+
+// As for the regular `f32` keys, but `-0.0` is mapped to the same key as `+0.0`, so that both zeros compare equal
+// and a stable sort preserves their relative input order.
+fn to_key(value: f32) -> u32 {
+    var bits = bitcast<u32>(value);
+
+    if bits == 0x80000000u {
+        bits = 0u;
+    }
+
+    if (bits & 0x80000000u) != 0 {
+        return ~bits;
+    } else {
+        return bits | 0x80000000u;
+    }
+}
+
+fn from_key(key: u32) -> f32 {
+    if (key & 0x80000000u) != 0 {
+        return bitcast<f32>(key & 0x7FFFFFFFu);
+    } else {
+        return bitcast<f32>(~key);
+    }
+}
@@ -0,0 +1,10 @@
+alias DATA_TYPE = i32;
+
+// Flip the sign bit, so that negative values order before positive values.
+fn to_key(value: i32) -> u32 {
+    return bitcast<u32>(value) ^ 0x80000000u;
+}
+
+fn from_key(key: u32) -> i32 {
+    return bitcast<i32>(key ^ 0x80000000u);
+}
@@ -0,0 +1,9 @@
+alias DATA_TYPE = u32;
+
+fn to_key(value: u32) -> u32 {
+    return value;
+}
+
+fn from_key(key: u32) -> u32 {
+    return key;
+}
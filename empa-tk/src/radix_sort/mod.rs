@@ -1,15 +1,200 @@
+use std::fmt::Write;
+#[cfg(feature = "value-types")]
+use std::mem;
+
+#[cfg(feature = "value-types")]
+use empa::device::Device;
+use empa::shader_module::ShaderSource;
+
+use crate::radix_key::sealed::KeyType;
+use crate::util::ShaderCode;
+#[cfg(feature = "value-types")]
+use crate::write_value_type::{write_value_type, ValueTypeError};
+
+#[cfg(feature = "value-types")]
+mod apply_permutation;
 mod bucket_histogram;
 mod bucket_scatter;
+#[cfg(feature = "value-types")]
 mod bucket_scatter_by;
+#[cfg(feature = "value-types")]
+mod bucket_scatter_multi_word;
+mod check_sorted;
+mod copy_back;
 mod generate_dispatches;
+#[cfg(feature = "value-types")]
+mod generate_indices;
 mod global_bucket_offsets;
+#[cfg(feature = "value-types")]
+mod invert_permutation;
+mod partition_offsets;
+mod resolve_bounds;
+
+pub(crate) use self::bucket_scatter::GroupState as BucketGroupState;
+#[cfg(feature = "value-types")]
+pub use self::bucket_scatter_by::BUCKET_SCATTER_BY_SEGMENT_SIZE;
 
 mod radix_sort;
 pub use self::radix_sort::*;
 
+mod radix_sort_fixed_size;
+pub use self::radix_sort_fixed_size::*;
+
+#[cfg(feature = "value-types")]
 mod radix_sort_by;
+#[cfg(feature = "value-types")]
 pub use self::radix_sort_by::*;
 
+#[cfg(feature = "value-types")]
+mod radix_sort_and_gather;
+#[cfg(feature = "value-types")]
+pub use self::radix_sort_and_gather::*;
+
+#[cfg(feature = "value-types")]
+mod radix_sort_multi_word;
+#[cfg(feature = "value-types")]
+pub use self::radix_sort_multi_word::*;
+
+#[cfg(feature = "value-types")]
+mod radix_sort_by_key;
+#[cfg(feature = "value-types")]
+pub use self::radix_sort_by_key::*;
+
+mod radix_sort_packed_16;
+pub use self::radix_sort_packed_16::*;
+
 const RADIX_SIZE: u32 = 8;
-const RADIX_DIGITS: usize = 256;
+pub(crate) const RADIX_DIGITS: usize = 256;
 const RADIX_GROUPS: usize = 4;
+
+const DEFAULT_GROUP_SIZE: u32 = 256;
+const DEFAULT_VALUES_PER_THREAD: u32 = 4;
+
+/// The number of values each workgroup processes in the histogram pass of a [RadixSort] or
+/// [RadixSortBy] with the default configuration.
+pub const BUCKET_HISTOGRAM_SEGMENT_SIZE: u32 = DEFAULT_GROUP_SIZE * DEFAULT_VALUES_PER_THREAD;
+
+/// The number of values each workgroup processes in the scatter passes of a [RadixSort] with the
+/// default configuration.
+///
+/// A sort over `n` values keeps per-workgroup state for `n.div_ceil(BUCKET_SCATTER_SEGMENT_SIZE)`
+/// workgroups.
+pub const BUCKET_SCATTER_SEGMENT_SIZE: u32 = DEFAULT_GROUP_SIZE * DEFAULT_VALUES_PER_THREAD;
+
+const KEY_U32: &str = include_str!("key_u32.wgsl");
+const KEY_I32: &str = include_str!("key_i32.wgsl");
+const KEY_F32: &str = include_str!("key_f32.wgsl");
+#[cfg(feature = "value-types")]
+const KEY_F32_ZERO_EQUAL: &str = include_str!("key_f32_zero_equal.wgsl");
+
+/// Prepends the key type definitions and the workgroup configuration to a shader template.
+fn shader_code(
+    template: &str,
+    key_type: KeyType,
+    group_size: u32,
+    values_per_thread: u32,
+) -> String {
+    let key_source = match key_type {
+        KeyType::U32 => KEY_U32,
+        KeyType::I32 => KEY_I32,
+        KeyType::F32 => KEY_F32,
+    };
+
+    key_source_shader_code(template, key_source, group_size, values_per_thread)
+}
+
+/// Selects the `default` shader for the default workgroup configuration, or otherwise generates the
+/// shader code for the custom configuration from the `template`.
+fn configured_shader(
+    default: &'static ShaderSource,
+    template: &str,
+    key_type: KeyType,
+    group_size: u32,
+    values_per_thread: u32,
+) -> ShaderCode {
+    if group_size == DEFAULT_GROUP_SIZE && values_per_thread == DEFAULT_VALUES_PER_THREAD {
+        ShaderCode::Validated(default)
+    } else {
+        ShaderCode::Template(shader_code(
+            template,
+            key_type,
+            group_size,
+            values_per_thread,
+        ))
+    }
+}
+
+/// Prepends the key definitions in `key_source` (`DATA_TYPE`, `to_key` and `from_key`) and the
+/// workgroup configuration to a shader template.
+fn key_source_shader_code(
+    template: &str,
+    key_source: &str,
+    group_size: u32,
+    values_per_thread: u32,
+) -> String {
+    let mut code = String::new();
+
+    write!(
+        code,
+        "{}\n\
+        const GROUP_SIZE = {}u;\n\
+        const VALUES_PER_THREAD = {}u;\n\n\
+        {}",
+        key_source, group_size, values_per_thread, template
+    )
+    .unwrap();
+
+    code
+}
+
+/// The number of `u32` words in a multi-word key of type `K`.
+#[cfg(feature = "value-types")]
+fn key_word_count<K>() -> usize {
+    mem::size_of::<K>() / 4
+}
+
+/// Prepends the definitions for sorting by the `word`-th `u32` word of a multi-word key of type
+/// `K`, and the workgroup configuration, to a shader template.
+#[cfg(feature = "value-types")]
+fn multi_word_shader_code<K>(
+    device: &Device,
+    template: &str,
+    word: usize,
+    group_size: u32,
+    values_per_thread: u32,
+) -> Result<String, ValueTypeError> {
+    let to_key = format!(
+        "fn to_key(value: VALUE_TYPE) -> u32 {{ return value.field_{}; }}",
+        word
+    );
+
+    value_key_shader_code::<K>(device, template, &to_key, group_size, values_per_thread)
+}
+
+/// Prepends the definitions for sorting values of type `V` by the `u32` key that the `to_key` WGSL
+/// function derives from each value, and the workgroup configuration, to a shader template.
+#[cfg(feature = "value-types")]
+fn value_key_shader_code<V>(
+    device: &Device,
+    template: &str,
+    to_key: &str,
+    group_size: u32,
+    values_per_thread: u32,
+) -> Result<String, ValueTypeError> {
+    let mut code = String::new();
+
+    write_value_type::<V>(device, &mut code)?;
+
+    write!(
+        code,
+        "alias DATA_TYPE = VALUE_TYPE;\n\n\
+        {}\n\n\
+        const GROUP_SIZE = {}u;\n\
+        const VALUES_PER_THREAD = {}u;\n\n\
+        {}",
+        to_key, group_size, values_per_thread, template
+    )
+    .unwrap();
+
+    Ok(code)
+}
@@ -0,0 +1,45 @@
+// `SIGNED` is prepended when the shader is initialized.
+//
+// Each packed word holds two 16-bit keys: the key at the even index in its lower half, the key at the odd index in
+// its upper half. Signed keys have their sign bit flipped while unpacked, so that they order correctly as unsigned
+// keys.
+const GROUP_SIZE = 256u;
+
+const SIGN_FLIP = select(0u, 0x8000u, SIGNED);
+
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<storage, read_write> packed: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read_write> unpacked: array<u32>;
+
+@compute @workgroup_size(GROUP_SIZE, 1, 1)
+fn unpack(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+
+    if index >= count {
+        return;
+    }
+
+    let word = packed[index];
+
+    unpacked[2 * index] = (word & 0xFFFFu) ^ SIGN_FLIP;
+    unpacked[2 * index + 1] = (word >> 16u) ^ SIGN_FLIP;
+}
+
+@compute @workgroup_size(GROUP_SIZE, 1, 1)
+fn pack(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+
+    if index >= count {
+        return;
+    }
+
+    let lower = (unpacked[2 * index] ^ SIGN_FLIP) & 0xFFFFu;
+    let upper = (unpacked[2 * index + 1] ^ SIGN_FLIP) & 0xFFFFu;
+
+    packed[index] = lower | (upper << 16u);
+}
@@ -0,0 +1,97 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer;
+use empa::buffer::{Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+
+use crate::radix_sort::{RADIX_DIGITS, RADIX_GROUPS};
+use crate::uniform_cache::UniformCache;
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    high_bits: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    global_bucket_offsets: Storage<'a, [[u32; RADIX_DIGITS]; RADIX_GROUPS]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    bucket_offsets: Storage<'a, [u32], ReadWrite>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+/// Resolves the offsets of the buckets of the top bits of the keys from the global bucket offsets
+/// of the most significant radix group.
+pub struct PartitionOffsets {
+    device: Device,
+    high_bits: UniformCache<u32>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: ComputePipeline<(ResourcesLayout,)>,
+}
+
+impl PartitionOffsets {
+    pub async fn init(device: Device) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        let high_bits = UniformCache::new(&device, 0);
+
+        PartitionOffsets {
+            device,
+            high_bits,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    pub fn encode<U0, U1>(
+        &mut self,
+        encoder: CommandEncoder,
+        high_bits: u32,
+        global_bucket_offsets: buffer::View<[[u32; RADIX_DIGITS]; RADIX_GROUPS], U0>,
+        bucket_offsets: buffer::View<[u32], U1>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        self.high_bits.update(&self.device, high_bits);
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                high_bits: self.high_bits.uniform(),
+                global_bucket_offsets: global_bucket_offsets.storage(),
+                bucket_offsets: bucket_offsets.storage(),
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
@@ -0,0 +1,24 @@
+const RADIX_SIZE = 8u;
+
+const RADIX_DIGITS = 256u;
+const RADIX_GROUPS = 4u;
+
+@group(0) @binding(0)
+var<uniform> high_bits: u32;
+
+@group(0) @binding(1)
+var<storage, read> global_bucket_offsets: array<array<u32, RADIX_DIGITS>, RADIX_GROUPS>;
+
+@group(0) @binding(2)
+var<storage, read_write> bucket_offsets: array<u32>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(local_invocation_index) local_index: u32) {
+    // A bucket of the top `high_bits` bits starts where the first digit of the most significant radix group that
+    // shares those bits starts
+    if local_index < (1u << high_bits) {
+        let digit = local_index << (RADIX_SIZE - high_bits);
+
+        bucket_offsets[local_index] = global_bucket_offsets[RADIX_GROUPS - 1][digit];
+    }
+}
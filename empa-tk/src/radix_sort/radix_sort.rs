@@ -1,50 +1,242 @@
-use std::future::join;
+use std::ops::Range;
+use std::{marker, mem};
 
-use empa::buffer::{Buffer, Uniform};
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage};
 use empa::command::{CommandEncoder, DispatchWorkgroups};
 use empa::device::Device;
 use empa::type_flag::{O, X};
 use empa::{abi, buffer};
+use futures::join;
 
+use crate::cost_estimate::CostEstimate;
+use crate::count::Count;
 use crate::count_buffer::CountBuffer;
-use crate::radix_sort::bucket_histogram::{
-    BucketHistogram, BucketHistogramResources, BUCKET_HISTOGRAM_SEGMENT_SIZE,
-};
-use crate::radix_sort::bucket_scatter::{
-    BucketScatter, BucketScatterInput, BUCKET_SCATTER_SEGMENT_SIZE,
-};
+use crate::profiler::Profiler;
+use crate::radix_key::RadixKey;
+use crate::radix_sort::bucket_histogram::{BucketHistogram, BucketHistogramResources};
+use crate::radix_sort::bucket_scatter::{BucketScatter, BucketScatterInput};
+use crate::radix_sort::check_sorted::{CheckSorted, CheckSortedInput};
+use crate::radix_sort::copy_back::{CopyBack, CopyBackResources};
 use crate::radix_sort::generate_dispatches::{
     GenerateDispatches, GenerateDispatchesResources, SegmentSizes,
 };
 use crate::radix_sort::global_bucket_offsets::GlobalBucketOffsets;
-use crate::radix_sort::{RADIX_DIGITS, RADIX_GROUPS};
+use crate::radix_sort::partition_offsets::PartitionOffsets;
+use crate::radix_sort::resolve_bounds::{ResolveBounds, ResolveBoundsResources};
+use crate::radix_sort::{
+    BucketGroupState, DEFAULT_GROUP_SIZE, DEFAULT_VALUES_PER_THREAD, RADIX_DIGITS, RADIX_GROUPS,
+    RADIX_SIZE,
+};
+use crate::requirements::Requirements;
+use crate::scratch_pool::ScratchPool;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+
+const SCATTER_STAGES: [&str; RADIX_GROUPS] = ["scatter_0", "scatter_1", "scatter_2", "scatter_3"];
 
 pub struct RadixSortInput<'a, T, U0, U1> {
     pub data: buffer::View<'a, [T], U0>,
+    /// Storage for the intermediate results of the sort; must hold at least as many elements as
+    /// the number of values to sort.
+    ///
+    /// Without a `count`, this is the length of `data`. With a [Count::Static] count, this is the
+    /// count, so that a large, partially filled `data` buffer does not require an equally large
+    /// temporary buffer. With a [Count::Indirect] count, the count is not known on the host, so
+    /// this is again the length of `data`.
     pub temporary_storage: buffer::View<'a, [T], U1>,
-    pub count: Option<Uniform<'a, u32>>,
+    /// The number of values to sort, if not all of `data` is to be sorted; see [Count].
+    ///
+    /// Only the values in `data[..count]` are read and written: the values beyond the count do
+    /// not contribute to the sort and are preserved verbatim.
+    pub count: Option<Count<'a>>,
+}
+
+pub struct RadixSortPartitionInput<'a, T, U> {
+    pub data: buffer::View<'a, [T], U>,
+    /// The number of values to partition, if not all of `data` is to be partitioned; see [Count].
+    pub count: Option<Count<'a>>,
 }
 
+pub struct RadixSortPartitionOutput<'a, T, U0, U1> {
+    /// Receives the values, grouped by the top bits of their keys in ascending order; must hold
+    /// at least as many elements as the number of values to partition.
+    pub data: buffer::View<'a, [T], U0>,
+    /// Receives the index at which each bucket starts in the output `data`, where bucket `b`
+    /// holds the values with `b` as the top bits of their keys; must hold at least
+    /// `1 << high_bits` elements.
+    pub bucket_offsets: buffer::View<'a, [u32], U1>,
+}
+
+/// Sorts values in place by their radix keys.
+///
+/// The same sort may be encoded several times into one command buffer, e.g. to sort several
+/// buffers in a single submission. The encoded sorts execute in the order in which they are
+/// encoded, each sort clears the internal state it uses before it runs, and the parameters of each
+/// sort are held in their own uniform buffers, so a sort does not clobber the state of the sorts
+/// encoded before it. Only results the sort retains internally, such as the
+/// [digit_counts](Self::digit_counts), reflect just the last sort that executed.
 pub struct RadixSort<T>
 where
     T: abi::Sized,
 {
     device: Device,
+    fallback_count: UniformCache<u32>,
     generate_dispatches: GenerateDispatches<T>,
     bucket_histogram: BucketHistogram<T>,
     global_bucket_offsets: GlobalBucketOffsets,
     bucket_scatter: BucketScatter<T>,
+    partition_offsets: PartitionOffsets,
+    resolve_bounds: ResolveBounds<T>,
+    check_sorted: CheckSorted<T>,
+    copy_back: CopyBack<T>,
     global_bucket_data:
         Buffer<[[u32; RADIX_DIGITS]; RADIX_GROUPS], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
     segment_sizes: Buffer<SegmentSizes, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
     histogram_dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
     scatter_dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+    temporary_storage: Option<Buffer<[T], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>>,
 }
 
 impl<T> RadixSort<T>
 where
     T: abi::Sized + 'static,
 {
+    /// The device limits a radix sort with the default configuration requires; see
+    /// [RadixSortBuilder::requirements] for a custom configuration.
+    pub fn requirements() -> Requirements {
+        scatter_requirements(DEFAULT_GROUP_SIZE, DEFAULT_VALUES_PER_THREAD)
+    }
+
+    /// A buffer holding the number of distinct digits (non-empty buckets) each radix group held in
+    /// the most recently encoded non-empty sort, where group `0` covers the least significant byte
+    /// of the keys (for `i32` and `f32` keys, of their order-preserving `u32` representation).
+    ///
+    /// Copy the buffer to a readback buffer after encoding a sort to inspect the digit
+    /// distribution of the keys; e.g. a group with a single distinct digit holds the same byte in
+    /// all keys. The buffer is overwritten by the next sort.
+    pub fn digit_counts(
+        &self,
+    ) -> buffer::View<[u32; RADIX_GROUPS], buffer::Usages<O, O, X, O, O, O, O, X, O, O>> {
+        self.global_bucket_offsets.digit_counts()
+    }
+
+    /// The number of values each workgroup processes in the histogram pass.
+    ///
+    /// Equals [BUCKET_HISTOGRAM_SEGMENT_SIZE](super::BUCKET_HISTOGRAM_SEGMENT_SIZE) unless configured
+    /// otherwise with a [RadixSortBuilder].
+    pub fn histogram_segment_size(&self) -> u32 {
+        self.bucket_histogram.segment_size()
+    }
+
+    /// The number of values each workgroup processes in the scatter passes.
+    ///
+    /// Equals [BUCKET_SCATTER_SEGMENT_SIZE](super::BUCKET_SCATTER_SEGMENT_SIZE) unless configured
+    /// otherwise with a [RadixSortBuilder].
+    pub fn scatter_segment_size(&self) -> u32 {
+        self.bucket_scatter.segment_size()
+    }
+
+    /// The number of workgroups the histogram pass dispatches to sort `count` values.
+    pub fn histogram_workgroups(&self, count: u32) -> u32 {
+        div_ceil(count, self.histogram_segment_size())
+    }
+
+    /// The number of workgroups each scatter pass dispatches to sort `count` values; this is also
+    /// the number of workgroups for which the sort keeps per-workgroup state.
+    pub fn scatter_workgroups(&self, count: u32) -> u32 {
+        div_ceil(count, self.scatter_segment_size())
+    }
+
+    /// Estimates the cost of sorting `count` values.
+    ///
+    /// The scratch memory includes the `temporary_storage` the sort requires, as well as the
+    /// per-workgroup state of the scatter passes.
+    pub fn plan_cost(&self, count: u32) -> CostEstimate {
+        if count == 0 {
+            return CostEstimate::default();
+        }
+
+        let temporary_storage_bytes = count as u64 * mem::size_of::<T>() as u64;
+        let group_state_bytes = self.scatter_workgroups(count) as u64
+            * RADIX_DIGITS as u64
+            * mem::size_of::<BucketGroupState>() as u64;
+
+        // A histogram pass and a global offsets dispatch, followed by a scatter pass per radix
+        // group
+        CostEstimate::new(
+            temporary_storage_bytes + group_state_bytes,
+            2 + RADIX_GROUPS as u32,
+            1 + RADIX_GROUPS as u32,
+        )
+    }
+
+    /// Allocates a zeroed buffer of `len` values that can be sorted in place.
+    ///
+    /// The input can be copied into the buffer and the sorted output can be copied out of it, e.g.
+    /// to a readback buffer.
+    pub fn sorted_output_buffer(
+        device: &Device,
+        len: usize,
+    ) -> Buffer<[T], buffer::Usages<O, O, X, O, O, O, X, X, O, O>> {
+        device.create_slice_buffer_zeroed(
+            len,
+            buffer::Usages::storage_binding()
+                .and_copy_dst()
+                .and_copy_src(),
+        )
+    }
+
+    /// Allocates a buffer that may serve as the [RadixSortInput::temporary_storage] for sorting up
+    /// to `len` values.
+    pub fn temporary_storage_buffer(
+        device: &Device,
+        len: usize,
+    ) -> Buffer<[T], buffer::Usages<O, O, X, O, O, O, O, O, O, O>> {
+        device.create_slice_buffer_zeroed(len, buffer::Usages::storage_binding())
+    }
+
+    /// Shrinks the per-workgroup state the sort retains to what sorting up to `max_count` values
+    /// requires; see [PrefixSum::shrink_to_fit](crate::prefix_sum::PrefixSum::shrink_to_fit).
+    ///
+    /// Also releases the internal temporary storage of
+    /// [encode_with_internal_storage](Self::encode_with_internal_storage) if it holds more than
+    /// `max_count` values.
+    pub fn shrink_to_fit(&mut self, max_count: u32) {
+        self.bucket_scatter.shrink_to_fit(max_count);
+
+        if let Some(temporary_storage) = &self.temporary_storage {
+            if temporary_storage.len() > max_count as usize {
+                self.temporary_storage = None;
+            }
+        }
+    }
+
+    /// Grows the per-workgroup state the sort retains to what sorting up to `max_count` values
+    /// requires; see [PrefixSum::reserve](crate::prefix_sum::PrefixSum::reserve).
+    pub fn reserve(&mut self, max_count: u32) {
+        self.bucket_scatter.reserve(max_count);
+    }
+
+    /// Encodes commands that zero the sort's persistent internal buffers.
+    ///
+    /// Each encoded sort already clears the state it uses before it runs; this makes the reset
+    /// explicit, e.g. to guarantee that no state from a preceding, unrelated sort recorded in the
+    /// same command buffer remains in the buffers. The [digit_counts](Self::digit_counts) of the
+    /// preceding sort are retained.
+    ///
+    /// Unlike [PrefixSum::encode_after_reset](crate::prefix_sum::PrefixSum::encode_after_reset),
+    /// there is no variant of the sort that skips its state clears after a reset: every scatter
+    /// pass of a sort reuses the same group state, so that state must be cleared again before each
+    /// pass regardless, and a reset could only replace the clears ahead of the first pass.
+    pub fn reset(&mut self, mut encoder: CommandEncoder) -> CommandEncoder {
+        encoder = encoder.clear_buffer(self.global_bucket_data.view());
+        encoder = self.bucket_histogram.reset(encoder);
+        encoder = self.bucket_scatter.reset(encoder);
+
+        self.check_sorted.reset(encoder)
+    }
+
     pub fn encode<U0, U1>(
         &mut self,
         encoder: CommandEncoder,
@@ -54,28 +246,296 @@ where
         U0: buffer::StorageBinding,
         U1: buffer::StorageBinding,
     {
-        self.encode_internal(encoder, input, 4)
+        self.encode_internal(encoder, input, 0..RADIX_GROUPS, None, None, None, None)
     }
 
-    fn encode_internal<U0, U1>(
+    /// Sorts all of `data`, using temporary storage that the sort allocates and retains internally
+    /// rather than a caller-provided [RadixSortInput::temporary_storage].
+    ///
+    /// The internal storage grows as needed to hold `data`; see [shrink_to_fit](Self::shrink_to_fit)
+    /// to release it after sorting a large input.
+    pub fn encode_with_internal_storage<U>(
         &mut self,
-        mut encoder: CommandEncoder,
+        encoder: CommandEncoder,
+        data: buffer::View<[T], U>,
+    ) -> CommandEncoder
+    where
+        U: buffer::StorageBinding,
+    {
+        let len = data.len().max(1);
+
+        let temporary_storage = match self.temporary_storage.take() {
+            Some(temporary_storage) if temporary_storage.len() >= len => temporary_storage,
+            _ => Self::temporary_storage_buffer(&self.device, len),
+        };
+
+        let encoder = self.encode_internal(
+            encoder,
+            RadixSortInput {
+                data,
+                temporary_storage: temporary_storage.view(),
+                count: None,
+            },
+            0..RADIX_GROUPS,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        self.temporary_storage = Some(temporary_storage);
+
+        encoder
+    }
+
+    /// Encodes the sort, additionally writing the minimum and maximum of the values to sort to
+    /// `bounds[0]` and `bounds[1]` respectively, e.g. to set up the range of a subsequent
+    /// [Histogram](crate::histogram::Histogram).
+    ///
+    /// The bounds are found by the histogram pass of the sort, which already reads every value, so
+    /// this does not take an additional pass over the data. For `i32` and `f32` values, the bounds
+    /// follow the same ordering as the sort (so for `f32`, `-0.0` orders before `0.0`, and NaN
+    /// values with the sign bit set order before all other values). The bounds of an empty input
+    /// are left unspecified.
+    pub fn encode_with_bounds<U0, U1, U2>(
+        &mut self,
+        encoder: CommandEncoder,
         input: RadixSortInput<T, U0, U1>,
-        radix_groups: usize,
+        bounds: buffer::View<[T; 2], U2>,
     ) -> CommandEncoder
     where
         U0: buffer::StorageBinding,
         U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
     {
-        let RadixSortInput {
+        self.encode_internal(
+            encoder,
+            input,
+            0..RADIX_GROUPS,
+            None,
+            None,
+            Some(bounds.storage()),
+            None,
+        )
+    }
+
+    /// Encodes a check of whether the values in `data` are sorted, without sorting them.
+    ///
+    /// Writes `1` to `is_sorted` if the values are in non-decreasing order in the order the sort
+    /// uses (for `i32` and `f32` values, the order of their radix keys, so e.g. `-0.0` after `0.0`
+    /// is out of order), and `0` otherwise; an empty `data` is sorted. The check takes a single
+    /// pass over the data, e.g. to assert on the device that a preceding sort produced sorted
+    /// output without reading the output back.
+    pub fn verify_sorted<U0, U1>(
+        &mut self,
+        encoder: CommandEncoder,
+        data: buffer::View<[T], U0>,
+        is_sorted: buffer::View<u32, U1>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        let count = data.len() as u32;
+
+        self.fallback_count.update(&self.device, count);
+
+        // An empty input is trivially sorted
+        let data = if !is_empty_input(count as usize) {
+            Some(data.storage())
+        } else {
+            None
+        };
+
+        self.check_sorted.encode_verify(
+            encoder,
+            self.fallback_count.uniform(),
             data,
-            temporary_storage,
-            count,
-        } = input;
+            self.histogram_workgroups(count),
+            is_sorted.storage(),
+        )
+    }
+
+    /// Encodes the sort, preceded by a check of whether the values are already sorted, in which
+    /// case the sort's passes are skipped.
+    ///
+    /// The check writes `1` to `is_sorted` if the values are already in non-decreasing order (in
+    /// the order the sort uses), and `0` otherwise; the caller may read it back. The check takes a
+    /// single pass over the data, which is much cheaper than the sort itself, so this suits data
+    /// that is usually already sorted (e.g. data that is mostly appended to in order). The
+    /// `is_sorted` flag is left untouched for an empty input.
+    pub fn encode_unless_sorted<U0, U1, U2>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: RadixSortInput<T, U0, U1>,
+        is_sorted: buffer::View<u32, U2>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        self.encode_internal(
+            encoder,
+            input,
+            0..RADIX_GROUPS,
+            None,
+            None,
+            None,
+            Some(is_sorted.storage()),
+        )
+    }
+
+    /// Encodes the sort, using the temporary buffers in the `scratch_pool` rather than the sort's
+    /// own temporary buffers.
+    pub fn encode_with_scratch_pool<U0, U1>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: RadixSortInput<T, U0, U1>,
+        scratch_pool: &mut ScratchPool,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        self.encode_internal(
+            encoder,
+            input,
+            0..RADIX_GROUPS,
+            Some(scratch_pool),
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// Encodes the sort, recording a timestamp with the `profiler` at the start of the sort and
+    /// after each of its passes.
+    ///
+    /// The stages are named `"generate_dispatches"` (only if an explicit `count` is provided),
+    /// `"histogram"`, `"global_bucket_offsets"` and `"scatter_0"` through `"scatter_3"`; the sort
+    /// records up to 8 timestamps.
+    pub fn encode_with_profiler<U0, U1>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: RadixSortInput<T, U0, U1>,
+        profiler: &mut Profiler,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        self.encode_internal(
+            encoder,
+            input,
+            0..RADIX_GROUPS,
+            None,
+            Some(profiler),
+            None,
+            None,
+        )
+    }
+
+    /// Encodes only the scatter passes in the `passes` range of the sort, so that a long sort can
+    /// be split across multiple submissions, e.g. `0..2` in one submission and `2..4` in a later
+    /// one.
+    ///
+    /// A range that starts at the first pass also encodes the histogram pass. The bucket offsets
+    /// it resolves are retained by the sort for the later ranges, so encode all ranges with the
+    /// same `input` and do not encode any other sort with this instance in between. Each range
+    /// leaves its results in `data` (after an odd number of passes the values are copied back
+    /// from the `temporary_storage`), so the `temporary_storage` need not be preserved between
+    /// ranges. An empty range encodes nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `passes` range extends beyond the 4 passes of the sort.
+    pub fn encode_passes<U0, U1>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: RadixSortInput<T, U0, U1>,
+        passes: Range<usize>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        assert!(
+            passes.end <= RADIX_GROUPS,
+            "the sort only has {} passes",
+            RADIX_GROUPS
+        );
+
+        if passes.is_empty() {
+            return encoder;
+        }
+
+        self.encode_internal(encoder, input, passes, None, None, None, None)
+    }
+
+    /// Encodes a single most-significant-digit pass that groups the values by the top `high_bits`
+    /// bits of their keys (for `i32` and `f32` keys, of their order-preserving `u32`
+    /// representation), rather than fully sorting the values.
+    ///
+    /// The buckets are ordered by ascending key; the order within a bucket is the order of the
+    /// input (the pass is stable), so the values within a bucket are not sorted. This takes a
+    /// single histogram and scatter pass, compared to the 4 scatter passes of a full sort, and is
+    /// suited to e.g. binning values into tiles by the high bits of a Morton code.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `high_bits` is not in the range `1..=8`, if `output.data` holds fewer elements
+    /// than the number of values to partition, or if `output.bucket_offsets` holds fewer than
+    /// `1 << high_bits` elements.
+    pub fn encode_msd_partition<U0, U1, U2>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: RadixSortPartitionInput<T, U0>,
+        output: RadixSortPartitionOutput<T, U1, U2>,
+        high_bits: u32,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding + buffer::CopyDst + 'static,
+    {
+        let RadixSortPartitionInput { data, count } = input;
+        let RadixSortPartitionOutput {
+            data: data_out,
+            bucket_offsets,
+        } = output;
+
+        assert!(
+            (1..=RADIX_SIZE).contains(&high_bits),
+            "`high_bits` must be in the range `1..={}` (got {})",
+            RADIX_SIZE,
+            high_bits
+        );
+        assert!(
+            bucket_offsets.len() >= 1 << high_bits,
+            "`bucket_offsets` must hold at least `1 << high_bits` elements"
+        );
+
+        let (count, fallback_count) = Count::resolve(count, data.len() as u32);
+
+        if is_empty_input(fallback_count as usize) {
+            return encoder.clear_buffer_slice(bucket_offsets);
+        }
 
         let dispatch_indirect = count.is_some();
-        let fallback_count = data.len() as u32;
-        let count = CountBuffer::new(count, &self.device, fallback_count);
+
+        if !dispatch_indirect {
+            assert!(
+                data_out.len() as u32 >= fallback_count,
+                "the output `data` must hold at least as many elements as the number of values \
+                to partition ({})",
+                fallback_count
+            );
+        }
+
+        self.fallback_count.update(&self.device, fallback_count);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
 
         if dispatch_indirect {
             encoder = self.generate_dispatches.encode(
@@ -105,12 +565,141 @@ where
         encoder = self
             .global_bucket_offsets
             .encode(encoder, self.global_bucket_data.view());
+        encoder = self.bucket_scatter.encode(
+            encoder,
+            BucketScatterInput {
+                data_in: data,
+                data_out,
+                global_base_bucket_offsets: self.global_bucket_data.view(),
+                radix_group: RADIX_GROUPS as u32 - 1,
+                max_count: count.uniform(),
+                dispatch_indirect,
+                dispatch: self.scatter_dispatch.view(),
+                fallback_count,
+            },
+            None,
+        );
+
+        self.partition_offsets.encode(
+            encoder,
+            high_bits,
+            self.global_bucket_data.view(),
+            bucket_offsets,
+        )
+    }
+
+    fn encode_internal<U0, U1>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: RadixSortInput<T, U0, U1>,
+        passes: Range<usize>,
+        mut scratch_pool: Option<&mut ScratchPool>,
+        mut profiler: Option<&mut Profiler>,
+        bounds: Option<Storage<[T; 2], ReadWrite>>,
+        is_sorted: Option<Storage<u32, ReadWrite>>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        let RadixSortInput {
+            data,
+            temporary_storage,
+            count,
+        } = input;
+
+        let (count, fallback_count) = Count::resolve(count, data.len() as u32);
+
+        if is_empty_input(fallback_count as usize) {
+            return encoder;
+        }
+
+        // For an indirect count the fallback count is the length of the data, as the count read on
+        // the device may be as large as that
+        assert!(
+            temporary_storage.len() as u32 >= fallback_count,
+            "`temporary_storage` must hold at least as many elements as the number of values to \
+            sort ({})",
+            fallback_count
+        );
+
+        let dispatch_indirect = count.is_some();
+
+        self.fallback_count.update(&self.device, fallback_count);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        // Skipping the sort relies on zeroing the indirect dispatches of its passes, so the check
+        // always dispatches indirectly
+        let dispatch_indirect = dispatch_indirect || is_sorted.is_some();
+
+        encoder = Profiler::record(profiler.as_deref_mut(), encoder, "start");
+
+        if dispatch_indirect {
+            encoder = self.generate_dispatches.encode(
+                encoder,
+                GenerateDispatchesResources {
+                    segment_sizes: self.segment_sizes.uniform(),
+                    max_count: count.uniform(),
+                    data: data.storage(),
+                    histogram_dispatch: self.histogram_dispatch.storage(),
+                    scatter_dispatch: self.scatter_dispatch.storage(),
+                },
+            );
+            encoder = Profiler::record(profiler.as_deref_mut(), encoder, "generate_dispatches");
+        }
+
+        if let Some(is_sorted) = is_sorted {
+            encoder = self.check_sorted.encode(
+                encoder,
+                CheckSortedInput {
+                    max_count: count.uniform(),
+                    data: data.storage(),
+                    is_sorted,
+                    histogram_dispatch: self.histogram_dispatch.view(),
+                    scatter_dispatch: self.scatter_dispatch.view(),
+                },
+            );
+        }
+
+        // The bucket offsets of all passes are resolved from the histograms of the unsorted
+        // values; a range of passes that does not start at the first pass relies on the offsets
+        // retained from an earlier range
+        if passes.start == 0 {
+            encoder = encoder.clear_buffer(self.global_bucket_data.view());
+            encoder = self.bucket_histogram.encode(
+                encoder,
+                BucketHistogramResources {
+                    max_count: count.uniform(),
+                    data: data.storage(),
+                    global_histograms: self.global_bucket_data.storage(),
+                },
+                dispatch_indirect,
+                self.histogram_dispatch.view(),
+                fallback_count,
+            );
+            encoder = Profiler::record(profiler.as_deref_mut(), encoder, "histogram");
+
+            if let Some(bounds) = bounds {
+                encoder = self.resolve_bounds.encode(
+                    encoder,
+                    ResolveBoundsResources {
+                        key_bounds: self.bucket_histogram.key_bounds().storage(),
+                        bounds,
+                    },
+                );
+            }
+            encoder = self
+                .global_bucket_offsets
+                .encode(encoder, self.global_bucket_data.view());
+            encoder = Profiler::record(profiler.as_deref_mut(), encoder, "global_bucket_offsets");
+        }
 
         let data_a = data;
         let data_b = temporary_storage;
 
-        for i in 0..radix_groups {
-            if (i & 1) == 0 {
+        for i in passes.clone() {
+            if ((i - passes.start) & 1) == 0 {
                 encoder = self.bucket_scatter.encode(
                     encoder,
                     BucketScatterInput {
@@ -123,6 +712,7 @@ where
                         dispatch: self.scatter_dispatch.view(),
                         fallback_count,
                     },
+                    scratch_pool.as_deref_mut(),
                 );
             } else {
                 encoder = self.bucket_scatter.encode(
@@ -137,31 +727,250 @@ where
                         dispatch: self.scatter_dispatch.view(),
                         fallback_count,
                     },
+                    scratch_pool.as_deref_mut(),
                 );
             }
+
+            encoder = Profiler::record(profiler.as_deref_mut(), encoder, SCATTER_STAGES[i]);
+        }
+
+        // After an odd number of scatter passes, the sorted values are in the temporary storage
+        if (passes.len() & 1) == 1 {
+            encoder = self.copy_back.encode(
+                encoder,
+                CopyBackResources {
+                    max_count: count.uniform(),
+                    data_in: data_b.storage(),
+                    data_out: data_a.storage(),
+                },
+                dispatch_indirect,
+                self.scatter_dispatch.view(),
+                fallback_count,
+            );
         }
 
         encoder
     }
 }
 
+impl<T> RadixSort<T>
+where
+    T: RadixKey,
+{
+    pub async fn init(device: Device) -> Self {
+        RadixSortBuilder::new(device).finish().await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init(device))
+    }
+}
+
 impl RadixSort<u32> {
     pub async fn init_u32(device: Device) -> Self {
+        Self::init(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_u32(device))
+    }
+
+    pub fn encode_half_precision<U0, U1>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: RadixSortInput<u32, U0, U1>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        self.encode_internal(encoder, input, 0..2, None, None, None, None)
+    }
+
+    /// Encodes the sort of values that are known not to exceed `max_value`, skipping the passes
+    /// for the radix digits that are zero for all such values.
+    ///
+    /// The sort takes `ceil(log2(max_value + 1) / 8)` passes rather than `4`, e.g. only `3` passes
+    /// for indices into an array of up to `2^24` elements. After an odd number of passes, the
+    /// sorted values are copied back from the `temporary_storage`, so that the result is always
+    /// in the `data`. Values that exceed `max_value` are not sorted correctly.
+    pub fn encode_with_max_value<U0, U1>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: RadixSortInput<u32, U0, U1>,
+        max_value: u32,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        let significant_bits = u32::BITS - max_value.leading_zeros();
+        let radix_groups = div_ceil(significant_bits, RADIX_SIZE) as usize;
+
+        // All values are `0`, and thus already sorted
+        if radix_groups == 0 {
+            return encoder;
+        }
+
+        self.encode_internal(encoder, input, 0..radix_groups, None, None, None, None)
+    }
+}
+
+impl RadixSort<i32> {
+    pub async fn init_i32(device: Device) -> Self {
+        Self::init(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_i32(device))
+    }
+}
+
+impl RadixSort<f32> {
+    pub async fn init_f32(device: Device) -> Self {
+        Self::init(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_f32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_f32(device))
+    }
+}
+
+/// Builds a [RadixSort] with a custom workgroup configuration.
+///
+/// The defaults match the configuration used by [RadixSort::init]: a group size of `256` with `4`
+/// values per thread. Smaller groups may perform better on devices with narrower SIMD units (e.g.
+/// many mobile GPUs).
+pub struct RadixSortBuilder<T> {
+    device: Device,
+    group_size: u32,
+    values_per_thread: u32,
+    max_count: u32,
+    _marker: marker::PhantomData<T>,
+}
+
+impl<T> RadixSortBuilder<T>
+where
+    T: RadixKey,
+{
+    /// The device limits the radix sort requires with the current configuration.
+    pub fn requirements(&self) -> Requirements {
+        scatter_requirements(self.group_size, self.values_per_thread)
+    }
+
+    pub fn new(device: Device) -> Self {
+        RadixSortBuilder {
+            device,
+            group_size: DEFAULT_GROUP_SIZE,
+            values_per_thread: DEFAULT_VALUES_PER_THREAD,
+            max_count: 0,
+            _marker: marker::PhantomData,
+        }
+    }
+
+    /// The number of invocations in each workgroup.
+    pub fn group_size(mut self, group_size: u32) -> Self {
+        self.group_size = group_size;
+
+        self
+    }
+
+    /// The number of values each invocation processes.
+    pub fn values_per_thread(mut self, values_per_thread: u32) -> Self {
+        self.values_per_thread = values_per_thread;
+
+        self
+    }
+
+    /// The number of values the sort is expected to sort at most; see [RadixSort::reserve].
+    ///
+    /// Not a limit: larger inputs may still be sorted, at the cost of growing the sort's internal
+    /// state on their first encounter.
+    pub fn max_count(mut self, max_count: u32) -> Self {
+        self.max_count = max_count;
+
+        self
+    }
+
+    /// Initializes the radix sort.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group size is `0` or exceeds the device's
+    /// `max_compute_invocations_per_workgroup` limit, or if the number of values per thread is `0`.
+    ///
+    /// Panics if a segment (`group_size * values_per_thread` values) holds fewer values than there
+    /// are radix digits (`256`), or if a segment does not fit in workgroup memory.
+    pub async fn finish(self) -> RadixSort<T> {
+        let RadixSortBuilder {
+            device,
+            group_size,
+            values_per_thread,
+            max_count,
+            ..
+        } = self;
+
+        let limits = device.limits();
+
+        assert!(
+            group_size > 0 && group_size <= limits.max_compute_invocations_per_workgroup,
+            "`group_size` must be in the range `1..={}` (got {})",
+            limits.max_compute_invocations_per_workgroup,
+            group_size
+        );
+        assert!(values_per_thread > 0, "`values_per_thread` must not be `0`");
+
+        let segment_size = group_size * values_per_thread;
+
+        assert!(
+            segment_size >= RADIX_DIGITS as u32,
+            "`group_size * values_per_thread` must be at least {} (got {})",
+            RADIX_DIGITS,
+            segment_size
+        );
+
+        // The scatter pass stores the segment and a workspace of the same size in workgroup
+        // memory, alongside 1 additional 4-byte value
+        let workgroup_storage_size = (segment_size * 2 + 1) * 4;
+
+        assert!(
+            workgroup_storage_size <= limits.max_compute_workgroup_storage_size,
+            "a segment of {} values does not fit in workgroup memory",
+            segment_size
+        );
+
         let global_bucket_data =
             device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_dst());
 
-        let (generate_dispatches, bucket_histogram, global_bucket_offsets, bucket_scatter) = join!(
+        let (
+            generate_dispatches,
+            bucket_histogram,
+            global_bucket_offsets,
+            bucket_scatter,
+            partition_offsets,
+            resolve_bounds,
+            check_sorted,
+            copy_back,
+        ) = join!(
             GenerateDispatches::init(device.clone()),
-            BucketHistogram::init_u32(device.clone()),
+            BucketHistogram::init(device.clone(), group_size, values_per_thread),
             GlobalBucketOffsets::init(device.clone()),
-            BucketScatter::init_u32(device.clone()),
-        )
-        .await;
+            BucketScatter::init(device.clone(), group_size, values_per_thread),
+            PartitionOffsets::init(device.clone()),
+            ResolveBounds::init(device.clone()),
+            CheckSorted::init(device.clone(), group_size, values_per_thread),
+            CopyBack::init(device.clone(), group_size, values_per_thread),
+        );
 
         let segment_sizes = device.create_buffer(
             SegmentSizes {
-                histogram: BUCKET_HISTOGRAM_SEGMENT_SIZE,
-                scatter: BUCKET_SCATTER_SEGMENT_SIZE,
+                histogram: bucket_histogram.segment_size(),
+                scatter: bucket_scatter.segment_size(),
+                max_workgroups: device.limits().max_compute_workgroups_per_dimension,
             },
             buffer::Usages::uniform_binding(),
         );
@@ -182,28 +991,41 @@ impl RadixSort<u32> {
             buffer::Usages::storage_binding().and_indirect(),
         );
 
-        RadixSort {
+        // Starting from the expected count means a first sort of that count does not recreate the
+        // uniform buffer
+        let fallback_count = UniformCache::new(&device, max_count);
+
+        let mut radix_sort = RadixSort {
             device,
+            fallback_count,
             generate_dispatches,
             bucket_histogram,
             global_bucket_offsets,
             bucket_scatter,
+            partition_offsets,
+            resolve_bounds,
+            check_sorted,
+            copy_back,
             global_bucket_data,
             segment_sizes,
             histogram_dispatch,
             scatter_dispatch,
-        }
+            temporary_storage: None,
+        };
+
+        radix_sort.reserve(max_count);
+
+        radix_sort
     }
 
-    pub fn encode_half_precision<U0, U1>(
-        &mut self,
-        encoder: CommandEncoder,
-        input: RadixSortInput<u32, U0, U1>,
-    ) -> CommandEncoder
-    where
-        U0: buffer::StorageBinding,
-        U1: buffer::StorageBinding,
-    {
-        self.encode_internal(encoder, input, 2)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn finish_blocking(self) -> RadixSort<T> {
+        pollster::block_on(self.finish())
     }
 }
+
+// The scatter pass dominates the sort's requirements: it stores the segment and a workspace of the
+// same size in workgroup memory, alongside 1 additional 4-byte value
+fn scatter_requirements(group_size: u32, values_per_thread: u32) -> Requirements {
+    Requirements::new(group_size, (group_size * values_per_thread * 2 + 1) * 4, 5)
+}
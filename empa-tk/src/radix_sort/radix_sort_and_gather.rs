@@ -0,0 +1,229 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::CommandEncoder;
+use empa::device::Device;
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count::Count;
+use crate::gather_by::{GatherBy, GatherByInput};
+use crate::radix_key::RadixKey;
+use crate::radix_sort::generate_indices::GenerateIndices;
+use crate::radix_sort::invert_permutation::InvertPermutation;
+use crate::radix_sort::{RadixSortBy, RadixSortByInput};
+use crate::requirements::Requirements;
+use crate::util::is_empty_input;
+use crate::write_value_type::ValueTypeError;
+
+pub struct RadixSortAndGatherInput<'a, V, U0, U1, K = u32> {
+    /// The keys to sort by; the keys are sorted in place.
+    pub keys: buffer::View<'a, [K], U0>,
+    /// The payload to gather into sorted order; must hold at least as many elements as `keys`.
+    pub payload: buffer::View<'a, [V], U1>,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// Sorts a payload of an arbitrary value type by a set of keys (`u32` keys unless specified
+/// otherwise).
+///
+/// Rather than moving the payload through every pass of the sort, the sort moves a permutation of
+/// element indices alongside the keys, after which the payload is gathered through the sorted
+/// permutation in a single pass. This saves bandwidth for large payload types. The index
+/// permutation and the other intermediate buffers are managed internally.
+pub struct RadixSortAndGather<V, K = u32>
+where
+    V: abi::Sized,
+    K: abi::Sized,
+{
+    device: Device,
+    generate_indices: GenerateIndices,
+    invert_permutation: InvertPermutation,
+    radix_sort_by: RadixSortBy<K, u32>,
+    gather_by: GatherBy<u32, V>,
+    indices: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    temporary_indices: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    temporary_keys: Buffer<[K], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+}
+
+impl<V, K> RadixSortAndGather<V, K>
+where
+    V: abi::Sized + 'static,
+    K: RadixKey,
+{
+    pub fn requirements() -> Requirements {
+        RadixSortBy::<K, u32>::requirements().max(GatherBy::<u32, V>::requirements())
+    }
+
+    pub async fn init(device: Device) -> Result<Self, ValueTypeError> {
+        let (generate_indices, invert_permutation, radix_sort_by, gather_by) = join!(
+            GenerateIndices::init(device.clone()),
+            InvertPermutation::init(device.clone()),
+            RadixSortBy::init(device.clone()),
+            GatherBy::init_u32(device.clone()),
+        );
+
+        let radix_sort_by = radix_sort_by?;
+        let gather_by = gather_by?;
+
+        let indices = device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+        let temporary_indices =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+        let temporary_keys =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+
+        Ok(RadixSortAndGather {
+            device,
+            generate_indices,
+            invert_permutation,
+            radix_sort_by,
+            gather_by,
+            indices,
+            temporary_indices,
+            temporary_keys,
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init(device))
+    }
+
+    /// Encodes the sort of the `input.keys`, and the gather of the `input.payload` into the
+    /// `output` in the sorted order of the keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input.payload` or `output` hold fewer elements than `input.keys`.
+    pub fn encode<U0, U1, U2>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: RadixSortAndGatherInput<V, U0, U1, K>,
+        output: buffer::View<[V], U2>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        self.encode_internal(encoder, input, output, None)
+    }
+
+    /// Encodes the sort and gather as for [encode](Self::encode), and additionally writes the
+    /// inverse of the sorted permutation to `inverse_permutation`: for each original position `i`,
+    /// `inverse_permutation[i]` receives the position in the sorted order to which the element at
+    /// `i` moved.
+    ///
+    /// The inverse is derived from the permutation the sort produced, so it is consistent with the
+    /// (stable) order of equal keys in the output. Positions beyond a `count` map to themselves.
+    /// Scatter values by the inverse permutation to return values in sorted order to the original
+    /// order of the keys, or use it as a position map to update references to the original
+    /// positions that are held elsewhere (e.g. slot ids stored in another table).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input.payload`, `output` or `inverse_permutation` hold fewer elements than
+    /// `input.keys`.
+    pub fn encode_with_inverse_permutation<U0, U1, U2, U3>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: RadixSortAndGatherInput<V, U0, U1, K>,
+        output: buffer::View<[V], U2>,
+        inverse_permutation: buffer::View<[u32], U3>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+        U3: buffer::StorageBinding,
+    {
+        assert!(
+            inverse_permutation.len() >= input.keys.len(),
+            "`inverse_permutation` must hold at least as many elements as `keys`"
+        );
+
+        // For an empty input there is no permutation to invert
+        let inverse_permutation = if !is_empty_input(input.keys.len()) {
+            Some(inverse_permutation.storage())
+        } else {
+            None
+        };
+
+        self.encode_internal(encoder, input, output, inverse_permutation)
+    }
+
+    fn encode_internal<U0, U1, U2>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: RadixSortAndGatherInput<V, U0, U1, K>,
+        output: buffer::View<[V], U2>,
+        inverse_permutation: Option<Storage<[u32], ReadWrite>>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        let RadixSortAndGatherInput {
+            keys,
+            payload,
+            count,
+        } = input;
+
+        assert!(
+            payload.len() >= keys.len(),
+            "`payload` must hold at least as many elements as `keys`"
+        );
+        assert!(
+            output.len() >= keys.len(),
+            "`output` must hold at least as many elements as `keys`"
+        );
+
+        if is_empty_input(keys.len()) {
+            return encoder;
+        }
+
+        // The index buffer must match the length of the keys exactly, as it also bounds the
+        // gather when the payload is longer than the keys
+        if self.indices.len() != keys.len() {
+            self.indices = self
+                .device
+                .create_slice_buffer_zeroed(keys.len(), self.indices.usage());
+            self.temporary_indices = self
+                .device
+                .create_slice_buffer_zeroed(keys.len(), self.temporary_indices.usage());
+            self.temporary_keys = self
+                .device
+                .create_slice_buffer_zeroed(keys.len(), self.temporary_keys.usage());
+        }
+
+        encoder = self.generate_indices.encode(encoder, self.indices.view());
+        encoder = self.radix_sort_by.encode(
+            encoder,
+            RadixSortByInput {
+                keys,
+                values: self.indices.view(),
+                temporary_key_storage: self.temporary_keys.view(),
+                temporary_value_storage: self.temporary_indices.view(),
+                count: count.clone().map(Count::Indirect),
+            },
+        );
+
+        if let Some(inverse_permutation) = inverse_permutation {
+            encoder =
+                self.invert_permutation
+                    .encode(encoder, self.indices.view(), inverse_permutation);
+        }
+
+        self.gather_by.encode(
+            encoder,
+            GatherByInput {
+                gather_by: self.indices.view(),
+                data: payload,
+                count,
+                index_transform: None,
+            },
+            output,
+        )
+    }
+}
@@ -1,15 +1,17 @@
-use std::future::join;
+use std::future::Future;
 
-use empa::buffer::{Buffer, Uniform};
+use empa::buffer::Buffer;
 use empa::command::{CommandEncoder, DispatchWorkgroups};
 use empa::device::Device;
 use empa::type_flag::{O, X};
 use empa::{abi, buffer};
+use futures::join;
 
+use crate::count::Count;
 use crate::count_buffer::CountBuffer;
-use crate::radix_sort::bucket_histogram::{
-    BucketHistogram, BucketHistogramResources, BUCKET_HISTOGRAM_SEGMENT_SIZE,
-};
+use crate::radix_key::RadixKey;
+use crate::radix_sort::apply_permutation::ApplyPermutation;
+use crate::radix_sort::bucket_histogram::{BucketHistogram, BucketHistogramResources};
 use crate::radix_sort::bucket_scatter_by::{
     BucketScatterBy, BucketScatterByInput, BUCKET_SCATTER_BY_SEGMENT_SIZE,
 };
@@ -17,26 +19,51 @@ use crate::radix_sort::generate_dispatches::{
     GenerateDispatches, GenerateDispatchesResources, SegmentSizes,
 };
 use crate::radix_sort::global_bucket_offsets::GlobalBucketOffsets;
-use crate::radix_sort::{RADIX_DIGITS, RADIX_GROUPS};
+use crate::radix_sort::{
+    DEFAULT_GROUP_SIZE, DEFAULT_VALUES_PER_THREAD, KEY_F32_ZERO_EQUAL, RADIX_DIGITS, RADIX_GROUPS,
+};
+use crate::requirements::Requirements;
+use crate::scratch_pool::ScratchPool;
+use crate::uniform_cache::UniformCache;
+use crate::util::is_empty_input;
+use crate::write_value_type::ValueTypeError;
 
 pub struct RadixSortByInput<'a, K, V, U0, U1, U2, U3> {
     pub keys: buffer::View<'a, [K], U0>,
+    /// The values to sort alongside the `keys`; must hold at least as many elements as the number
+    /// of keys to sort.
     pub values: buffer::View<'a, [V], U1>,
+    /// Storage for the intermediate keys; must hold at least as many elements as the number of
+    /// keys to sort.
     pub temporary_key_storage: buffer::View<'a, [K], U2>,
+    /// Storage for the intermediate values; must hold at least as many elements as the number of
+    /// keys to sort.
     pub temporary_value_storage: buffer::View<'a, [V], U3>,
-    pub count: Option<Uniform<'a, u32>>,
+    /// The number of keys to sort, if not all of `keys` is to be sorted; see [Count].
+    ///
+    /// The count, rather than the length of any of the buffers, bounds the work: all buffers may
+    /// be sized to a capacity larger than the count, and only the first `count` keys and values
+    /// are moved.
+    pub count: Option<Count<'a>>,
 }
 
+/// Sorts a buffer of keys, moving a buffer of values alongside the keys.
+///
+/// To sort values by one of their own `u32` fields instead, without maintaining a separate buffer
+/// of keys, use a [RadixSortByKey](crate::radix_sort::RadixSortByKey) with a
+/// [KeyFunction::Field](crate::radix_sort::KeyFunction::Field) key function.
 pub struct RadixSortBy<K, V>
 where
     K: abi::Sized,
     V: abi::Sized,
 {
     device: Device,
+    fallback_count: UniformCache<u32>,
     generate_dispatches: GenerateDispatches<K>,
     bucket_histogram: BucketHistogram<K>,
     global_bucket_offsets: GlobalBucketOffsets,
     bucket_scatter_by: BucketScatterBy<K, V>,
+    apply_permutation: ApplyPermutation<V>,
     global_bucket_data:
         Buffer<[[u32; RADIX_DIGITS]; RADIX_GROUPS], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
     segment_sizes: Buffer<SegmentSizes, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
@@ -49,6 +76,22 @@ where
     K: abi::Sized + 'static,
     V: abi::Sized + 'static,
 {
+    pub fn requirements() -> Requirements {
+        // The scatter pass stores the segment's keys, value indices and a workspace in workgroup
+        // memory, alongside 1 additional 4-byte value
+        Requirements::new(
+            DEFAULT_GROUP_SIZE,
+            (BUCKET_SCATTER_BY_SEGMENT_SIZE * 3 + 1) * 4,
+            7,
+        )
+    }
+
+    /// Shrinks the per-workgroup state the sort retains to what sorting up to `max_count` keys
+    /// requires; see [PrefixSum::shrink_to_fit](crate::prefix_sum::PrefixSum::shrink_to_fit).
+    pub fn shrink_to_fit(&mut self, max_count: u32) {
+        self.bucket_scatter_by.shrink_to_fit(max_count);
+    }
+
     pub fn encode<U0, U1, U2, U3>(
         &mut self,
         encoder: CommandEncoder,
@@ -60,7 +103,61 @@ where
         U2: buffer::StorageBinding,
         U3: buffer::StorageBinding,
     {
-        self.encode_internal(encoder, input, 4)
+        self.encode_internal(encoder, input, 4, None, false)
+    }
+
+    /// Encodes the sort, using the temporary buffers in the `scratch_pool` rather than the sort's
+    /// own temporary buffers.
+    pub fn encode_with_scratch_pool<U0, U1, U2, U3>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: RadixSortByInput<K, V, U0, U1, U2, U3>,
+        scratch_pool: &mut ScratchPool,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+        U3: buffer::StorageBinding,
+    {
+        self.encode_internal(encoder, input, 4, Some(scratch_pool), false)
+    }
+
+    /// Encodes the movement of the `values` into the order of a known `permutation`, without
+    /// sorting any keys: `output[i]` receives `values[permutation[i]]`.
+    ///
+    /// The `permutation` is typically the result of a preceding sort (e.g. of
+    /// [encode_argsort](Self::encode_argsort)) that is to be reapplied to a different set of
+    /// values. The values are moved in the same way the sort moves values. Entries in the
+    /// `permutation` that are out of bounds for the `values` leave the corresponding output
+    /// element untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `output` holds fewer elements than the `permutation`.
+    pub fn apply_permutation<U0, U1, U2>(
+        &mut self,
+        encoder: CommandEncoder,
+        permutation: buffer::View<[u32], U0>,
+        values: buffer::View<[V], U1>,
+        output: buffer::View<[V], U2>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        assert!(
+            output.len() >= permutation.len(),
+            "`output` must hold at least as many elements as `permutation`"
+        );
+
+        if is_empty_input(permutation.len()) || is_empty_input(values.len()) {
+            return encoder;
+        }
+
+        self.apply_permutation
+            .encode(encoder, permutation, values, output)
     }
 
     fn encode_internal<U0, U1, U2, U3>(
@@ -68,6 +165,8 @@ where
         mut encoder: CommandEncoder,
         input: RadixSortByInput<K, V, U0, U1, U2, U3>,
         radix_groups: usize,
+        mut scratch_pool: Option<&mut ScratchPool>,
+        generate_values: bool,
     ) -> CommandEncoder
     where
         U0: buffer::StorageBinding,
@@ -83,9 +182,42 @@ where
             count,
         } = input;
 
+        let (count, fallback_count) = Count::resolve(count, keys.len() as u32);
+
+        if is_empty_input(fallback_count as usize) {
+            return encoder;
+        }
+
         let dispatch_indirect = count.is_some();
-        let fallback_count = keys.len() as u32;
-        let count = CountBuffer::new(count, &self.device, fallback_count);
+
+        // With an indirect count, the count is only known on the device; the count can be at most
+        // the length of `keys`
+        let required = fallback_count as usize;
+
+        assert!(
+            values.len() >= required,
+            "`values` ({}) must hold at least as many elements as the number of keys to sort ({})",
+            values.len(),
+            required
+        );
+        assert!(
+            temporary_key_storage.len() >= required,
+            "`temporary_key_storage` ({}) must hold at least as many elements as the number of \
+            keys to sort ({})",
+            temporary_key_storage.len(),
+            required
+        );
+        assert!(
+            temporary_value_storage.len() >= required,
+            "`temporary_value_storage` ({}) must hold at least as many elements as the number of \
+            keys to sort ({})",
+            temporary_value_storage.len(),
+            required
+        );
+
+        self.fallback_count.update(&self.device, fallback_count);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
 
         if dispatch_indirect {
             encoder = self.generate_dispatches.encode(
@@ -137,7 +269,9 @@ where
                         dispatch_indirect,
                         dispatch: self.scatter_dispatch.view(),
                         fallback_count,
+                        generate_values: generate_values && i == 0,
                     },
+                    scratch_pool.as_deref_mut(),
                 );
             } else {
                 encoder = self.bucket_scatter_by.encode(
@@ -153,7 +287,9 @@ where
                         dispatch_indirect,
                         dispatch: self.scatter_dispatch.view(),
                         fallback_count,
+                        generate_values: false,
                     },
+                    scratch_pool.as_deref_mut(),
                 );
             }
         }
@@ -162,27 +298,78 @@ where
     }
 }
 
-impl<V> RadixSortBy<u32, V>
+impl<K> RadixSortBy<K, u32>
 where
+    K: abi::Sized + 'static,
+{
+    /// Encodes the sort of the `input.keys`, writing the sorted permutation to `input.values`:
+    /// `values[i]` receives the original index of the key that moved to position `i`.
+    ///
+    /// The initial contents of `input.values` are ignored: rather than sorting a separately
+    /// generated sequence of indices, the first pass of the sort generates each key's index as its
+    /// value. Sorting with generated indices thus requires no index buffer besides the
+    /// permutation itself.
+    pub fn encode_argsort<U0, U1, U2, U3>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: RadixSortByInput<K, u32, U0, U1, U2, U3>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+        U3: buffer::StorageBinding,
+    {
+        self.encode_internal(encoder, input, 4, None, true)
+    }
+}
+
+impl<K, V> RadixSortBy<K, V>
+where
+    K: RadixKey,
     V: abi::Sized + 'static,
 {
-    pub async fn init_u32(device: Device) -> Self {
+    pub async fn init(device: Device) -> Result<Self, ValueTypeError> {
+        let init_bucket_histogram = BucketHistogram::init(
+            device.clone(),
+            DEFAULT_GROUP_SIZE,
+            DEFAULT_VALUES_PER_THREAD,
+        );
+        let init_bucket_scatter_by = BucketScatterBy::init(device.clone());
+
+        Self::init_internal(device, init_bucket_histogram, init_bucket_scatter_by).await
+    }
+
+    async fn init_internal(
+        device: Device,
+        init_bucket_histogram: impl Future<Output = BucketHistogram<K>>,
+        init_bucket_scatter_by: impl Future<Output = Result<BucketScatterBy<K, V>, ValueTypeError>>,
+    ) -> Result<Self, ValueTypeError> {
         let global_bucket_data =
             device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_dst());
 
-        let (generate_dispatches, bucket_histogram, global_bucket_offsets, bucket_scatter_by) =
-            join!(
-                GenerateDispatches::init(device.clone()),
-                BucketHistogram::init_u32(device.clone()),
-                GlobalBucketOffsets::init(device.clone()),
-                BucketScatterBy::init_u32(device.clone()),
-            )
-            .await;
+        let (
+            generate_dispatches,
+            bucket_histogram,
+            global_bucket_offsets,
+            bucket_scatter_by,
+            apply_permutation,
+        ) = join!(
+            GenerateDispatches::init(device.clone()),
+            init_bucket_histogram,
+            GlobalBucketOffsets::init(device.clone()),
+            init_bucket_scatter_by,
+            ApplyPermutation::init(device.clone()),
+        );
+
+        let bucket_scatter_by = bucket_scatter_by?;
+        let apply_permutation = apply_permutation?;
 
         let segment_sizes = device.create_buffer(
             SegmentSizes {
-                histogram: BUCKET_HISTOGRAM_SEGMENT_SIZE,
+                histogram: bucket_histogram.segment_size(),
                 scatter: BUCKET_SCATTER_BY_SEGMENT_SIZE,
+                max_workgroups: device.limits().max_compute_workgroups_per_dimension,
             },
             buffer::Usages::uniform_binding(),
         );
@@ -203,17 +390,40 @@ where
             buffer::Usages::storage_binding().and_indirect(),
         );
 
-        RadixSortBy {
+        let fallback_count = UniformCache::new(&device, 0);
+
+        Ok(RadixSortBy {
             device,
+            fallback_count,
             generate_dispatches,
             bucket_histogram,
             global_bucket_offsets,
             bucket_scatter_by,
+            apply_permutation,
             global_bucket_data,
             segment_sizes,
             histogram_dispatch,
             scatter_dispatch,
-        }
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init(device))
+    }
+}
+
+impl<V> RadixSortBy<u32, V>
+where
+    V: abi::Sized + 'static,
+{
+    pub async fn init_u32(device: Device) -> Result<Self, ValueTypeError> {
+        Self::init(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_u32(device))
     }
 
     pub fn encode_half_precision<U0, U1, U2, U3>(
@@ -227,6 +437,58 @@ where
         U2: buffer::StorageBinding,
         U3: buffer::StorageBinding,
     {
-        self.encode_internal(encoder, input, 2)
+        self.encode_internal(encoder, input, 2, None, false)
+    }
+}
+
+impl<V> RadixSortBy<i32, V>
+where
+    V: abi::Sized + 'static,
+{
+    pub async fn init_i32(device: Device) -> Result<Self, ValueTypeError> {
+        Self::init(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_i32(device))
+    }
+}
+
+impl<V> RadixSortBy<f32, V>
+where
+    V: abi::Sized + 'static,
+{
+    pub async fn init_f32(device: Device) -> Result<Self, ValueTypeError> {
+        Self::init(device).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_f32_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_f32(device))
+    }
+
+    /// Initializes a sort for `f32` keys that orders `-0.0` and `+0.0` as equal keys.
+    ///
+    /// The regular `f32` sort orders keys by their bit patterns, which places `-0.0` before `+0.0`.
+    /// This sort instead maps `-0.0` to the same radix key as `+0.0`, so that the stable sort
+    /// preserves the relative input order of all zero keys, regardless of their signs. The keys
+    /// themselves are not modified: a `-0.0` key is output as `-0.0`.
+    pub async fn init_f32_zero_equal(device: Device) -> Result<Self, ValueTypeError> {
+        let init_bucket_histogram = BucketHistogram::init_key_source(
+            device.clone(),
+            KEY_F32_ZERO_EQUAL,
+            DEFAULT_GROUP_SIZE,
+            DEFAULT_VALUES_PER_THREAD,
+        );
+        let init_bucket_scatter_by =
+            BucketScatterBy::init_key_source(device.clone(), KEY_F32_ZERO_EQUAL);
+
+        Self::init_internal(device, init_bucket_histogram, init_bucket_scatter_by).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_f32_zero_equal_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_f32_zero_equal(device))
     }
 }
@@ -0,0 +1,329 @@
+use empa::buffer::{Buffer, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups};
+use empa::device::Device;
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::radix_sort::bucket_histogram::{BucketHistogram, BucketHistogramResources};
+use crate::radix_sort::bucket_scatter_multi_word::{
+    BucketScatterMultiWord, BucketScatterMultiWordInput, BUCKET_SCATTER_MULTI_WORD_SEGMENT_SIZE,
+};
+use crate::radix_sort::generate_dispatches::{
+    GenerateDispatches, GenerateDispatchesResources, SegmentSizes,
+};
+use crate::radix_sort::global_bucket_offsets::GlobalBucketOffsets;
+use crate::radix_sort::{
+    key_word_count, DEFAULT_GROUP_SIZE, DEFAULT_VALUES_PER_THREAD, RADIX_DIGITS, RADIX_GROUPS,
+};
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::is_empty_input;
+use crate::validate::validate;
+use crate::write_value_type::ValueTypeError;
+
+/// A built-in function that derives a `u32` sort key from a value.
+///
+/// The value is viewed as a struct of `u32` fields, where field `i` covers the bytes
+/// `4 * i..4 * i + 4` of the value.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyFunction {
+    /// Sorts by the field with the given index.
+    Field(u32),
+    /// Sorts by a hash of the field with the given index; see [KeyFunction::hash].
+    FieldHash(u32),
+}
+
+impl KeyFunction {
+    /// Sorts by the field at the given byte `offset` within the value, e.g. the
+    /// `mem::offset_of!` a `u32` key field of a `#[repr(C)]` struct.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `offset` is not a multiple of `4`.
+    pub fn field_at_offset(offset: usize) -> Self {
+        assert!(
+            offset % 4 == 0,
+            "the key offset must be a multiple of 4 (got {})",
+            offset
+        );
+
+        KeyFunction::Field((offset / 4) as u32)
+    }
+
+    /// The hash [KeyFunction::FieldHash] applies to a field, for deriving the same keys on the
+    /// CPU.
+    pub fn hash(value: u32) -> u32 {
+        let mut x = value;
+
+        x ^= x >> 16;
+        x = x.wrapping_mul(0x7feb352d);
+        x ^= x >> 15;
+        x = x.wrapping_mul(0x846ca68b);
+        x ^= x >> 16;
+
+        x
+    }
+
+    fn field(&self) -> u32 {
+        match self {
+            KeyFunction::Field(field) => *field,
+            KeyFunction::FieldHash(field) => *field,
+        }
+    }
+
+    fn to_key_wgsl(&self) -> String {
+        match self {
+            KeyFunction::Field(field) => format!(
+                "fn to_key(value: VALUE_TYPE) -> u32 {{ return value.field_{}; }}",
+                field
+            ),
+            KeyFunction::FieldHash(field) => format!(
+                "fn to_key(value: VALUE_TYPE) -> u32 {{\n    \
+                    var x = value.field_{};\n\n    \
+                    x ^= x >> 16u;\n    \
+                    x *= 0x7feb352du;\n    \
+                    x ^= x >> 15u;\n    \
+                    x *= 0x846ca68bu;\n    \
+                    x ^= x >> 16u;\n\n    \
+                    return x;\n\
+                }}",
+                field
+            ),
+        }
+    }
+}
+
+pub struct RadixSortByKeyInput<'a, V, U0, U1> {
+    pub data: buffer::View<'a, [V], U0>,
+    /// Storage for the intermediate results of the sort; must hold at least as many elements as
+    /// `data`.
+    pub temporary_storage: buffer::View<'a, [V], U1>,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// Sorts values by a `u32` key that a [KeyFunction] derives from each value.
+///
+/// The key is derived on the fly in each pass, so no separate key buffer is required; the values
+/// themselves are moved. The sort is stable.
+pub struct RadixSortByKey<V>
+where
+    V: abi::Sized,
+{
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    generate_dispatches: GenerateDispatches<V>,
+    bucket_histogram: BucketHistogram<V>,
+    global_bucket_offsets: GlobalBucketOffsets,
+    bucket_scatter: BucketScatterMultiWord<V>,
+    global_bucket_data:
+        Buffer<[[u32; RADIX_DIGITS]; RADIX_GROUPS], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    segment_sizes: Buffer<SegmentSizes, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
+    histogram_dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+    scatter_dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+}
+
+impl<V> RadixSortByKey<V>
+where
+    V: abi::Sized + 'static,
+{
+    pub fn requirements() -> Requirements {
+        Requirements::new(
+            DEFAULT_GROUP_SIZE,
+            (BUCKET_SCATTER_MULTI_WORD_SEGMENT_SIZE * 3 + 1) * 4,
+            5,
+        )
+    }
+
+    /// Initializes a sort by the key the `key_function` derives from each value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the field the `key_function` reads lies outside of the value type.
+    pub async fn init(device: Device, key_function: KeyFunction) -> Result<Self, ValueTypeError> {
+        assert!(
+            (key_function.field() as usize) < key_word_count::<V>(),
+            "the key field `{}` lies outside of a value type with {} fields",
+            key_function.field(),
+            key_word_count::<V>()
+        );
+
+        let to_key = key_function.to_key_wgsl();
+
+        let global_bucket_data =
+            device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_dst());
+
+        let (generate_dispatches, bucket_histogram, global_bucket_offsets, bucket_scatter) = join!(
+            GenerateDispatches::init_value_type(device.clone()),
+            BucketHistogram::init_value_key(
+                device.clone(),
+                &to_key,
+                DEFAULT_GROUP_SIZE,
+                DEFAULT_VALUES_PER_THREAD
+            ),
+            GlobalBucketOffsets::init(device.clone()),
+            BucketScatterMultiWord::init_value_key(device.clone(), &to_key),
+        );
+
+        let generate_dispatches = generate_dispatches?;
+        let bucket_histogram = bucket_histogram?;
+        let bucket_scatter = bucket_scatter?;
+
+        let segment_sizes = device.create_buffer(
+            SegmentSizes {
+                histogram: bucket_histogram.segment_size(),
+                scatter: BUCKET_SCATTER_MULTI_WORD_SEGMENT_SIZE,
+                max_workgroups: device.limits().max_compute_workgroups_per_dimension,
+            },
+            buffer::Usages::uniform_binding(),
+        );
+        let histogram_dispatch = device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+        let scatter_dispatch = device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+
+        let fallback_count = UniformCache::new(&device, 0);
+
+        Ok(RadixSortByKey {
+            device,
+            fallback_count,
+            generate_dispatches,
+            bucket_histogram,
+            global_bucket_offsets,
+            bucket_scatter,
+            global_bucket_data,
+            segment_sizes,
+            histogram_dispatch,
+            scatter_dispatch,
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(
+        device: Device,
+        key_function: KeyFunction,
+    ) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init(device, key_function))
+    }
+
+    /// Shrinks the per-workgroup state the sort retains to what sorting up to `max_count` values
+    /// requires; see [PrefixSum::shrink_to_fit](crate::prefix_sum::PrefixSum::shrink_to_fit).
+    pub fn shrink_to_fit(&mut self, max_count: u32) {
+        self.bucket_scatter.shrink_to_fit(max_count);
+    }
+
+    pub fn encode<U0, U1>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: RadixSortByKeyInput<V, U0, U1>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        let RadixSortByKeyInput {
+            data,
+            temporary_storage,
+            count,
+        } = input;
+
+        validate!(
+            temporary_storage.len() >= data.len(),
+            "`temporary_storage` ({}) must hold at least as many elements as `data` ({})",
+            temporary_storage.len(),
+            data.len()
+        );
+
+        if is_empty_input(data.len()) {
+            return encoder;
+        }
+
+        let dispatch_indirect = count.is_some();
+        let fallback_count = data.len() as u32;
+
+        self.fallback_count.update(&self.device, fallback_count);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        if dispatch_indirect {
+            encoder = self.generate_dispatches.encode(
+                encoder,
+                GenerateDispatchesResources {
+                    segment_sizes: self.segment_sizes.uniform(),
+                    max_count: count.uniform(),
+                    data: data.storage(),
+                    histogram_dispatch: self.histogram_dispatch.storage(),
+                    scatter_dispatch: self.scatter_dispatch.storage(),
+                },
+            );
+        }
+
+        encoder = encoder.clear_buffer(self.global_bucket_data.view());
+        encoder = self.bucket_histogram.encode(
+            encoder,
+            BucketHistogramResources {
+                max_count: count.uniform(),
+                data: data.storage(),
+                global_histograms: self.global_bucket_data.storage(),
+            },
+            dispatch_indirect,
+            self.histogram_dispatch.view(),
+            fallback_count,
+        );
+        encoder = self
+            .global_bucket_offsets
+            .encode(encoder, self.global_bucket_data.view());
+
+        let data_a = data;
+        let data_b = temporary_storage;
+
+        for i in 0..RADIX_GROUPS {
+            if (i & 1) == 0 {
+                encoder = self.bucket_scatter.encode(
+                    encoder,
+                    BucketScatterMultiWordInput {
+                        data_in: data_a,
+                        data_out: data_b,
+                        global_base_bucket_offsets: self.global_bucket_data.view(),
+                        word: 0,
+                        radix_group: i as u32,
+                        max_count: count.uniform(),
+                        dispatch_indirect,
+                        dispatch: self.scatter_dispatch.view(),
+                        fallback_count,
+                    },
+                );
+            } else {
+                encoder = self.bucket_scatter.encode(
+                    encoder,
+                    BucketScatterMultiWordInput {
+                        data_in: data_b,
+                        data_out: data_a,
+                        global_base_bucket_offsets: self.global_bucket_data.view(),
+                        word: 0,
+                        radix_group: i as u32,
+                        max_count: count.uniform(),
+                        dispatch_indirect,
+                        dispatch: self.scatter_dispatch.view(),
+                        fallback_count,
+                    },
+                );
+            }
+        }
+
+        encoder
+    }
+}
@@ -0,0 +1,83 @@
+use empa::buffer;
+use empa::command::CommandEncoder;
+use empa::device::Device;
+
+use crate::radix_key::RadixKey;
+use crate::radix_sort::{RadixSort, RadixSortBuilder, RadixSortInput};
+
+/// A [RadixSort] specialized for repeatedly sorting buffers of a single, fixed length (e.g. a
+/// per-frame sort of a fixed-size buffer).
+///
+/// All internal state is allocated for the fixed length when the sort is initialized, and the sort
+/// always dispatches directly for that length: encoding a sort never allocates a buffer, nor
+/// generates its dispatches on the device. The bind groups for the data and temporary storage are
+/// still created for each encoded sort, as these bind the buffers passed to
+/// [encode](Self::encode).
+pub struct FixedSizeRadixSort<T>
+where
+    T: RadixKey,
+{
+    radix_sort: RadixSort<T>,
+    len: usize,
+}
+
+impl<T> FixedSizeRadixSort<T>
+where
+    T: RadixKey,
+{
+    pub async fn init(device: Device, len: usize) -> Self {
+        let radix_sort = RadixSortBuilder::new(device)
+            .max_count(len as u32)
+            .finish()
+            .await;
+
+        FixedSizeRadixSort { radix_sort, len }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device, len: usize) -> Self {
+        pollster::block_on(Self::init(device, len))
+    }
+
+    /// The length of the buffers this sort sorts.
+    pub fn fixed_len(&self) -> usize {
+        self.len
+    }
+
+    /// Encodes the sort of all of `data`, using `temporary_storage` for the intermediate results.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` does not have the length for which the sort was initialized, or if
+    /// `temporary_storage` holds fewer elements than that length.
+    pub fn encode<U0, U1>(
+        &mut self,
+        encoder: CommandEncoder,
+        data: buffer::View<[T], U0>,
+        temporary_storage: buffer::View<[T], U1>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        assert_eq!(
+            data.len(),
+            self.len,
+            "`data` must have the length for which the sort was initialized"
+        );
+        assert!(
+            temporary_storage.len() >= self.len,
+            "`temporary_storage` must hold at least as many elements as the length for which the \
+            sort was initialized"
+        );
+
+        self.radix_sort.encode(
+            encoder,
+            RadixSortInput {
+                data,
+                temporary_storage,
+                count: None,
+            },
+        )
+    }
+}
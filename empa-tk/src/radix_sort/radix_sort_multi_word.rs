@@ -0,0 +1,253 @@
+use empa::buffer::{Buffer, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups};
+use empa::device::Device;
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::radix_sort::bucket_histogram::{BucketHistogram, BucketHistogramResources};
+use crate::radix_sort::bucket_scatter_multi_word::{
+    BucketScatterMultiWord, BucketScatterMultiWordInput, BUCKET_SCATTER_MULTI_WORD_SEGMENT_SIZE,
+};
+use crate::radix_sort::generate_dispatches::{
+    GenerateDispatches, GenerateDispatchesResources, SegmentSizes,
+};
+use crate::radix_sort::global_bucket_offsets::GlobalBucketOffsets;
+use crate::radix_sort::{
+    key_word_count, DEFAULT_GROUP_SIZE, DEFAULT_VALUES_PER_THREAD, RADIX_DIGITS, RADIX_GROUPS,
+};
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::is_empty_input;
+use crate::validate::validate;
+use crate::write_value_type::ValueTypeError;
+
+pub struct RadixSortMultiWordInput<'a, K, U0, U1> {
+    pub data: buffer::View<'a, [K], U0>,
+    /// Storage for the intermediate results of the sort; must hold at least as many elements as
+    /// `data`.
+    pub temporary_storage: buffer::View<'a, [K], U1>,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// Sorts keys that consist of multiple `u32` words (e.g. `[u32; 4]` identifiers).
+///
+/// The key type may be any [abi::Sized] type with a size that is a multiple of `4` bytes; its
+/// `u32` words are compared big-endian: the first word is the most significant word. Each word is
+/// sorted with the same 4 stable passes a [RadixSort](super::RadixSort) uses for a `u32` key,
+/// starting with the least significant word, so sorting a key of `n` words takes `4 * n` passes.
+pub struct RadixSortMultiWord<K>
+where
+    K: abi::Sized,
+{
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    generate_dispatches: GenerateDispatches<K>,
+    bucket_histograms: Vec<BucketHistogram<K>>,
+    global_bucket_offsets: GlobalBucketOffsets,
+    bucket_scatter: BucketScatterMultiWord<K>,
+    global_bucket_data:
+        Buffer<[[u32; RADIX_DIGITS]; RADIX_GROUPS], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    segment_sizes: Buffer<SegmentSizes, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
+    histogram_dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+    scatter_dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+}
+
+impl<K> RadixSortMultiWord<K>
+where
+    K: abi::Sized + 'static,
+{
+    pub fn requirements() -> Requirements {
+        Requirements::new(
+            DEFAULT_GROUP_SIZE,
+            (BUCKET_SCATTER_MULTI_WORD_SEGMENT_SIZE * 3 + 1) * 4,
+            5,
+        )
+    }
+
+    pub async fn init(device: Device) -> Result<Self, ValueTypeError> {
+        let global_bucket_data =
+            device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_dst());
+
+        let (generate_dispatches, global_bucket_offsets, bucket_scatter) = join!(
+            GenerateDispatches::init_value_type(device.clone()),
+            GlobalBucketOffsets::init(device.clone()),
+            BucketScatterMultiWord::init(device.clone()),
+        );
+
+        let generate_dispatches = generate_dispatches?;
+        let bucket_scatter = bucket_scatter?;
+
+        let mut bucket_histograms = Vec::with_capacity(key_word_count::<K>());
+
+        for word in 0..key_word_count::<K>() {
+            let bucket_histogram = BucketHistogram::init_multi_word(
+                device.clone(),
+                word,
+                DEFAULT_GROUP_SIZE,
+                DEFAULT_VALUES_PER_THREAD,
+            )
+            .await?;
+
+            bucket_histograms.push(bucket_histogram);
+        }
+
+        let segment_sizes = device.create_buffer(
+            SegmentSizes {
+                histogram: bucket_histograms[0].segment_size(),
+                scatter: BUCKET_SCATTER_MULTI_WORD_SEGMENT_SIZE,
+                max_workgroups: device.limits().max_compute_workgroups_per_dimension,
+            },
+            buffer::Usages::uniform_binding(),
+        );
+        let histogram_dispatch = device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+        let scatter_dispatch = device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+
+        let fallback_count = UniformCache::new(&device, 0);
+
+        Ok(RadixSortMultiWord {
+            device,
+            fallback_count,
+            generate_dispatches,
+            bucket_histograms,
+            global_bucket_offsets,
+            bucket_scatter,
+            global_bucket_data,
+            segment_sizes,
+            histogram_dispatch,
+            scatter_dispatch,
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init(device))
+    }
+
+    /// Shrinks the per-workgroup state the sort retains to what sorting up to `max_count` keys
+    /// requires; see [PrefixSum::shrink_to_fit](crate::prefix_sum::PrefixSum::shrink_to_fit).
+    pub fn shrink_to_fit(&mut self, max_count: u32) {
+        self.bucket_scatter.shrink_to_fit(max_count);
+    }
+
+    pub fn encode<U0, U1>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: RadixSortMultiWordInput<K, U0, U1>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        let RadixSortMultiWordInput {
+            data,
+            temporary_storage,
+            count,
+        } = input;
+
+        validate!(
+            temporary_storage.len() >= data.len(),
+            "`temporary_storage` ({}) must hold at least as many elements as `data` ({})",
+            temporary_storage.len(),
+            data.len()
+        );
+
+        if is_empty_input(data.len()) {
+            return encoder;
+        }
+
+        let dispatch_indirect = count.is_some();
+        let fallback_count = data.len() as u32;
+
+        self.fallback_count.update(&self.device, fallback_count);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        if dispatch_indirect {
+            encoder = self.generate_dispatches.encode(
+                encoder,
+                GenerateDispatchesResources {
+                    segment_sizes: self.segment_sizes.uniform(),
+                    max_count: count.uniform(),
+                    data: data.storage(),
+                    histogram_dispatch: self.histogram_dispatch.storage(),
+                    scatter_dispatch: self.scatter_dispatch.storage(),
+                },
+            );
+        }
+
+        let data_a = data;
+        let data_b = temporary_storage;
+
+        // Every word takes an even number of passes, so each word's passes start from `data_a` and
+        // the sorted result ends up back in `data_a`
+        for word in (0..self.bucket_histograms.len()).rev() {
+            encoder = encoder.clear_buffer(self.global_bucket_data.view());
+            encoder = self.bucket_histograms[word].encode(
+                encoder,
+                BucketHistogramResources {
+                    max_count: count.uniform(),
+                    data: data_a.storage(),
+                    global_histograms: self.global_bucket_data.storage(),
+                },
+                dispatch_indirect,
+                self.histogram_dispatch.view(),
+                fallback_count,
+            );
+            encoder = self
+                .global_bucket_offsets
+                .encode(encoder, self.global_bucket_data.view());
+
+            for i in 0..RADIX_GROUPS {
+                if (i & 1) == 0 {
+                    encoder = self.bucket_scatter.encode(
+                        encoder,
+                        BucketScatterMultiWordInput {
+                            data_in: data_a,
+                            data_out: data_b,
+                            global_base_bucket_offsets: self.global_bucket_data.view(),
+                            word,
+                            radix_group: i as u32,
+                            max_count: count.uniform(),
+                            dispatch_indirect,
+                            dispatch: self.scatter_dispatch.view(),
+                            fallback_count,
+                        },
+                    );
+                } else {
+                    encoder = self.bucket_scatter.encode(
+                        encoder,
+                        BucketScatterMultiWordInput {
+                            data_in: data_b,
+                            data_out: data_a,
+                            global_base_bucket_offsets: self.global_bucket_data.view(),
+                            word,
+                            radix_group: i as u32,
+                            max_count: count.uniform(),
+                            dispatch_indirect,
+                            dispatch: self.scatter_dispatch.view(),
+                            fallback_count,
+                        },
+                    );
+                }
+            }
+        }
+
+        encoder
+    }
+}
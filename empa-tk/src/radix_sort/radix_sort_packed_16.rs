@@ -0,0 +1,215 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer;
+use empa::buffer::{Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::ShaderSource;
+use futures::join;
+
+use crate::count::Count;
+use crate::radix_sort::{RadixSort, RadixSortInput};
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+
+const SHADER_TEMPLATE: &str = include_str!("packed_16.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    packed: Storage<'a, [u32], ReadWrite>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    unpacked: Storage<'a, [u32], ReadWrite>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+pub struct RadixSortPacked16Input<'a, U0, U1, U2> {
+    /// The keys to sort, packed two per word: the key at index `2 * i` in the lower 16 bits of
+    /// word `i`, and the key at index `2 * i + 1` in the upper 16 bits (the layout of a `[u16]` or
+    /// `[i16]` slice cast to `[u32]`).
+    ///
+    /// All `2 * data.len()` keys are sorted; pad an odd number of keys with the largest key.
+    pub data: buffer::View<'a, [u32], U0>,
+    /// Receives the unpacked keys while they are sorted; must hold at least `2 * data.len()`
+    /// elements.
+    pub unpacked_storage: buffer::View<'a, [u32], U1>,
+    /// Storage for the intermediate results of the sort; must hold at least `2 * data.len()`
+    /// elements.
+    pub temporary_storage: buffer::View<'a, [u32], U2>,
+}
+
+/// Sorts 16-bit keys that are stored packed, two keys per `u32` word.
+///
+/// The keys are unpacked into one key per word, sorted with the 2 passes of
+/// [RadixSort::encode_half_precision], and then packed again. The packed layout halves the memory
+/// that holds the keys outside of the sort (and the bandwidth of any operation that produces or
+/// consumes them), but the sort passes themselves operate on the unpacked keys.
+pub struct RadixSortPacked16 {
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    unpack_pipeline: ComputePipeline<(ResourcesLayout,)>,
+    pack_pipeline: ComputePipeline<(ResourcesLayout,)>,
+    radix_sort: RadixSort<u32>,
+}
+
+impl RadixSortPacked16 {
+    pub fn requirements() -> Requirements {
+        RadixSort::<u32>::requirements().max(Requirements::new(GROUP_SIZE, 0, 2))
+    }
+
+    async fn init_internal(device: Device, signed: bool) -> Self {
+        let code = format!("const SIGNED = {};\n\n{}", signed, SHADER_TEMPLATE);
+
+        let shader_source = ShaderSource::unparsed(code);
+        let shader = device.create_shader_module(&shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let create_unpack_pipeline = unsafe {
+            device.create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute_unchecked(ComputeStageBuilder::begin(&shader, "unpack").finish())
+                    .finish(),
+            )
+        };
+        let create_pack_pipeline = unsafe {
+            device.create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute_unchecked(ComputeStageBuilder::begin(&shader, "pack").finish())
+                    .finish(),
+            )
+        };
+
+        let (unpack_pipeline, pack_pipeline, radix_sort) = join!(
+            create_unpack_pipeline,
+            create_pack_pipeline,
+            RadixSort::init_u32(device.clone())
+        );
+
+        let fallback_count = UniformCache::new(&device, 0);
+
+        RadixSortPacked16 {
+            device,
+            fallback_count,
+            bind_group_layout,
+            unpack_pipeline,
+            pack_pipeline,
+            radix_sort,
+        }
+    }
+
+    /// Initializes a sort for packed unsigned (`u16`) keys.
+    pub async fn init_u16(device: Device) -> Self {
+        Self::init_internal(device, false).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u16_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_u16(device))
+    }
+
+    /// Initializes a sort for packed signed (`i16`) keys.
+    pub async fn init_i16(device: Device) -> Self {
+        Self::init_internal(device, true).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i16_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_i16(device))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `input.unpacked_storage` or `input.temporary_storage` holds fewer than
+    /// `2 * input.data.len()` elements.
+    pub fn encode<U0, U1, U2>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: RadixSortPacked16Input<U0, U1, U2>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        let RadixSortPacked16Input {
+            data,
+            unpacked_storage,
+            temporary_storage,
+        } = input;
+
+        if is_empty_input(data.len()) {
+            return encoder;
+        }
+
+        let key_count = 2 * data.len();
+
+        assert!(
+            unpacked_storage.len() >= key_count,
+            "`unpacked_storage` must hold at least 2 elements for each packed word"
+        );
+        assert!(
+            temporary_storage.len() >= key_count,
+            "`temporary_storage` must hold at least 2 elements for each packed word"
+        );
+
+        self.fallback_count.update(&self.device, data.len() as u32);
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                count: self.fallback_count.uniform(),
+                packed: data.storage(),
+                unpacked: unpacked_storage.storage(),
+            },
+        );
+
+        let workgroups = div_ceil(data.len() as u32, GROUP_SIZE);
+
+        encoder = encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.unpack_pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: workgroups,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end();
+
+        // The unpacked storage may be larger than the number of keys, so the sort is limited to
+        // the keys with a static count
+        encoder = self.radix_sort.encode_half_precision(
+            encoder,
+            RadixSortInput {
+                data: unpacked_storage,
+                temporary_storage,
+                count: Some(Count::Static(key_count as u32)),
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pack_pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: workgroups,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
@@ -0,0 +1,95 @@
+use empa::abi;
+use empa::access_mode::ReadWrite;
+use empa::buffer::Storage;
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::{BindGroupLayout, Resources};
+use empa::shader_module::ShaderSource;
+
+use crate::radix_key::RadixKey;
+use crate::radix_sort::shader_code;
+
+const SHADER_TEMPLATE: &str = include_str!("shader_template.wgsl");
+
+#[derive(empa::resource_binding::Resources)]
+pub struct ResolveBoundsResources<'a, T>
+where
+    T: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    pub key_bounds: Storage<'a, [u32; 2]>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    pub bounds: Storage<'a, [T; 2], ReadWrite>,
+}
+
+type ResourcesLayout<T> = <ResolveBoundsResources<'static, T> as Resources>::Layout;
+
+/// Converts the key bounds the histogram pass found back into the minimum and maximum value.
+pub struct ResolveBounds<T>
+where
+    T: abi::Sized,
+{
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
+    pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+}
+
+impl<T> ResolveBounds<T>
+where
+    T: RadixKey,
+{
+    pub async fn init(device: Device) -> Self {
+        let code = shader_code(SHADER_TEMPLATE, T::KEY_TYPE, 1, 1);
+        let shader_source = ShaderSource::unparsed(code);
+        let shader = device.create_shader_module(&shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = unsafe {
+            device
+                .create_compute_pipeline(
+                    &ComputePipelineDescriptorBuilder::begin()
+                        .layout(&pipeline_layout)
+                        .compute_unchecked(ComputeStageBuilder::begin(&shader, "main").finish())
+                        .finish(),
+                )
+                .await
+        };
+
+        ResolveBounds {
+            device,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+}
+
+impl<T> ResolveBounds<T>
+where
+    T: abi::Sized + 'static,
+{
+    pub fn encode(
+        &self,
+        encoder: CommandEncoder,
+        resources: ResolveBoundsResources<T>,
+    ) -> CommandEncoder {
+        let bind_group = self
+            .device
+            .create_bind_group(&self.bind_group_layout, resources);
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
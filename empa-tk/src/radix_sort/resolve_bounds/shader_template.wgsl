@@ -0,0 +1,11 @@
+@group(0) @binding(0)
+var<storage, read> key_bounds: array<u32, 2>;
+
+@group(0) @binding(1)
+var<storage, read_write> bounds: array<DATA_TYPE, 2>;
+
+@compute @workgroup_size(1, 1, 1)
+fn main() {
+    bounds[0] = from_key(~key_bounds[0]);
+    bounds[1] = from_key(key_bounds[1]);
+}
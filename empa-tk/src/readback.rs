@@ -0,0 +1,40 @@
+use std::error::Error;
+
+use empa::buffer::Buffer;
+use empa::{abi, buffer};
+
+/// Maps the `buffer` for reading, copies its values into a [Vec] and unmaps it again.
+///
+/// Resolves once the buffer is mapped, so any commands that write to the buffer (e.g. a copy of the
+/// output of an operation) must have been submitted before awaiting the result. See [read_back]
+/// for a buffer that holds a single value.
+pub async fn read_back_vec<T, U>(buffer: &Buffer<[T], U>) -> Result<Vec<T>, Box<dyn Error>>
+where
+    T: abi::Sized + Copy,
+    U: buffer::MapRead,
+{
+    buffer.map_read().await?;
+
+    let values = buffer.mapped().to_vec();
+
+    buffer.unmap();
+
+    Ok(values)
+}
+
+/// Maps the `buffer` for reading, copies out its value and unmaps it again.
+///
+/// See [read_back_vec] for a buffer that holds a slice of values.
+pub async fn read_back<T, U>(buffer: &Buffer<T, U>) -> Result<T, Box<dyn Error>>
+where
+    T: abi::Sized + Copy,
+    U: buffer::MapRead,
+{
+    buffer.map_read().await?;
+
+    let value = *buffer.mapped();
+
+    buffer.unmap();
+
+    Ok(value)
+}
@@ -0,0 +1,106 @@
+use empa::abi;
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::{BindGroupLayout, Resources};
+use empa::shader_module::{shader_source, ShaderSource};
+
+use crate::radix_key::sealed::KeyType;
+use crate::radix_key::RadixKey;
+use crate::reduce_by_key::GROUP_SIZE;
+use crate::util::div_ceil;
+
+const SHADER_U32: ShaderSource = shader_source!("shader_u32.wgsl");
+const SHADER_I32: ShaderSource = shader_source!("shader_i32.wgsl");
+const SHADER_F32: ShaderSource = shader_source!("shader_f32.wgsl");
+
+#[derive(empa::resource_binding::Resources)]
+pub struct CollectUniqueKeysResources<'a, T>
+where
+    T: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    pub count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    pub keys: Storage<'a, [T]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    pub run_count: Storage<'a, u32>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    pub run_starts: Storage<'a, [u32], ReadWrite>,
+    #[resource(binding = 4, visibility = "COMPUTE")]
+    pub unique_keys: Storage<'a, [T], ReadWrite>,
+}
+
+type ResourcesLayout<T> = <CollectUniqueKeysResources<'static, T> as Resources>::Layout;
+
+pub struct CollectUniqueKeys<T>
+where
+    T: abi::Sized,
+{
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
+    pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+}
+
+impl<T> CollectUniqueKeys<T>
+where
+    T: RadixKey,
+{
+    pub async fn init(device: Device) -> Self {
+        let shader_source = match T::KEY_TYPE {
+            KeyType::U32 => &SHADER_U32,
+            KeyType::I32 => &SHADER_I32,
+            KeyType::F32 => &SHADER_F32,
+        };
+
+        let shader = device.create_shader_module(shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        CollectUniqueKeys {
+            device,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    /// Encodes the collection of the key of each run, and pads the `run_starts` past the run count.
+    ///
+    /// Always dispatches over the full length of the `run_starts`, as the padding must cover all
+    /// of it, regardless of the count.
+    pub fn encode(
+        &self,
+        encoder: CommandEncoder,
+        resources: CollectUniqueKeysResources<T>,
+        run_starts_len: u32,
+    ) -> CommandEncoder {
+        let bind_group = self
+            .device
+            .create_bind_group(&self.bind_group_layout, resources);
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: div_ceil(run_starts_len, GROUP_SIZE),
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
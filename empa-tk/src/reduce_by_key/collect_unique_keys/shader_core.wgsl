@@ -0,0 +1,33 @@
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<storage, read> keys: array<DATA_TYPE>;
+
+@group(0) @binding(2)
+var<storage, read> run_count: u32;
+
+@group(0) @binding(3)
+var<storage, read_write> run_starts: array<u32>;
+
+@group(0) @binding(4)
+var<storage, read_write> unique_keys: array<DATA_TYPE>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+
+    if index >= arrayLength(&run_starts) {
+        return;
+    }
+
+    if index < run_count {
+        if index < arrayLength(&unique_keys) {
+            unique_keys[index] = keys[run_starts[index]];
+        }
+    } else {
+        // Pad the run starts past the last run with the end of the data, so that the segmented
+        // reduction sees these as empty segments, rather than as segments that extend backwards
+        run_starts[index] = min(count, arrayLength(&keys));
+    }
+}
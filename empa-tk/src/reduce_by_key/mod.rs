@@ -0,0 +1,204 @@
+use empa::buffer::{Buffer, Uniform};
+use empa::command::CommandEncoder;
+use empa::device::Device;
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::find_runs::{FindRuns, FindRunsInput, FindRunsOutput};
+use crate::radix_key::RadixKey;
+use crate::reduce_by_key::collect_unique_keys::{CollectUniqueKeys, CollectUniqueKeysResources};
+use crate::requirements::Requirements;
+use crate::segmented_reduce::{ReduceOperator, SegmentedReduce, SegmentedReduceInput};
+use crate::uniform_cache::UniformCache;
+use crate::util::is_empty_input;
+
+mod collect_unique_keys;
+
+const GROUP_SIZE: u32 = 256;
+
+pub struct ReduceByKeyInput<'a, K, V, U0, U1> {
+    /// The keys that determine the groups; must be sorted, such that equal keys are adjacent.
+    pub keys: buffer::View<'a, [K], U0>,
+    /// The values to aggregate per group; must hold at least as many elements as `keys`.
+    pub values: buffer::View<'a, [V], U1>,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+pub struct ReduceByKeyOutput<'a, K, V, U0, U1, U2> {
+    /// Receives the key of each group, in order; holding as many elements as the input keys is
+    /// always sufficient (every key may start a new group).
+    pub unique_keys: buffer::View<'a, [K], U0>,
+    /// Receives the aggregate of the values of each group; must hold at least as many elements as
+    /// the input keys. The elements past the group count receive the identity of the operator.
+    pub aggregates: buffer::View<'a, [V], U1>,
+    /// Receives the number of groups; `0` if the input is empty.
+    pub group_count: buffer::View<'a, u32, U2>,
+}
+
+/// Aggregates the values that share a key: finds the runs of equal keys in sorted keys, and then
+/// reduces the values of each run with a [ReduceOperator].
+///
+/// Together with a sort of the keys and values (e.g. a [RadixSortBy](crate::radix_sort::RadixSortBy)),
+/// this computes the equivalent of a `GROUP BY key` with a `SUM`, `MIN` or `MAX` aggregate. This
+/// chains a [FindRuns] and a [SegmentedReduce]; the intermediate buffers these require are managed
+/// internally.
+pub struct ReduceByKey<K, V>
+where
+    K: abi::Sized,
+    V: abi::Sized,
+{
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    find_runs: FindRuns<K>,
+    collect_unique_keys: CollectUniqueKeys<K>,
+    segmented_reduce: SegmentedReduce<V>,
+    run_starts: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    run_mapping: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+}
+
+impl<K, V> ReduceByKey<K, V>
+where
+    K: RadixKey,
+    V: RadixKey,
+{
+    pub fn requirements() -> Requirements {
+        FindRuns::<K>::requirements()
+            .max(SegmentedReduce::<V>::requirements())
+            .max(Requirements::new(GROUP_SIZE, 0, 5))
+    }
+
+    /// Initializes a reduction that combines the values of each group with the `operator`.
+    pub async fn init(device: Device, operator: ReduceOperator) -> Self {
+        let (find_runs, collect_unique_keys, segmented_reduce) = join!(
+            FindRuns::init(device.clone()),
+            CollectUniqueKeys::init(device.clone()),
+            SegmentedReduce::init(device.clone(), operator),
+        );
+
+        let run_starts = device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+        let run_mapping =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding().and_copy_dst());
+
+        let fallback_count = UniformCache::new(&device, 0);
+
+        ReduceByKey {
+            device,
+            fallback_count,
+            find_runs,
+            collect_unique_keys,
+            segmented_reduce,
+            run_starts,
+            run_mapping,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device, operator: ReduceOperator) -> Self {
+        pollster::block_on(Self::init(device, operator))
+    }
+
+    /// Encodes the search for the groups of equal keys in the `input.keys`, and the reduction of
+    /// the values of each group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input.values` or `output.aggregates` hold fewer elements than `input.keys`.
+    pub fn encode<U0, U1, U2, U3, U4>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: ReduceByKeyInput<K, V, U0, U1>,
+        output: ReduceByKeyOutput<K, V, U2, U3, U4>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+        U3: buffer::StorageBinding,
+        U4: buffer::StorageBinding,
+    {
+        let ReduceByKeyInput {
+            keys,
+            values,
+            count,
+        } = input;
+
+        let ReduceByKeyOutput {
+            unique_keys,
+            aggregates,
+            group_count,
+        } = output;
+
+        assert!(
+            values.len() >= keys.len(),
+            "`values` must hold at least as many elements as `keys`"
+        );
+        assert!(
+            aggregates.len() >= keys.len(),
+            "`aggregates` must hold at least as many elements as `keys`"
+        );
+
+        let len = keys.len();
+
+        // The run starts serve as the segment offsets of the reduction, the number of which
+        // determines the number of aggregates; they must match the length of the keys exactly
+        if len > 0 && self.run_starts.len() != len {
+            self.run_starts = self
+                .device
+                .create_slice_buffer_zeroed(len, self.run_starts.usage());
+        }
+
+        if self.run_mapping.len() < len {
+            self.run_mapping = self
+                .device
+                .create_slice_buffer_zeroed(len, self.run_mapping.usage());
+        }
+
+        // For an empty input, the run search still resolves the group count to `0`
+        encoder = self.find_runs.encode(
+            encoder,
+            FindRunsInput {
+                data: keys,
+                count: count.clone(),
+            },
+            FindRunsOutput {
+                run_count: group_count,
+                run_starts: self.run_starts.view(),
+                run_mapping: self.run_mapping.view(),
+            },
+        );
+
+        if is_empty_input(len) {
+            return encoder;
+        }
+
+        self.fallback_count.update(&self.device, len as u32);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        encoder = self.collect_unique_keys.encode(
+            encoder,
+            CollectUniqueKeysResources {
+                count: count.uniform(),
+                keys: keys.storage(),
+                run_count: group_count.storage(),
+                run_starts: self.run_starts.storage(),
+                unique_keys: unique_keys.storage(),
+            },
+            len as u32,
+        );
+
+        // The count is always passed on, even if none was specified, as the `values` may be longer
+        // than the `keys`, in which case the last group must still end at the end of the keys
+        self.segmented_reduce.encode(
+            encoder,
+            SegmentedReduceInput {
+                data: values,
+                segment_offsets: self.run_starts.view(),
+                count: Some(count.uniform()),
+            },
+            aggregates,
+        )
+    }
+}
@@ -0,0 +1,78 @@
+use empa::adapter::{Adapter, Limits};
+
+/// The device limits an operation's pipelines require.
+///
+/// Obtain an operation's requirements from its `requirements` function (e.g.
+/// [RadixSort::requirements](crate::radix_sort::RadixSort::requirements)) to check whether an
+/// adapter can run the operation before initializing it. The requirements apply to the operation's
+/// default configuration; a builder that changes the group size or the number of values per thread
+/// changes the requirements accordingly.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Requirements {
+    /// The number of invocations in the largest workgroup the operation dispatches.
+    pub workgroup_size: u32,
+    /// The largest amount of workgroup memory (in bytes) any of the operation's pipelines uses.
+    pub workgroup_storage_size: u32,
+    /// The largest number of storage buffers any of the operation's pipelines binds.
+    pub storage_buffers_per_shader_stage: u32,
+}
+
+impl Requirements {
+    pub(crate) const fn new(
+        workgroup_size: u32,
+        workgroup_storage_size: u32,
+        storage_buffers_per_shader_stage: u32,
+    ) -> Self {
+        Requirements {
+            workgroup_size,
+            workgroup_storage_size,
+            storage_buffers_per_shader_stage,
+        }
+    }
+
+    /// Combines the requirements of two operations into requirements that meet both.
+    pub const fn max(self, other: Requirements) -> Requirements {
+        const fn max(a: u32, b: u32) -> u32 {
+            if a > b {
+                a
+            } else {
+                b
+            }
+        }
+
+        Requirements {
+            workgroup_size: max(self.workgroup_size, other.workgroup_size),
+            workgroup_storage_size: max(self.workgroup_storage_size, other.workgroup_storage_size),
+            storage_buffers_per_shader_stage: max(
+                self.storage_buffers_per_shader_stage,
+                other.storage_buffers_per_shader_stage,
+            ),
+        }
+    }
+
+    /// The limits to request when creating a device for the operation.
+    ///
+    /// Limits the operation does not depend on are left at their default values.
+    pub fn required_limits(&self) -> Limits {
+        Limits {
+            max_compute_invocations_per_workgroup: self.workgroup_size,
+            max_compute_workgroup_size_x: self.workgroup_size,
+            max_compute_workgroup_storage_size: self.workgroup_storage_size,
+            max_storage_buffers_per_shader_stage: self.storage_buffers_per_shader_stage,
+            ..Default::default()
+        }
+    }
+
+    /// Whether the given `limits` meet these requirements.
+    pub fn is_met_by(&self, limits: &Limits) -> bool {
+        limits.max_compute_invocations_per_workgroup >= self.workgroup_size
+            && limits.max_compute_workgroup_size_x >= self.workgroup_size
+            && limits.max_compute_workgroup_storage_size >= self.workgroup_storage_size
+            && limits.max_storage_buffers_per_shader_stage >= self.storage_buffers_per_shader_stage
+    }
+
+    /// Whether the `adapter` supports these requirements.
+    pub fn supported(&self, adapter: &Adapter) -> bool {
+        self.is_met_by(&adapter.limits())
+    }
+}
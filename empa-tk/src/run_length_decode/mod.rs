@@ -0,0 +1,199 @@
+use std::fmt::Write;
+
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::ShaderSource;
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+use crate::write_value_type::{write_value_type, ValueTypeError};
+
+const SHADER_TEMPLATE: &str = include_str!("shader_template.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a, V>
+where
+    V: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    run_count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    values: Storage<'a, [V]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    run_ends: Storage<'a, [u32]>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    output: Storage<'a, [V], ReadWrite>,
+}
+
+type ResourcesLayout<V> = <Resources<'static, V> as empa::resource_binding::Resources>::Layout;
+
+pub struct RunLengthDecodeInput<'a, V, U0, U1> {
+    /// The value of each run.
+    pub values: buffer::View<'a, [V], U0>,
+    /// The length of each run.
+    pub lengths: buffer::View<'a, [u32], U1>,
+    /// The number of runs; if not specified, all `lengths` are decoded.
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// Expands runs of `(value, length)` pairs into a flat array in which each value is repeated
+/// `length` times.
+///
+/// An inclusive prefix sum over the run lengths determines where each run ends in the output,
+/// after which each output element finds the run it belongs to with a binary search.
+pub struct RunLengthDecode<V>
+where
+    V: abi::Sized,
+{
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    prefix_sum: PrefixSum<u32>,
+    run_ends: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<V>>,
+    pipeline: ComputePipeline<(ResourcesLayout<V>,)>,
+}
+
+impl<V> RunLengthDecode<V>
+where
+    V: abi::Sized + 'static,
+{
+    pub fn requirements() -> Requirements {
+        PrefixSum::<u32>::requirements().max(Requirements::new(GROUP_SIZE, 0, 3))
+    }
+
+    pub async fn init(device: Device) -> Result<Self, ValueTypeError> {
+        let mut code = String::new();
+
+        write_value_type::<V>(&device, &mut code)?;
+
+        write!(code, "{}", SHADER_TEMPLATE).unwrap();
+
+        let shader_source = ShaderSource::unparsed(code);
+        let shader = device.create_shader_module(&shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<V>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let create_pipeline = unsafe {
+            device.create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute_unchecked(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+        };
+        let init_prefix_sum = PrefixSum::init_inclusive_u32(device.clone());
+
+        let (pipeline, prefix_sum) = join!(create_pipeline, init_prefix_sum);
+
+        let run_ends =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding().and_copy_dst());
+
+        let fallback_count = UniformCache::new(&device, 0);
+
+        Ok(RunLengthDecode {
+            device,
+            fallback_count,
+            prefix_sum,
+            run_ends,
+            bind_group_layout,
+            pipeline,
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init(device))
+    }
+
+    /// Encodes the expansion of the runs into the `output`.
+    ///
+    /// The `output` should hold at least as many elements as the sum of the run lengths; runs
+    /// that extend past the end of the `output` are truncated. Elements past the sum of the run
+    /// lengths are left untouched.
+    pub fn encode<U0, U1, U2>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: RunLengthDecodeInput<V, U0, U1>,
+        output: buffer::View<[V], U2>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::CopySrc + 'static,
+        U2: buffer::StorageBinding,
+    {
+        let RunLengthDecodeInput {
+            values,
+            lengths,
+            count,
+        } = input;
+
+        if is_empty_input(lengths.len()) || is_empty_input(output.len()) {
+            return encoder;
+        }
+
+        let dispatch_indirect = count.is_some();
+
+        self.fallback_count
+            .update(&self.device, lengths.len() as u32);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        // The run lengths are scanned in a copy, so that the input is left intact
+        if self.run_ends.len() != lengths.len() {
+            self.run_ends = self
+                .device
+                .create_slice_buffer_zeroed(lengths.len(), self.run_ends.usage());
+        }
+
+        encoder = encoder.copy_buffer_to_buffer_slice(lengths, self.run_ends.view());
+        encoder = self.prefix_sum.encode(
+            encoder,
+            PrefixSumInput {
+                data: self.run_ends.view(),
+                count: if dispatch_indirect {
+                    Some(count.uniform())
+                } else {
+                    None
+                },
+                initial: None,
+                order: ScanOrder::Ascending,
+            },
+        );
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                run_count: count.uniform(),
+                values: values.storage(),
+                run_ends: self.run_ends.storage(),
+                output: output.storage(),
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: div_ceil(output.len() as u32, GROUP_SIZE),
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
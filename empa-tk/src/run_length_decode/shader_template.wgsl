@@ -0,0 +1,45 @@
+@group(0) @binding(0)
+var<uniform> run_count: u32;
+
+@group(0) @binding(1)
+var<storage, read> values: array<VALUE_TYPE>;
+
+@group(0) @binding(2)
+var<storage, read> run_ends: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read_write> output: array<VALUE_TYPE>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    let runs = min(run_count, min(arrayLength(&values), arrayLength(&run_ends)));
+
+    if runs == 0 {
+        return;
+    }
+
+    // After the inclusive prefix sum over the run lengths, the end of the last run is the total decoded length.
+    let total = min(run_ends[runs - 1], arrayLength(&output));
+
+    if index >= total {
+        return;
+    }
+
+    // Find the first run that ends after the output index; empty runs are skipped, as they end where the previous run
+    // ends.
+    var lo = 0u;
+    var hi = runs;
+
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+
+        if run_ends[mid] > index {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    output[index] = values[lo];
+}
@@ -1,6 +1,6 @@
 use std::fmt::Write;
-use std::future::join;
 
+use bytemuck::Zeroable;
 use empa::access_mode::ReadWrite;
 use empa::buffer::{Buffer, Storage, Uniform};
 use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
@@ -8,16 +8,25 @@ use empa::compute_pipeline::{
     ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
 };
 use empa::device::Device;
-use empa::resource_binding::BindGroupLayout;
+use empa::resource_binding::{BindGroup, BindGroupLayout};
 use empa::shader_module::ShaderSource;
 use empa::type_flag::{O, X};
 use empa::{abi, buffer};
+use futures::join;
 
+use crate::aliasing::views_overlap;
 use crate::count_buffer::CountBuffer;
-use crate::generate_dispatch::{GenerateDispatch, GenerateDispatchResources};
-use crate::write_value_type::write_value_type;
+use crate::generate_dispatch::{GenerateDispatch, PlanDispatch};
+use crate::index_transform::{IndexTransform, IndexWindow, INDEX_32, INDEX_64};
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+use crate::write_value_type::{write_value_type, ValueTypeError};
 
-const SHADER_TEMPLATE: &str = include_str!("shader_template.wgsl");
+const SHADER_TEMPLATE: &str = concat!(
+    include_str!("../dispatch.wgsl"),
+    include_str!("shader_template.wgsl")
+);
 
 const GROUP_SIZE: u32 = 256;
 
@@ -35,6 +44,12 @@ where
     data_in: Storage<'a, [V]>,
     #[resource(binding = 3, visibility = "COMPUTE")]
     data_out: Storage<'a, [V], ReadWrite>,
+    #[resource(binding = 4, visibility = "COMPUTE")]
+    range: Uniform<'a, ScatterByRange>,
+    #[resource(binding = 5, visibility = "COMPUTE")]
+    index_transform: Uniform<'a, IndexTransform>,
+    #[resource(binding = 6, visibility = "COMPUTE")]
+    index_window: Uniform<'a, IndexWindow>,
 }
 
 type ResourcesLayout<K, V> =
@@ -44,19 +59,47 @@ pub struct ScatterByInput<'a, B, V, U0, U1> {
     pub scatter_by: buffer::View<'a, [B], U0>,
     pub data: buffer::View<'a, [V], U1>,
     pub count: Option<Uniform<'a, u32>>,
+    /// A transform applied to the `scatter_by` indices; the indices are used as is if `None`.
+    pub index_transform: Option<IndexTransform>,
 }
 
+/// A window of the input and the output that a [ScatterBy] operates on.
+///
+/// For each `i` in `0..len`, the value at `data[src_offset + i]` is written to
+/// `output[dst_offset + scatter_by[src_offset + i]]`.
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug, Zeroable)]
+#[repr(C)]
+pub struct ScatterByRange {
+    pub src_offset: u32,
+    pub dst_offset: u32,
+    pub len: u32,
+}
+
+/// Writes each input value to the output position given by the corresponding `scatter_by`
+/// index: `output[scatter_by[i]] = data[i]`.
+///
+/// If `scatter_by` is a permutation (every output position is targeted exactly once), the scatter
+/// is the inverse of a [GatherBy](crate::gather_by::GatherBy) by the same indices. If several
+/// values target the same output position, one of these values is written, but which one is
+/// unspecified and may differ between runs: do not rely on any particular value winning a
+/// collision. Output positions that no value targets keep their previous contents.
+///
+/// The output must not overlap `data`; debug builds panic if it does.
 pub struct ScatterBy<B, V>
 where
     B: abi::Sized,
     V: abi::Sized,
 {
     device: Device,
+    fallback_count: UniformCache<u32>,
+    range: UniformCache<ScatterByRange>,
+    index_transform: UniformCache<IndexTransform>,
+    index_window: UniformCache<IndexWindow>,
     bind_group_layout: BindGroupLayout<ResourcesLayout<B, V>>,
     pipeline: ComputePipeline<(ResourcesLayout<B, V>,)>,
     generate_dispatch: GenerateDispatch,
+    segment_size: u32,
     group_size: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
-    dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
 }
 
 impl<B, V> ScatterBy<B, V>
@@ -64,12 +107,29 @@ where
     B: abi::Sized + 'static,
     V: abi::Sized + 'static,
 {
-    async fn init_internal(device: Device, by_type: &str, shader_template: &str) -> Self {
+    pub fn requirements() -> Requirements {
+        Requirements::new(GROUP_SIZE, 0, 3)
+    }
+
+    async fn init_internal(
+        device: Device,
+        by_type: &str,
+        index_source: &str,
+        shader_template: &str,
+        values_per_thread: u32,
+    ) -> Result<Self, ValueTypeError> {
+        assert!(values_per_thread > 0, "`values_per_thread` must not be `0`");
+
         let mut code = String::new();
 
-        write_value_type::<V>(&mut code);
+        write_value_type::<V>(&device, &mut code)?;
 
-        write!(code, "alias BY_TYPE = {};\n\n{}", by_type, shader_template).unwrap();
+        write!(
+            code,
+            "alias BY_TYPE = {};\n\nconst VALUES_PER_THREAD = {}u;\n\n{}\n{}",
+            by_type, values_per_thread, index_source, shader_template
+        )
+        .unwrap();
 
         let shader_source = ShaderSource::unparsed(code);
         let shader = device.create_shader_module(&shader_source);
@@ -87,34 +147,130 @@ where
         };
         let init_generate_dispatch = GenerateDispatch::init(device.clone());
 
-        let (pipeline, generate_dispatch) = join!(create_pipeline, init_generate_dispatch,).await;
+        let (pipeline, generate_dispatch) = join!(create_pipeline, init_generate_dispatch);
+
+        let segment_size = GROUP_SIZE * values_per_thread;
+        let group_size = device.create_buffer(segment_size, buffer::Usages::uniform_binding());
 
-        let group_size = device.create_buffer(GROUP_SIZE, buffer::Usages::uniform_binding());
-        let dispatch = device.create_buffer(
-            DispatchWorkgroups {
-                count_x: 1,
-                count_y: 1,
-                count_z: 1,
+        let fallback_count = UniformCache::new(&device, 0);
+        let range = UniformCache::new(
+            &device,
+            ScatterByRange {
+                src_offset: 0,
+                dst_offset: 0,
+                len: 0,
             },
-            buffer::Usages::storage_binding().and_indirect(),
         );
+        let index_transform = UniformCache::new(&device, IndexTransform::default());
+        let index_window = UniformCache::new(&device, IndexWindow::default());
 
-        ScatterBy {
+        Ok(ScatterBy {
             device,
+            fallback_count,
+            range,
+            index_transform,
+            index_window,
             bind_group_layout,
             pipeline,
             generate_dispatch,
+            segment_size,
             group_size,
-            dispatch,
-        }
+        })
     }
 
     pub fn encode<U0, U1, U2>(
         &mut self,
-        mut encoder: CommandEncoder,
+        encoder: CommandEncoder,
         input: ScatterByInput<B, V, U0, U1>,
         output: buffer::View<[V], U2>,
     ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        self.prepare(input, output).encode(encoder)
+    }
+
+    /// Encodes the scatter for a window of the input; see [ScatterByRange].
+    ///
+    /// If a `count` is specified, then at most `count` values of the window are scattered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range does not lie within `input.scatter_by` or within `input.data`.
+    pub fn encode_range<U0, U1, U2>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: ScatterByInput<B, V, U0, U1>,
+        output: buffer::View<[V], U2>,
+        range: ScatterByRange,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        self.prepare_range(input, output, range).encode(encoder)
+    }
+
+    /// Prepares the scatter of `input.data` into the `output` as a [ScatterByPlan], which can be
+    /// encoded repeatedly without recreating its bindings.
+    pub fn prepare<U0, U1, U2>(
+        &mut self,
+        input: ScatterByInput<B, V, U0, U1>,
+        output: buffer::View<[V], U2>,
+    ) -> ScatterByPlan<B, V>
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        let range = ScatterByRange {
+            src_offset: 0,
+            dst_offset: 0,
+            len: input.data.len() as u32,
+        };
+
+        self.prepare_internal(input, output, range, 0)
+    }
+
+    /// Prepares the scatter for a window of the input as a [ScatterByPlan]; see
+    /// [encode_range](Self::encode_range).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range does not lie within `input.scatter_by` or within `input.data`.
+    pub fn prepare_range<U0, U1, U2>(
+        &mut self,
+        input: ScatterByInput<B, V, U0, U1>,
+        output: buffer::View<[V], U2>,
+        range: ScatterByRange,
+    ) -> ScatterByPlan<B, V>
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        assert!(
+            range.src_offset as usize + range.len as usize <= input.scatter_by.len(),
+            "the range must lie within `scatter_by`"
+        );
+        assert!(
+            range.src_offset as usize + range.len as usize <= input.data.len(),
+            "the range must lie within `data`"
+        );
+
+        self.prepare_internal(input, output, range, 0)
+    }
+
+    fn prepare_internal<U0, U1, U2>(
+        &mut self,
+        input: ScatterByInput<B, V, U0, U1>,
+        output: buffer::View<[V], U2>,
+        range: ScatterByRange,
+        window_offset: u64,
+    ) -> ScatterByPlan<B, V>
     where
         U0: buffer::StorageBinding,
         U1: buffer::StorageBinding,
@@ -124,22 +280,41 @@ where
             scatter_by,
             data,
             count,
+            index_transform,
         } = input;
 
-        let dispatch_indirect = count.is_some();
-        let count = CountBuffer::new(count, &self.device, data.len() as u32);
-
-        if dispatch_indirect {
-            encoder = self.generate_dispatch.encode(
-                encoder,
-                GenerateDispatchResources {
-                    group_size: self.group_size.uniform(),
-                    count: count.uniform(),
-                    dispatch: self.dispatch.storage(),
-                },
-            );
+        debug_assert!(
+            !views_overlap(&data, &output),
+            "the output must not overlap `data`; a scatter cannot be performed in place"
+        );
+
+        if is_empty_input(data.len()) || is_empty_input(range.len as usize) {
+            return ScatterByPlan {
+                pipeline: self.pipeline.clone(),
+                bind_group: None,
+                dispatch: PlanDispatch::Direct(0),
+            };
         }
 
+        // The uniform caches recreate (rather than overwrite) their buffers when their values
+        // change, so the plan's bindings are not affected by later encodes or plans.
+        self.fallback_count.update(&self.device, range.len);
+        self.range.update(&self.device, range);
+        self.index_transform
+            .update(&self.device, index_transform.unwrap_or_default());
+        self.index_window
+            .update(&self.device, IndexWindow::new(window_offset));
+
+        let dispatch_indirect = count.is_some();
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        let dispatch = if dispatch_indirect {
+            self.generate_dispatch
+                .prepare_indirect(self.group_size.uniform(), count.uniform())
+        } else {
+            PlanDispatch::Direct(div_ceil(range.len, self.segment_size))
+        };
+
         let bind_group = self.device.create_bind_group(
             &self.bind_group_layout,
             Resources {
@@ -147,28 +322,68 @@ where
                 scatter_by: scatter_by.storage(),
                 data_in: data.storage(),
                 data_out: output.storage(),
+                range: self.range.uniform(),
+                index_transform: self.index_transform.uniform(),
+                index_window: self.index_window.uniform(),
             },
         );
 
+        ScatterByPlan {
+            pipeline: self.pipeline.clone(),
+            bind_group: Some(bind_group),
+            dispatch,
+        }
+    }
+}
+
+/// A [ScatterBy] with its bindings prepared for a particular input and output; see
+/// [ScatterBy::prepare].
+///
+/// Like a [GatherByPlan](crate::gather_by::GatherByPlan), the plan owns its pipeline and
+/// bindings, and reads the buffers' contents at the time its commands execute.
+pub struct ScatterByPlan<B, V>
+where
+    B: abi::Sized,
+    V: abi::Sized,
+{
+    pipeline: ComputePipeline<(ResourcesLayout<B, V>,)>,
+    bind_group: Option<BindGroup<ResourcesLayout<B, V>>>,
+    dispatch: PlanDispatch,
+}
+
+impl<B, V> ScatterByPlan<B, V>
+where
+    B: abi::Sized + 'static,
+    V: abi::Sized + 'static,
+{
+    pub fn encode(&self, mut encoder: CommandEncoder) -> CommandEncoder {
+        let Some(bind_group) = &self.bind_group else {
+            return encoder;
+        };
+
+        if let PlanDispatch::Indirect {
+            generate_dispatch, ..
+        } = &self.dispatch
+        {
+            encoder = generate_dispatch.encode(encoder);
+        }
+
         let encoder = encoder
             .begin_compute_pass()
             .set_pipeline(&self.pipeline)
-            .set_bind_groups(&bind_group);
-
-        if dispatch_indirect {
-            encoder
-                .dispatch_workgroups_indirect(self.dispatch.view())
-                .end()
-        } else {
-            let workgroups = (data.len() as u32).div_ceil(GROUP_SIZE);
+            .set_bind_groups(bind_group);
 
-            encoder
+        match &self.dispatch {
+            PlanDispatch::Direct(workgroups) => encoder
                 .dispatch_workgroups(DispatchWorkgroups {
-                    count_x: workgroups,
+                    count_x: *workgroups,
                     count_y: 1,
                     count_z: 1,
                 })
-                .end()
+                .end(),
+            PlanDispatch::Indirect { dispatch, .. } => {
+                encoder.dispatch_workgroups_indirect(dispatch.view()).end()
+            }
         }
     }
 }
@@ -177,8 +392,34 @@ impl<V> ScatterBy<u32, V>
 where
     V: abi::Sized + 'static,
 {
-    pub async fn init_u32(device: Device) -> Self {
-        Self::init_internal(device, "u32", SHADER_TEMPLATE).await
+    pub async fn init_u32(device: Device) -> Result<Self, ValueTypeError> {
+        Self::init_u32_tiled(device, 1).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_u32(device))
+    }
+
+    /// Initializes a scatter in which each invocation processes a contiguous tile of
+    /// `values_per_thread` values, rather than a single value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values_per_thread` is `0`.
+    pub async fn init_u32_tiled(
+        device: Device,
+        values_per_thread: u32,
+    ) -> Result<Self, ValueTypeError> {
+        Self::init_internal(device, "u32", INDEX_32, SHADER_TEMPLATE, values_per_thread).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_tiled_blocking(
+        device: Device,
+        values_per_thread: u32,
+    ) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_u32_tiled(device, values_per_thread))
     }
 }
 
@@ -186,7 +427,110 @@ impl<V> ScatterBy<i32, V>
 where
     V: abi::Sized + 'static,
 {
-    pub async fn init_i32(device: Device) -> Self {
-        Self::init_internal(device, "i32", SHADER_TEMPLATE).await
+    pub async fn init_i32(device: Device) -> Result<Self, ValueTypeError> {
+        Self::init_i32_tiled(device, 1).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_i32(device))
+    }
+
+    /// Initializes a scatter in which each invocation processes a contiguous tile of
+    /// `values_per_thread` values, rather than a single value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values_per_thread` is `0`.
+    pub async fn init_i32_tiled(
+        device: Device,
+        values_per_thread: u32,
+    ) -> Result<Self, ValueTypeError> {
+        Self::init_internal(device, "i32", INDEX_32, SHADER_TEMPLATE, values_per_thread).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_tiled_blocking(
+        device: Device,
+        values_per_thread: u32,
+    ) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_i32_tiled(device, values_per_thread))
+    }
+}
+
+impl<V> ScatterBy<[u32; 2], V>
+where
+    V: abi::Sized + 'static,
+{
+    /// Initializes a scatter by 64-bit indices, represented as `[u32; 2]` pairs of the low and high
+    /// 32 bits of the index.
+    ///
+    /// The index transform is applied with 64-bit arithmetic. The output a shader can address is
+    /// still limited to what a single binding can hold, so the indices address a larger buffer of
+    /// which the output is a window; see [encode_window](Self::encode_window).
+    pub async fn init_u64(device: Device) -> Result<Self, ValueTypeError> {
+        Self::init_u64_tiled(device, 1).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u64_blocking(device: Device) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_u64(device))
+    }
+
+    /// Initializes a scatter in which each invocation processes a contiguous tile of
+    /// `values_per_thread` values, rather than a single value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values_per_thread` is `0`.
+    pub async fn init_u64_tiled(
+        device: Device,
+        values_per_thread: u32,
+    ) -> Result<Self, ValueTypeError> {
+        Self::init_internal(
+            device,
+            "array<u32, 2>",
+            INDEX_64,
+            SHADER_TEMPLATE,
+            values_per_thread,
+        )
+        .await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u64_tiled_blocking(
+        device: Device,
+        values_per_thread: u32,
+    ) -> Result<Self, ValueTypeError> {
+        pollster::block_on(Self::init_u64_tiled(device, values_per_thread))
+    }
+
+    /// Encodes the scatter, where the `output` is the window of a larger buffer that starts at
+    /// element `window_offset` of that buffer, and the (transformed) indices address the larger
+    /// buffer.
+    ///
+    /// Values for which the index lies outside of the window are not written, so a scatter into a
+    /// buffer that is too large for a single binding can be performed as a sequence of scatters
+    /// into consecutive windows.
+    pub fn encode_window<U0, U1, U2>(
+        &mut self,
+        encoder: CommandEncoder,
+        input: ScatterByInput<[u32; 2], V, U0, U1>,
+        output: buffer::View<[V], U2>,
+        window_offset: u64,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        let range = ScatterByRange {
+            src_offset: 0,
+            dst_offset: 0,
+            len: input.data.len() as u32,
+        };
+
+        self.prepare_internal(input, output, range, window_offset)
+            .encode(encoder)
     }
 }
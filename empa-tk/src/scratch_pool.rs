@@ -0,0 +1,43 @@
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::Device;
+use empa::type_flag::{O, X};
+
+/// A pool of temporary buffers that may be shared between operations.
+///
+/// Operations such as [PrefixSum](crate::prefix_sum::PrefixSum),
+/// [RadixSort](crate::radix_sort::RadixSort) and [FindRuns](crate::find_runs::FindRuns) keep
+/// per-workgroup state in temporary buffers that grow with the size of the data. By default,
+/// each operation owns its own temporary buffers. When several operations are chained, passing
+/// the same pool to their `encode_with_scratch_pool` methods lets them share a single buffer
+/// instead, so that the pool is only as large as the largest consumer requires.
+///
+/// The operations clear the temporary state they use before use, so a pool may be shared by
+/// operations encoded back to back into the same command encoder.
+pub struct ScratchPool {
+    device: Device,
+    state: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, X, O, O, O>>,
+}
+
+impl ScratchPool {
+    pub fn new(device: Device) -> Self {
+        let state =
+            device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding().and_copy_dst());
+
+        ScratchPool { device, state }
+    }
+
+    /// Returns the pool's state buffer, grown to hold at least `len` words if it is smaller.
+    pub(crate) fn state(
+        &mut self,
+        len: usize,
+    ) -> &Buffer<[u32], buffer::Usages<O, O, X, O, O, O, X, O, O, O>> {
+        if self.state.len() < len {
+            self.state = self
+                .device
+                .create_slice_buffer_zeroed(len, self.state.usage());
+        }
+
+        &self.state
+    }
+}
@@ -0,0 +1,227 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::generate_dispatch::{GenerateDispatch, GenerateDispatchResources};
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::div_ceil;
+use crate::validate::validate;
+
+const SHADER_U32: ShaderSource = shader_source!("shader_u32.wgsl");
+const SHADER_I32: ShaderSource = shader_source!("shader_i32.wgsl");
+const SHADER_F32: ShaderSource = shader_source!("shader_f32.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a, T>
+where
+    T: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    haystack: Storage<'a, [T]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    queries: Storage<'a, [T]>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    output_indices: Storage<'a, [u32], ReadWrite>,
+}
+
+type ResourcesLayout<T> = <Resources<'static, T> as empa::resource_binding::Resources>::Layout;
+
+pub struct LowerBoundInput<'a, T, U0, U1> {
+    pub haystack: buffer::View<'a, [T], U0>,
+    pub queries: buffer::View<'a, [T], U1>,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// For each query value, finds the first index in a sorted `haystack` at which the value is not
+/// less than the query value.
+///
+/// If the query is less than all values in the haystack, the resulting index is `0`; if the
+/// query is greater than all values in the haystack, the resulting index is the length of the
+/// haystack. If the haystack contains values equal to the query, the resulting index is the index
+/// of the first such value.
+pub struct LowerBound<T>
+where
+    T: abi::Sized,
+{
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
+    pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+    generate_dispatch: GenerateDispatch,
+    group_size: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
+    dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+}
+
+impl<T> LowerBound<T>
+where
+    T: abi::Sized + 'static,
+{
+    pub fn requirements() -> Requirements {
+        Requirements::new(GROUP_SIZE, 0, 3)
+    }
+
+    async fn init_internal(device: Device, shader_source: &ShaderSource) -> Self {
+        let shader = device.create_shader_module(shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let create_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                .finish(),
+        );
+        let init_generate_dispatch = GenerateDispatch::init(device.clone());
+
+        let (pipeline, generate_dispatch) = join!(create_pipeline, init_generate_dispatch);
+
+        let group_size = device.create_buffer(GROUP_SIZE, buffer::Usages::uniform_binding());
+        let dispatch = device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+
+        let fallback_count = UniformCache::new(&device, 0);
+
+        LowerBound {
+            device,
+            fallback_count,
+            bind_group_layout,
+            pipeline,
+            generate_dispatch,
+            group_size,
+            dispatch,
+        }
+    }
+
+    /// Encodes the search for the lower bound of each of the `input.queries` in the
+    /// `input.haystack`.
+    ///
+    /// The `output_indices` must hold at least as many elements as there are queries. If
+    /// `input.count` is specified, only the first `count` queries are resolved.
+    pub fn encode<U0, U1, U2>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: LowerBoundInput<T, U0, U1>,
+        output_indices: buffer::View<[u32], U2>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        let LowerBoundInput {
+            haystack,
+            queries,
+            count,
+        } = input;
+
+        validate!(
+            output_indices.len() >= queries.len(),
+            "`output_indices` ({}) must hold at least as many elements as `queries` ({})",
+            output_indices.len(),
+            queries.len()
+        );
+
+        let dispatch_indirect = count.is_some();
+
+        self.fallback_count
+            .update(&self.device, queries.len() as u32);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        if dispatch_indirect {
+            encoder = self.generate_dispatch.encode(
+                encoder,
+                GenerateDispatchResources {
+                    group_size: self.group_size.uniform(),
+                    count: count.uniform(),
+                    dispatch: self.dispatch.storage(),
+                    max_count: None,
+                },
+            );
+        }
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                count: count.uniform(),
+                haystack: haystack.storage(),
+                queries: queries.storage(),
+                output_indices: output_indices.storage(),
+            },
+        );
+
+        let encoder = encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group);
+
+        if dispatch_indirect {
+            encoder
+                .dispatch_workgroups_indirect(self.dispatch.view())
+                .end()
+        } else {
+            encoder
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: div_ceil(queries.len() as u32, GROUP_SIZE),
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end()
+        }
+    }
+}
+
+impl LowerBound<u32> {
+    pub async fn init_u32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_U32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_u32(device))
+    }
+}
+
+impl LowerBound<i32> {
+    pub async fn init_i32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_I32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_i32(device))
+    }
+}
+
+impl LowerBound<f32> {
+    pub async fn init_f32(device: Device) -> Self {
+        Self::init_internal(device, &SHADER_F32).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_f32_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init_f32(device))
+    }
+}
@@ -0,0 +1,40 @@
+#include "../../dispatch.wgsl"
+
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<storage, read> haystack: array<DATA_TYPE>;
+
+@group(0) @binding(2)
+var<storage, read> queries: array<DATA_TYPE>;
+
+@group(0) @binding(3)
+var<storage, read_write> output_indices: array<u32>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let index = linear_workgroup_index(workgroup_id, num_workgroups) * 256u + local_index;
+
+    if index >= min(count, arrayLength(&queries)) {
+        return;
+    }
+
+    let query = queries[index];
+
+    var lo = 0u;
+    var hi = arrayLength(&haystack);
+
+    // Find the first position at which the haystack value is not less than the query
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+
+        if haystack[mid] < query {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    output_indices[index] = lo;
+}
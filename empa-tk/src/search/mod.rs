@@ -0,0 +1,2 @@
+mod lower_bound;
+pub use self::lower_bound::*;
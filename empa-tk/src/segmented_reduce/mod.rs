@@ -0,0 +1,332 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use futures::join;
+
+use crate::count_buffer::CountBuffer;
+use crate::generate_dispatch::{GenerateDispatch, GenerateDispatchResources};
+use crate::radix_key::sealed::KeyType;
+use crate::radix_key::RadixKey;
+use crate::requirements::Requirements;
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+
+const SHADER_SUM_U32: ShaderSource = shader_source!("shader_sum_u32.wgsl");
+const SHADER_MIN_U32: ShaderSource = shader_source!("shader_min_u32.wgsl");
+const SHADER_MAX_U32: ShaderSource = shader_source!("shader_max_u32.wgsl");
+const SHADER_SUM_I32: ShaderSource = shader_source!("shader_sum_i32.wgsl");
+const SHADER_MIN_I32: ShaderSource = shader_source!("shader_min_i32.wgsl");
+const SHADER_MAX_I32: ShaderSource = shader_source!("shader_max_i32.wgsl");
+const SHADER_SUM_F32: ShaderSource = shader_source!("shader_sum_f32.wgsl");
+const SHADER_MIN_F32: ShaderSource = shader_source!("shader_min_f32.wgsl");
+const SHADER_MAX_F32: ShaderSource = shader_source!("shader_max_f32.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+const VALUES_PER_THREAD: u32 = 4;
+
+const SEGMENT_SIZE: u32 = GROUP_SIZE * VALUES_PER_THREAD;
+
+/// The operator used to combine the values in a segment.
+///
+/// The aggregate for an empty segment is the operator's identity element: `0` for [Sum], the
+/// type's maximum value for [Min] and the type's minimum value for [Max] (for `f32` these are
+/// positive and negative infinity respectively).
+///
+/// [Sum]: ReduceOperator::Sum
+/// [Min]: ReduceOperator::Min
+/// [Max]: ReduceOperator::Max
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReduceOperator {
+    Sum,
+    Min,
+    Max,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a, T>
+where
+    T: abi::Sized,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    max_count: Uniform<'a, u32>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    data: Storage<'a, [T]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    segment_offsets: Storage<'a, [u32]>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    partials: Storage<'a, [[T; 2]], ReadWrite>,
+    #[resource(binding = 4, visibility = "COMPUTE")]
+    output: Storage<'a, [T], ReadWrite>,
+}
+
+type ResourcesLayout<T> = <Resources<'static, T> as empa::resource_binding::Resources>::Layout;
+
+/// The input for a [SegmentedReduce].
+///
+/// The `segment_offsets` must be sorted; segment `i` covers the `data` elements in the range
+/// `segment_offsets[i]..segment_offsets[i + 1]`, the last segment extends to the end of the
+/// data (or to `count`, if specified). The `data` elements before `segment_offsets[0]` do not
+/// belong to any segment and do not contribute to any aggregate.
+pub struct SegmentedReduceInput<'a, T, U0, U1> {
+    pub data: buffer::View<'a, [T], U0>,
+    pub segment_offsets: buffer::View<'a, [u32], U1>,
+    pub count: Option<Uniform<'a, u32>>,
+}
+
+/// Reduces each segment of a segmented buffer into a single aggregate value.
+///
+/// The data is processed in fixed size tiles independent of the segment boundaries, so the
+/// performance does not depend on the distribution of the segment sizes.
+pub struct SegmentedReduce<T>
+where
+    T: abi::Sized,
+{
+    device: Device,
+    fallback_count: UniformCache<u32>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout<T>>,
+    reduce_tiles_pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+    resolve_segments_pipeline: ComputePipeline<(ResourcesLayout<T>,)>,
+    partials: Buffer<[[T; 2]], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    generate_dispatch: GenerateDispatch,
+    group_size: Buffer<u32, buffer::Usages<O, O, O, X, O, O, O, O, O, O>>,
+    dispatch: Buffer<DispatchWorkgroups, buffer::Usages<O, X, X, O, O, O, O, O, O, O>>,
+}
+
+impl<T> SegmentedReduce<T>
+where
+    T: abi::Sized + 'static,
+{
+    pub fn requirements() -> Requirements {
+        // The segment's values and segment indices are stored in workgroup memory
+        Requirements::new(GROUP_SIZE, SEGMENT_SIZE * 4 * 2, 4)
+    }
+
+    async fn init_internal(device: Device, shader_source: &ShaderSource) -> Self {
+        let shader = device.create_shader_module(shader_source);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout<T>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let create_reduce_tiles_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&shader, "reduce_tiles").finish())
+                .finish(),
+        );
+        let create_resolve_segments_pipeline = device.create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(&pipeline_layout)
+                .compute(ComputeStageBuilder::begin(&shader, "resolve_segments").finish())
+                .finish(),
+        );
+        let init_generate_dispatch = GenerateDispatch::init(device.clone());
+
+        let (reduce_tiles_pipeline, resolve_segments_pipeline, generate_dispatch) = join!(
+            create_reduce_tiles_pipeline,
+            create_resolve_segments_pipeline,
+            init_generate_dispatch
+        );
+
+        let partials = device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+        let group_size = device.create_buffer(SEGMENT_SIZE, buffer::Usages::uniform_binding());
+        let dispatch = device.create_buffer(
+            DispatchWorkgroups {
+                count_x: 1,
+                count_y: 1,
+                count_z: 1,
+            },
+            buffer::Usages::storage_binding().and_indirect(),
+        );
+
+        let fallback_count = UniformCache::new(&device, 0);
+
+        SegmentedReduce {
+            device,
+            fallback_count,
+            bind_group_layout,
+            reduce_tiles_pipeline,
+            resolve_segments_pipeline,
+            partials,
+            generate_dispatch,
+            group_size,
+            dispatch,
+        }
+    }
+
+    /// Shrinks the buffer of per-tile partial results to what reducing up to `max_count` values
+    /// requires. The buffer grows again as needed.
+    pub fn shrink_to_fit(&mut self, max_count: u32) {
+        let tiles = div_ceil(max_count, SEGMENT_SIZE).max(1) as usize;
+
+        if self.partials.len() > tiles {
+            self.partials = self
+                .device
+                .create_slice_buffer_zeroed(tiles, self.partials.usage());
+        }
+    }
+
+    /// Encodes the reduction of each segment into `output`.
+    ///
+    /// The `output` must hold at least as many elements as there are `segment_offsets`. If the
+    /// `data` is empty, then nothing is encoded and the `output` is left as is.
+    pub fn encode<U0, U1, U2>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: SegmentedReduceInput<T, U0, U1>,
+        output: buffer::View<[T], U2>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding,
+    {
+        let SegmentedReduceInput {
+            data,
+            segment_offsets,
+            count,
+        } = input;
+
+        assert!(
+            output.len() >= segment_offsets.len(),
+            "the output must hold at least one element for every segment"
+        );
+
+        if is_empty_input(data.len()) || is_empty_input(segment_offsets.len()) {
+            return encoder;
+        }
+
+        let dispatch_indirect = count.is_some();
+
+        self.fallback_count.update(&self.device, data.len() as u32);
+
+        let count = CountBuffer::new(count, &self.fallback_count);
+
+        let tiles = div_ceil(data.len() as u32, SEGMENT_SIZE);
+
+        if self.partials.len() < tiles as usize {
+            self.partials = self
+                .device
+                .create_slice_buffer_zeroed(tiles as usize, self.partials.usage());
+        }
+
+        if dispatch_indirect {
+            encoder = self.generate_dispatch.encode(
+                encoder,
+                GenerateDispatchResources {
+                    group_size: self.group_size.uniform(),
+                    count: count.uniform(),
+                    dispatch: self.dispatch.storage(),
+                    max_count: None,
+                },
+            );
+        }
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                max_count: count.uniform(),
+                data: data.storage(),
+                segment_offsets: segment_offsets.storage(),
+                partials: self.partials.storage(),
+                output: output.storage(),
+            },
+        );
+
+        let encoder = encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.reduce_tiles_pipeline)
+            .set_bind_groups(&bind_group);
+
+        let encoder = if dispatch_indirect {
+            encoder
+                .dispatch_workgroups_indirect(self.dispatch.view())
+                .end()
+        } else {
+            encoder
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: tiles,
+                    count_y: 1,
+                    count_z: 1,
+                })
+                .end()
+        };
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.resolve_segments_pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: div_ceil(segment_offsets.len() as u32, GROUP_SIZE),
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
+
+impl<T> SegmentedReduce<T>
+where
+    T: RadixKey,
+{
+    pub async fn init(device: Device, operator: ReduceOperator) -> Self {
+        let shader_source = match (T::KEY_TYPE, operator) {
+            (KeyType::U32, ReduceOperator::Sum) => &SHADER_SUM_U32,
+            (KeyType::U32, ReduceOperator::Min) => &SHADER_MIN_U32,
+            (KeyType::U32, ReduceOperator::Max) => &SHADER_MAX_U32,
+            (KeyType::I32, ReduceOperator::Sum) => &SHADER_SUM_I32,
+            (KeyType::I32, ReduceOperator::Min) => &SHADER_MIN_I32,
+            (KeyType::I32, ReduceOperator::Max) => &SHADER_MAX_I32,
+            (KeyType::F32, ReduceOperator::Sum) => &SHADER_SUM_F32,
+            (KeyType::F32, ReduceOperator::Min) => &SHADER_MIN_F32,
+            (KeyType::F32, ReduceOperator::Max) => &SHADER_MAX_F32,
+        };
+
+        Self::init_internal(device, shader_source).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device, operator: ReduceOperator) -> Self {
+        pollster::block_on(Self::init(device, operator))
+    }
+}
+
+impl SegmentedReduce<u32> {
+    pub async fn init_u32(device: Device, operator: ReduceOperator) -> Self {
+        Self::init(device, operator).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_u32_blocking(device: Device, operator: ReduceOperator) -> Self {
+        pollster::block_on(Self::init_u32(device, operator))
+    }
+}
+
+impl SegmentedReduce<i32> {
+    pub async fn init_i32(device: Device, operator: ReduceOperator) -> Self {
+        Self::init(device, operator).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_i32_blocking(device: Device, operator: ReduceOperator) -> Self {
+        pollster::block_on(Self::init_i32(device, operator))
+    }
+}
+
+impl SegmentedReduce<f32> {
+    pub async fn init_f32(device: Device, operator: ReduceOperator) -> Self {
+        Self::init(device, operator).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_f32_blocking(device: Device, operator: ReduceOperator) -> Self {
+        pollster::block_on(Self::init_f32(device, operator))
+    }
+}
@@ -0,0 +1,190 @@
+#include "../dispatch.wgsl"
+
+// Reduces the data in fixed size tiles, independent of how the data is divided into segments, so that the work is
+// evenly distributed regardless of the segment sizes. Segments that lie entirely within a single tile are resolved
+// directly by the `reduce_tiles` pass. For every tile, we also store the partial aggregates for the tile's first and
+// last segments; the `resolve_segments` pass then combines these partial aggregates for all segments that span
+// multiple tiles.
+
+const GROUP_SIZE = 256u;
+const VALUES_PER_THREAD = 4u;
+const SEGMENT_SIZE = 1024u; // GROUP_SIZE * VALUES_PER_THREAD;
+
+const PARTIAL_FIRST = 0u;
+const PARTIAL_LAST = 1u;
+
+// Marks the data before the first segment, and the unused positions of a partial tile
+const NO_SEGMENT = 0xFFFFFFFFu;
+
+@group(0) @binding(0)
+var<uniform> max_count: u32;
+
+@group(0) @binding(1)
+var<storage, read> data: array<DATA_TYPE>;
+
+@group(0) @binding(2)
+var<storage, read> segment_offsets: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read_write> partials: array<array<DATA_TYPE, 2>>;
+
+@group(0) @binding(4)
+var<storage, read_write> output: array<DATA_TYPE>;
+
+var<workgroup> local_values: array<DATA_TYPE, SEGMENT_SIZE>;
+
+var<workgroup> local_segments: array<u32, SEGMENT_SIZE>;
+
+fn resolve_count() -> u32 {
+    return min(max_count, arrayLength(&data));
+}
+
+fn segment_start(segment: u32) -> u32 {
+    return min(segment_offsets[segment], resolve_count());
+}
+
+fn segment_end(segment: u32) -> u32 {
+    if segment + 1 < arrayLength(&segment_offsets) {
+        return min(segment_offsets[segment + 1], resolve_count());
+    } else {
+        return resolve_count();
+    }
+}
+
+// Finds the last segment that starts at or before the given data index, or `NO_SEGMENT` if the index precedes the
+// first segment.
+fn find_segment(data_index: u32) -> u32 {
+    var lo = 0u;
+    var hi = arrayLength(&segment_offsets);
+
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+
+        if segment_offsets[mid] <= data_index {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    if lo == 0 {
+        return NO_SEGMENT;
+    }
+
+    return lo - 1;
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn reduce_tiles(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let count = resolve_count();
+    let tile_index = linear_workgroup_index(workgroup_id, num_workgroups);
+    let tile_start = tile_index * SEGMENT_SIZE;
+
+    if tile_start >= count {
+        return;
+    }
+
+    let tile_end = min(tile_start + SEGMENT_SIZE, count);
+    let tile_size = tile_end - tile_start;
+
+    for (var i = local_index; i < SEGMENT_SIZE; i += GROUP_SIZE) {
+        if i < tile_size {
+            local_values[i] = data[tile_start + i];
+            local_segments[i] = find_segment(tile_start + i);
+        } else {
+            local_values[i] = identity();
+            local_segments[i] = NO_SEGMENT;
+        }
+    }
+
+    workgroupBarrier();
+
+    // Segmented inclusive scan: because the segment indices are monotonic, any two elements with the same segment index
+    // only have elements of that same segment in between.
+    for (var i = 1u; i < SEGMENT_SIZE; i <<= 1u) {
+        var values: array<DATA_TYPE, VALUES_PER_THREAD>;
+
+        for (var j = 0u; j < VALUES_PER_THREAD; j += 1u) {
+            let index = j * GROUP_SIZE + local_index;
+
+            if index >= i && local_segments[index - i] == local_segments[index] {
+                values[j] = combine(local_values[index - i], local_values[index]);
+            } else {
+                values[j] = local_values[index];
+            }
+        }
+
+        workgroupBarrier();
+
+        for (var j = 0u; j < VALUES_PER_THREAD; j += 1u) {
+            let index = j * GROUP_SIZE + local_index;
+
+            local_values[index] = values[j];
+        }
+
+        workgroupBarrier();
+    }
+
+    let first_segment = local_segments[0];
+    let last_segment = local_segments[tile_size - 1];
+
+    for (var j = 0u; j < VALUES_PER_THREAD; j += 1u) {
+        let index = j * GROUP_SIZE + local_index;
+
+        if index < tile_size {
+            let segment = local_segments[index];
+            let is_segment_tail = index == tile_size - 1 || local_segments[index + 1] != segment;
+
+            if is_segment_tail && segment != NO_SEGMENT {
+                let aggregate = local_values[index];
+
+                if segment_start(segment) >= tile_start && segment_end(segment) <= tile_end {
+                    output[segment] = aggregate;
+                }
+
+                if segment == first_segment {
+                    partials[tile_index][PARTIAL_FIRST] = aggregate;
+                }
+
+                if segment == last_segment {
+                    partials[tile_index][PARTIAL_LAST] = aggregate;
+                }
+            }
+        }
+    }
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn resolve_segments(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let segment = global_id.x;
+
+    if segment >= arrayLength(&segment_offsets) {
+        return;
+    }
+
+    let start = segment_start(segment);
+    let end = segment_end(segment);
+
+    if start >= end {
+        output[segment] = identity();
+
+        return;
+    }
+
+    let first_tile = start / SEGMENT_SIZE;
+    let last_tile = (end - 1) / SEGMENT_SIZE;
+
+    // Segments that lie within a single tile were already resolved by the `reduce_tiles` pass
+    if first_tile == last_tile {
+        return;
+    }
+
+    // The segment is the last segment of its first tile, and the first segment of every subsequent tile it spans
+    var aggregate = partials[first_tile][PARTIAL_LAST];
+
+    for (var tile = first_tile + 1; tile <= last_tile; tile += 1u) {
+        aggregate = combine(aggregate, partials[tile][PARTIAL_FIRST]);
+    }
+
+    output[segment] = aggregate;
+}
@@ -0,0 +1,15 @@
+alias DATA_TYPE = f32;
+
+// Bitcasting a constant expression to a non-finite float is a shader-creation error, so we bitcast from a private
+// variable instead.
+var<private> NEGATIVE_INFINITY_BITS: u32 = 0xFF800000u;
+
+fn identity() -> f32 {
+    return bitcast<f32>(NEGATIVE_INFINITY_BITS);
+}
+
+fn combine(a: f32, b: f32) -> f32 {
+    return max(a, b);
+}
+
+#include "shader_core.wgsl"
@@ -0,0 +1,11 @@
+alias DATA_TYPE = i32;
+
+fn identity() -> i32 {
+    return -2147483647i - 1i;
+}
+
+fn combine(a: i32, b: i32) -> i32 {
+    return max(a, b);
+}
+
+#include "shader_core.wgsl"
@@ -0,0 +1,15 @@
+alias DATA_TYPE = f32;
+
+// Bitcasting a constant expression to a non-finite float is a shader-creation error, so we bitcast from a private
+// variable instead.
+var<private> INFINITY_BITS: u32 = 0x7F800000u;
+
+fn identity() -> f32 {
+    return bitcast<f32>(INFINITY_BITS);
+}
+
+fn combine(a: f32, b: f32) -> f32 {
+    return min(a, b);
+}
+
+#include "shader_core.wgsl"
@@ -0,0 +1,11 @@
+alias DATA_TYPE = f32;
+
+fn identity() -> f32 {
+    return 0.0f;
+}
+
+fn combine(a: f32, b: f32) -> f32 {
+    return a + b;
+}
+
+#include "shader_core.wgsl"
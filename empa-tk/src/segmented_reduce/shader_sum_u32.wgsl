@@ -0,0 +1,11 @@
+alias DATA_TYPE = u32;
+
+fn identity() -> u32 {
+    return 0u;
+}
+
+fn combine(a: u32, b: u32) -> u32 {
+    return a + b;
+}
+
+#include "shader_core.wgsl"
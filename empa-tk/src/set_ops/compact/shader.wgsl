@@ -0,0 +1,42 @@
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<storage, read> merged: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read> kept_offsets: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read_write> output: array<u32>;
+
+@group(0) @binding(4)
+var<storage, read_write> result_count: u32;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    let data_count = min(count, min(arrayLength(&merged), arrayLength(&kept_offsets)));
+
+    if index >= data_count {
+        return;
+    }
+
+    // The offsets are an inclusive prefix sum over the keep flags, so an element is kept if its offset differs from
+    // the offset of the preceding element.
+    let offset = kept_offsets[index];
+
+    var previous = 0u;
+
+    if index > 0 {
+        previous = kept_offsets[index - 1];
+    }
+
+    if offset != previous && offset - 1 < arrayLength(&output) {
+        output[offset - 1] = merged[index];
+    }
+
+    if index == data_count - 1 {
+        result_count = offset;
+    }
+}
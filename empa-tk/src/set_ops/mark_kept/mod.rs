@@ -0,0 +1,102 @@
+use bytemuck::Zeroable;
+use empa::abi;
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Storage, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::{BindGroupLayout, Resources};
+use empa::shader_module::{shader_source, ShaderSource};
+
+use crate::requirements::Requirements;
+use crate::util::div_ceil;
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+const GROUP_SIZE: u32 = 256;
+const VALUES_PER_THREAD: u32 = 8;
+
+const SEGMENT_SIZE: u32 = GROUP_SIZE * VALUES_PER_THREAD;
+
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug, Zeroable)]
+#[repr(C)]
+pub struct MarkKeptUniforms {
+    pub a_len: u32,
+    pub b_len: u32,
+    pub operation: u32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+pub struct MarkKeptResources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    pub uniforms: Uniform<'a, MarkKeptUniforms>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    pub a: Storage<'a, [u32]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    pub b: Storage<'a, [u32]>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    pub merged: Storage<'a, [u32], ReadWrite>,
+    #[resource(binding = 4, visibility = "COMPUTE")]
+    pub kept: Storage<'a, [u32], ReadWrite>,
+}
+
+type ResourcesLayout = <MarkKeptResources<'static> as Resources>::Layout;
+
+pub struct MarkKept {
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: ComputePipeline<(ResourcesLayout,)>,
+}
+
+impl MarkKept {
+    pub fn requirements() -> Requirements {
+        // The bounds of the segment's range in the first input are shared in workgroup memory
+        Requirements::new(GROUP_SIZE, 2 * 4, 4)
+    }
+
+    pub async fn init(device: Device) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        MarkKept {
+            device,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    pub fn encode(
+        &self,
+        encoder: CommandEncoder,
+        resources: MarkKeptResources,
+        total: u32,
+    ) -> CommandEncoder {
+        let bind_group = self
+            .device
+            .create_bind_group(&self.bind_group_layout, resources);
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: div_ceil(total, SEGMENT_SIZE),
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
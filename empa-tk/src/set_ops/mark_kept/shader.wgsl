@@ -0,0 +1,115 @@
+const GROUP_SIZE = 256u;
+const VALUES_PER_THREAD = 8u;
+const SEGMENT_SIZE = 2048u; // GROUP_SIZE * VALUES_PER_THREAD;
+
+const OPERATION_INTERSECTION = 0u;
+const OPERATION_UNION = 1u;
+const OPERATION_DIFFERENCE = 2u;
+
+struct Uniforms {
+    a_len: u32,
+    b_len: u32,
+    operation: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+@group(0) @binding(1)
+var<storage, read> a: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read> b: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read_write> merged: array<u32>;
+
+@group(0) @binding(4)
+var<storage, read_write> kept: array<u32>;
+
+var<workgroup> a_start: u32;
+var<workgroup> a_end: u32;
+
+// Finds the number of elements from `a` that precede the given `diagonal` on the merge path, searching only between
+// `lo_bound` and `hi_bound`. Ties resolve in favor of `a`, so that an element from `a` is merged directly before an
+// equal element from `b`.
+fn merge_path(diagonal: u32, lo_bound: u32, hi_bound: u32) -> u32 {
+    let b_len = uniforms.b_len;
+
+    var lo = max(select(0u, diagonal - b_len, diagonal > b_len), lo_bound);
+    var hi = min(min(diagonal, uniforms.a_len), hi_bound);
+
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+
+        if a[mid] <= b[diagonal - mid - 1] {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    return lo;
+}
+
+@compute @workgroup_size(GROUP_SIZE, 1, 1)
+fn main(@builtin(workgroup_id) workgroup_id: vec3<u32>, @builtin(local_invocation_index) local_index: u32) {
+    let a_len = uniforms.a_len;
+    let b_len = uniforms.b_len;
+    let operation = uniforms.operation;
+
+    let total = a_len + b_len;
+    let diagonal_start = workgroup_id.x * SEGMENT_SIZE;
+    let diagonal_end = min(diagonal_start + SEGMENT_SIZE, total);
+
+    // Resolve the workgroup's part of `a` once, so that the threads only search within the workgroup's segment
+    if local_index == 0 {
+        a_start = merge_path(diagonal_start, 0, a_len);
+        a_end = merge_path(diagonal_end, 0, a_len);
+    }
+
+    workgroupBarrier();
+
+    let thread_diagonal = min(diagonal_start + local_index * VALUES_PER_THREAD, diagonal_end);
+
+    var i = merge_path(thread_diagonal, a_start, a_end);
+    var j = thread_diagonal - i;
+
+    for (var k = 0u; k < VALUES_PER_THREAD; k += 1u) {
+        let index = thread_diagonal + k;
+
+        if index >= diagonal_end {
+            break;
+        }
+
+        var value: u32;
+        var keep: bool;
+
+        if j >= b_len || (i < a_len && a[i] <= b[j]) {
+            value = a[i];
+
+            // An equal element in `b` is merged directly after this element
+            let matched = j < b_len && b[j] == value;
+
+            keep = (operation == OPERATION_INTERSECTION && matched) ||
+                operation == OPERATION_UNION ||
+                (operation == OPERATION_DIFFERENCE && !matched);
+
+            i += 1u;
+        } else {
+            value = b[j];
+
+            // An equal element in `a` was merged directly before this element
+            let matched = i > 0 && a[i - 1] == value;
+
+            // Matched elements are represented by the element from `a`, so an element from `b` is only ever kept if it
+            // is unique to `b` and the operation is a union
+            keep = operation == OPERATION_UNION && !matched;
+
+            j += 1u;
+        }
+
+        merged[index] = value;
+        kept[index] = u32(keep);
+    }
+}
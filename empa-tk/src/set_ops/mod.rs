@@ -0,0 +1,232 @@
+use empa::buffer;
+use empa::buffer::{Buffer, Storage};
+use empa::command::CommandEncoder;
+use empa::device::Device;
+use empa::type_flag::{O, X};
+use futures::join;
+
+use crate::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
+use crate::requirements::Requirements;
+use crate::set_ops::compact::{Compact, CompactResources};
+use crate::set_ops::mark_kept::{MarkKept, MarkKeptResources, MarkKeptUniforms};
+use crate::uniform_cache::UniformCache;
+use crate::util::is_empty_input;
+
+mod compact;
+mod mark_kept;
+
+/// The operation a [SetOps] evaluates.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SetOperation {
+    /// Keeps the elements that occur in both `a` and `b`.
+    Intersection,
+    /// Keeps the elements that occur in either `a` or `b`.
+    Union,
+    /// Keeps the elements that occur in `a`, but not in `b`.
+    Difference,
+}
+
+impl SetOperation {
+    fn to_u32(self) -> u32 {
+        match self {
+            SetOperation::Intersection => 0,
+            SetOperation::Union => 1,
+            SetOperation::Difference => 2,
+        }
+    }
+
+    /// The maximum number of elements the result of this operation may hold for inputs with the
+    /// given lengths.
+    pub fn max_result_len(&self, a_len: usize, b_len: usize) -> usize {
+        match self {
+            SetOperation::Intersection => a_len.min(b_len),
+            SetOperation::Union => a_len + b_len,
+            SetOperation::Difference => a_len,
+        }
+    }
+}
+
+pub struct SetOpsInput<'a, U0, U1> {
+    /// A sorted set of values; must not contain duplicates.
+    pub a: buffer::View<'a, [u32], U0>,
+    /// A sorted set of values; must not contain duplicates.
+    pub b: buffer::View<'a, [u32], U1>,
+}
+
+pub struct SetOpsOutput<'a, U0, U1> {
+    /// Receives the number of elements in the result.
+    pub result_count: buffer::View<'a, u32, U0>,
+    /// Receives the sorted elements of the result; must hold at least
+    /// [SetOperation::max_result_len] elements.
+    pub data: buffer::View<'a, [u32], U1>,
+}
+
+/// Evaluates the intersection, union or difference of two sorted sets.
+///
+/// The two sets are merged along the "merge path", such that every workgroup processes an
+/// equally sized part of the merged sequence independently, regardless of how the lengths of `a`
+/// and `b` relate. Because the merge places an element from `a` directly before an equal element
+/// from `b`, each element can tell whether it occurs in both sets by comparing against its
+/// neighbour from the other set. The elements the operation keeps are then compacted into the
+/// output.
+pub struct SetOps {
+    device: Device,
+    uniforms: UniformCache<MarkKeptUniforms>,
+    total_count: UniformCache<u32>,
+    mark_kept: MarkKept,
+    prefix_sum_inclusive: PrefixSum<u32>,
+    compact: Compact,
+    merged: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    kept: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    empty_set: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+}
+
+impl SetOps {
+    pub fn requirements() -> Requirements {
+        PrefixSum::<u32>::requirements().max(MarkKept::requirements())
+    }
+
+    pub async fn init(device: Device) -> Self {
+        let (mark_kept, prefix_sum_inclusive, compact) = join!(
+            MarkKept::init(device.clone()),
+            PrefixSum::init_inclusive_u32(device.clone()),
+            Compact::init(device.clone()),
+        );
+
+        let merged = device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+        let kept = device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+        let empty_set = device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+
+        let uniforms = UniformCache::new(
+            &device,
+            MarkKeptUniforms {
+                a_len: 0,
+                b_len: 0,
+                operation: 0,
+            },
+        );
+        let total_count = UniformCache::new(&device, 0);
+
+        SetOps {
+            device,
+            uniforms,
+            total_count,
+            mark_kept,
+            prefix_sum_inclusive,
+            compact,
+            merged,
+            kept,
+            empty_set,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init(device))
+    }
+
+    /// Encodes the evaluation of the set `operation` over `input.a` and `input.b` into `output`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `output.data` holds fewer elements than [SetOperation::max_result_len] for the
+    /// lengths of `input.a` and `input.b`.
+    pub fn encode<U0, U1, U2, U3>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        operation: SetOperation,
+        input: SetOpsInput<U0, U1>,
+        output: SetOpsOutput<U2, U3>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding + buffer::CopyDst + 'static,
+        U3: buffer::StorageBinding,
+    {
+        let SetOpsInput { a, b } = input;
+        let SetOpsOutput { result_count, data } = output;
+
+        let max_result_len = operation.max_result_len(a.len(), b.len());
+
+        assert!(
+            data.len() >= max_result_len,
+            "the output must be able to hold the largest possible result of the operation"
+        );
+
+        // The result is necessarily empty
+        if is_empty_input(max_result_len) {
+            return encoder.clear_buffer(result_count);
+        }
+
+        let total = (a.len() + b.len()) as u32;
+
+        self.uniforms.update(
+            &self.device,
+            MarkKeptUniforms {
+                a_len: a.len() as u32,
+                b_len: b.len() as u32,
+                operation: operation.to_u32(),
+            },
+        );
+        self.total_count.update(&self.device, total);
+
+        if self.merged.len() < total as usize {
+            self.merged = self
+                .device
+                .create_slice_buffer_zeroed(total as usize, self.merged.usage());
+            self.kept = self
+                .device
+                .create_slice_buffer_zeroed(total as usize, self.kept.usage());
+        }
+
+        // Only one of the sets may be empty here; it is substituted by a placeholder, as its length
+        // is passed separately
+        let a: Storage<[u32]> = if a.len() == 0 {
+            self.empty_set.storage()
+        } else {
+            a.storage()
+        };
+        let b: Storage<[u32]> = if b.len() == 0 {
+            self.empty_set.storage()
+        } else {
+            b.storage()
+        };
+
+        encoder = self.mark_kept.encode(
+            encoder,
+            MarkKeptResources {
+                uniforms: self.uniforms.uniform(),
+                a,
+                b,
+                merged: self.merged.storage(),
+                kept: self.kept.storage(),
+            },
+            total,
+        );
+
+        // The temporary buffers may be larger than the merged sequence, so the scan is limited to
+        // its length
+        encoder = self.prefix_sum_inclusive.encode(
+            encoder,
+            PrefixSumInput {
+                data: self.kept.view(),
+                count: Some(self.total_count.uniform()),
+                initial: None,
+                order: ScanOrder::Ascending,
+            },
+        );
+
+        self.compact.encode(
+            encoder,
+            CompactResources {
+                count: self.total_count.uniform(),
+                merged: self.merged.storage(),
+                kept_offsets: self.kept.storage(),
+                output: data.storage(),
+                result_count: result_count.storage(),
+            },
+            total,
+        )
+    }
+}
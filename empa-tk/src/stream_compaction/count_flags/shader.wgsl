@@ -0,0 +1,29 @@
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<storage, read> flags: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read_write> word_offsets: array<u32>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let word_index = global_id.x;
+    let word_count = min((count + 31u) / 32u, min(arrayLength(&flags), arrayLength(&word_offsets)));
+
+    if word_index >= word_count {
+        return;
+    }
+
+    var word = flags[word_index];
+
+    // The bits beyond the count in the last word do not flag values
+    let remainder = count % 32u;
+
+    if word_index == word_count - 1 && remainder != 0 {
+        word &= (1u << remainder) - 1u;
+    }
+
+    word_offsets[word_index] = countOneBits(word);
+}
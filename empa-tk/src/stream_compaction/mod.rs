@@ -0,0 +1,185 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer;
+use empa::buffer::{Buffer, Storage};
+use empa::command::CommandEncoder;
+use empa::device::Device;
+use empa::type_flag::{O, X};
+use futures::join;
+
+use crate::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
+use crate::requirements::Requirements;
+use crate::stream_compaction::count_flags::{CountFlags, CountFlagsResources};
+use crate::stream_compaction::scatter_kept::{ScatterKept, ScatterKeptResources};
+use crate::uniform_cache::UniformCache;
+use crate::util::{div_ceil, is_empty_input};
+
+mod count_flags;
+mod scatter_kept;
+
+const GROUP_SIZE: u32 = 256;
+
+pub struct StreamCompactionInput<'a, U0, U1> {
+    /// The values to compact.
+    pub data: buffer::View<'a, [u32], U0>,
+    /// The flags that select the values to keep, packed into words of 32 flags each: the value at
+    /// index `i` is kept if bit `i % 32` of the word at index `i / 32` is set.
+    ///
+    /// Must hold at least `data.len().div_ceil(32)` words. The bits in the last word that lie
+    /// beyond the length of `data` are ignored.
+    pub flags: buffer::View<'a, [u32], U1>,
+}
+
+pub struct StreamCompactionOutput<'a, U0, U1> {
+    /// Receives the number of values that were kept.
+    pub kept_count: buffer::View<'a, u32, U0>,
+    /// Receives the kept values, in their original order, in the first `kept_count` elements.
+    ///
+    /// The length of this buffer is its capacity: if more values are kept than it can hold, then
+    /// only the first kept values are written. The `kept_count` still receives the actual number
+    /// of kept values, so compare it against the capacity to detect an overflow.
+    pub data: buffer::View<'a, [u32], U1>,
+}
+
+/// Compacts the values selected by bit-packed flags into a contiguous output, preserving their
+/// order.
+///
+/// Rather than scanning one flag per value, the number of set flags in each word is counted and
+/// only the per-word counts are scanned, so the scan processes 32 times fewer elements. A value's
+/// output position is then its word's offset plus the number of set flags that precede it within
+/// its word.
+pub struct StreamCompaction {
+    device: Device,
+    count: UniformCache<u32>,
+    word_count: UniformCache<u32>,
+    count_flags: CountFlags,
+    prefix_sum_exclusive: PrefixSum<u32>,
+    scatter_kept: ScatterKept,
+    word_offsets: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+    empty_output: Buffer<[u32], buffer::Usages<O, O, X, O, O, O, O, O, O, O>>,
+}
+
+impl StreamCompaction {
+    pub fn requirements() -> Requirements {
+        PrefixSum::<u32>::requirements().max(Requirements::new(GROUP_SIZE, 0, 5))
+    }
+
+    pub async fn init(device: Device) -> Self {
+        let (count_flags, prefix_sum_exclusive, scatter_kept) = join!(
+            CountFlags::init(device.clone()),
+            PrefixSum::init_exclusive_u32(device.clone()),
+            ScatterKept::init(device.clone()),
+        );
+
+        let word_offsets = device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+        let empty_output = device.create_slice_buffer_zeroed(1, buffer::Usages::storage_binding());
+
+        let count = UniformCache::new(&device, 0);
+        let word_count = UniformCache::new(&device, 0);
+
+        StreamCompaction {
+            device,
+            count,
+            word_count,
+            count_flags,
+            prefix_sum_exclusive,
+            scatter_kept,
+            word_offsets,
+            empty_output,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_blocking(device: Device) -> Self {
+        pollster::block_on(Self::init(device))
+    }
+
+    /// Encodes the compaction of the values in `input.data` for which the corresponding bit in
+    /// `input.flags` is set into `output`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input.flags` holds fewer than `input.data.len().div_ceil(32)` words.
+    pub fn encode<U0, U1, U2, U3>(
+        &mut self,
+        mut encoder: CommandEncoder,
+        input: StreamCompactionInput<U0, U1>,
+        output: StreamCompactionOutput<U2, U3>,
+    ) -> CommandEncoder
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::StorageBinding + buffer::CopyDst + 'static,
+        U3: buffer::StorageBinding,
+    {
+        let StreamCompactionInput { data, flags } = input;
+        let StreamCompactionOutput {
+            kept_count,
+            data: output_data,
+        } = output;
+
+        let count = data.len() as u32;
+        let word_count = div_ceil(count, 32);
+
+        assert!(
+            flags.len() >= word_count as usize,
+            "`flags` must hold a bit for every value in `data`"
+        );
+
+        // Nothing is kept from an empty input
+        if is_empty_input(count as usize) {
+            return encoder.clear_buffer(kept_count);
+        }
+
+        // An empty view cannot be bound, but the kept count must still be resolved, so an empty
+        // output is substituted by a placeholder
+        let output_data: Storage<[u32], ReadWrite> = if output_data.len() == 0 {
+            self.empty_output.storage()
+        } else {
+            output_data.storage()
+        };
+
+        self.count.update(&self.device, count);
+        self.word_count.update(&self.device, word_count);
+
+        if self.word_offsets.len() < word_count as usize {
+            self.word_offsets = self
+                .device
+                .create_slice_buffer_zeroed(word_count as usize, self.word_offsets.usage());
+        }
+
+        encoder = self.count_flags.encode(
+            encoder,
+            CountFlagsResources {
+                count: self.count.uniform(),
+                flags: flags.storage(),
+                word_offsets: self.word_offsets.storage(),
+            },
+            word_count,
+        );
+
+        // The word offsets buffer may be larger than the number of words, so the scan is limited
+        // to the number of words
+        encoder = self.prefix_sum_exclusive.encode(
+            encoder,
+            PrefixSumInput {
+                data: self.word_offsets.view(),
+                count: Some(self.word_count.uniform()),
+                initial: None,
+                order: ScanOrder::Ascending,
+            },
+        );
+
+        self.scatter_kept.encode(
+            encoder,
+            ScatterKeptResources {
+                count: self.count.uniform(),
+                data: data.storage(),
+                flags: flags.storage(),
+                word_offsets: self.word_offsets.storage(),
+                output: output_data,
+                kept_count: kept_count.storage(),
+            },
+            count,
+        )
+    }
+}
@@ -0,0 +1,44 @@
+@group(0) @binding(0)
+var<uniform> count: u32;
+
+@group(0) @binding(1)
+var<storage, read> data: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read> flags: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read> word_offsets: array<u32>;
+
+@group(0) @binding(4)
+var<storage, read_write> output: array<u32>;
+
+@group(0) @binding(5)
+var<storage, read_write> kept_count: u32;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    let data_count = min(count, arrayLength(&data));
+
+    if index >= data_count {
+        return;
+    }
+
+    let word_index = index / 32u;
+    let bit = index % 32u;
+    let word = flags[word_index];
+    let is_kept = (word >> bit) & 1u;
+
+    // The word offsets are an exclusive prefix sum over the number of flagged values in each word, so a value's
+    // position in the output is its word's offset plus the number of flagged values that precede it in its word.
+    let offset = word_offsets[word_index] + countOneBits(word & ((1u << bit) - 1u));
+
+    if is_kept == 1u && offset < arrayLength(&output) {
+        output[offset] = data[index];
+    }
+
+    if index == data_count - 1 {
+        kept_count = offset + is_kept;
+    }
+}
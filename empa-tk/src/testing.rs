@@ -0,0 +1,41 @@
+//! Utilities for reproducing the results of operations on the host, e.g. to verify a sort in a
+//! regression test.
+//!
+//! The generators are deterministic: the same `seed` and `count` always produce the same values,
+//! so that a failing input can be reproduced from just its seed and count.
+
+use crate::radix_key::RadixKey;
+
+/// Generates `count` pseudo-random `u32` values from the `seed`.
+pub fn random_u32(seed: u64, count: usize) -> Vec<u32> {
+    let mut rng = oorandom::Rand32::new(seed);
+
+    (0..count).map(|_| rng.rand_u32()).collect()
+}
+
+/// Generates `count` pseudo-random `i32` values from the `seed`.
+pub fn random_i32(seed: u64, count: usize) -> Vec<i32> {
+    let mut rng = oorandom::Rand32::new(seed);
+
+    (0..count).map(|_| rng.rand_i32()).collect()
+}
+
+/// Generates `count` pseudo-random `f32` values in the range `-1_000_000.0..1_000_000.0` from the
+/// `seed`.
+pub fn random_f32(seed: u64, count: usize) -> Vec<f32> {
+    let mut rng = oorandom::Rand32::new(seed);
+
+    (0..count)
+        .map(|_| rng.rand_float() * 2_000_000.0 - 1_000_000.0)
+        .collect()
+}
+
+/// Sorts the `data` on the host in the same order as a [RadixSort](crate::radix_sort::RadixSort).
+///
+/// See [radix_sort_cpu](crate::cpu::radix_sort_cpu).
+pub fn sort_reference<T>(data: &mut [T])
+where
+    T: RadixKey + Copy,
+{
+    crate::cpu::radix_sort_cpu(data);
+}
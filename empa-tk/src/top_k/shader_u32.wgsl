@@ -0,0 +1,7 @@
+alias DATA_TYPE = u32;
+
+fn to_key(value: u32) -> u32 {
+    return value;
+}
+
+#include "shader_core.wgsl"
@@ -0,0 +1,39 @@
+use empa::abi;
+use empa::buffer::{Buffer, Uniform, Usages};
+use empa::device::Device;
+use empa::type_flag::{O, X};
+
+/// A uniform buffer that is only recreated when the value it holds changes.
+///
+/// Avoids allocating a new buffer on every encode when an operation is repeatedly encoded with
+/// the same parameters (e.g. for buffers of the same size).
+pub struct UniformCache<T>
+where
+    T: abi::Sized,
+{
+    value: T,
+    buffer: Buffer<T, Usages<O, O, O, X, O, O, O, O, O, O>>,
+}
+
+impl<T> UniformCache<T>
+where
+    T: abi::Sized + PartialEq + Copy + 'static,
+{
+    pub fn new(device: &Device, value: T) -> Self {
+        UniformCache {
+            value,
+            buffer: device.create_buffer(value, Usages::uniform_binding()),
+        }
+    }
+
+    pub fn update(&mut self, device: &Device, value: T) {
+        if self.value != value {
+            self.value = value;
+            self.buffer = device.create_buffer(value, Usages::uniform_binding());
+        }
+    }
+
+    pub fn uniform(&self) -> Uniform<T> {
+        self.buffer.uniform()
+    }
+}
@@ -0,0 +1,45 @@
+use empa::shader_module::ShaderSource;
+
+/// Divides `a` by `b`, rounding up.
+///
+/// Equivalent to `u32::div_ceil`; unlike `(a + b - 1) / b`, this does not overflow for an `a`
+/// close to `u32::MAX`.
+pub(crate) fn div_ceil(a: u32, b: u32) -> u32 {
+    a / b + (a % b != 0) as u32
+}
+
+/// Whether an input of `len` values is empty.
+///
+/// An empty buffer view cannot be bound, so operations check their inputs with this before
+/// creating any bind groups, and return early for an empty input (after writing any outputs that
+/// must reflect the empty input, such as counts or bins) instead of dispatching no workgroups.
+pub(crate) fn is_empty_input(len: usize) -> bool {
+    len == 0
+}
+
+/// The shader for a pipeline with a configurable workgroup size.
+///
+/// A custom group size or number of values per thread is templated into the shader code, rather
+/// than set through WGSL `override` constants: `VALUES_PER_THREAD` sizes function-scope arrays,
+/// which requires a creation-time constant, and empa does not expose pipeline constants on
+/// `ComputeStageBuilder`, so not even `GROUP_SIZE` can be specialized at pipeline creation.
+pub(crate) enum ShaderCode {
+    /// The shader for the default configuration, validated when the crate is compiled.
+    Validated(&'static ShaderSource),
+    /// Shader code generated from a template for a custom configuration.
+    Template(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn div_ceil_matches_std() {
+        for b in [1, 2, 3, 255, 256, u32::MAX - 1, u32::MAX] {
+            for a in [0, 1, b - 1, b, b.saturating_add(1), u32::MAX] {
+                assert_eq!(div_ceil(a, b), a.div_ceil(b), "a = {}, b = {}", a, b);
+            }
+        }
+    }
+}
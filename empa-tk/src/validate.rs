@@ -0,0 +1,13 @@
+/// Asserts a host-side precondition on the inputs to an `encode` call.
+///
+/// Only checked when the `validate` feature is enabled; otherwise the condition is never
+/// evaluated and the check compiles out.
+macro_rules! validate {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "validate") {
+            assert!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use validate;
@@ -1,21 +1,162 @@
+use std::error::Error;
 use std::fmt::Write;
-use std::mem;
 use std::ops::Rem;
+use std::{fmt, mem};
 
-pub fn write_value_type<V>(s: &mut String) {
-    let size = mem::size_of::<V>();
+use empa::device::Device;
 
-    if size.rem(4) != 0 {
-        panic!("Expected an `abi::Sized` type's size to be a multiple of 4")
+/// The maximum size (in bytes) of a value type that can be moved by operations such as
+/// [GatherBy](crate::gather_by::GatherBy).
+///
+/// Values are moved as structs of `u32` fields; larger values result in shaders that some
+/// backends fail to compile. Consider moving an index instead and gathering larger values in a
+/// separate pass.
+pub const MAX_VALUE_TYPE_SIZE: usize = 256;
+
+/// Error returned when a value type cannot be used with an operation that moves values of that
+/// type (e.g. [GatherBy](crate::gather_by::GatherBy)).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValueTypeError {
+    /// The value type is zero-sized. There are no values to move; use the operation's variant that
+    /// does not take values instead (e.g. a [RadixSort](crate::radix_sort::RadixSort) rather than
+    /// a [RadixSortBy](crate::radix_sort::RadixSortBy)).
+    ZeroSized,
+    /// The size of the value type (in bytes) is not a multiple of `4`.
+    UnalignedSize { size: usize },
+    /// The alignment of the value type (in bytes) exceeds `4`, the alignment of the `u32` fields
+    /// in which the generated shaders lay out a value.
+    ExceedsAlignment { align: usize },
+    /// The size of the value type (in bytes) exceeds the limit, see [MAX_VALUE_TYPE_SIZE]. The
+    /// limit may be lower than [MAX_VALUE_TYPE_SIZE] if the device's storage buffer binding size
+    /// limit is lower.
+    ExceedsSizeLimit { size: usize, limit: usize },
+}
+
+impl fmt::Display for ValueTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueTypeError::ZeroSized => write!(
+                f,
+                "the value type is zero-sized; use an operation that does not move values instead \
+                 (e.g. `RadixSort` rather than `RadixSortBy`)"
+            ),
+            ValueTypeError::UnalignedSize { size } => write!(
+                f,
+                "expected the value type's size to be a multiple of 4, found a size of {}",
+                size
+            ),
+            ValueTypeError::ExceedsAlignment { align } => write!(
+                f,
+                "expected the value type's alignment to be at most 4, found an alignment of {}",
+                align
+            ),
+            ValueTypeError::ExceedsSizeLimit { size, limit } => write!(
+                f,
+                "the value type's size of {} bytes exceeds the limit of {} bytes",
+                size, limit
+            ),
+        }
     }
+}
 
-    write!(s, "struct VALUE_TYPE {{").unwrap();
+impl Error for ValueTypeError {}
 
-    let field_count = size / 4;
+/// The number of `u32` words in which values of type `V` are moved by operations such as
+/// [GatherBy](crate::gather_by::GatherBy), [ScatterBy](crate::scatter_by::ScatterBy) and
+/// [RadixSortBy](crate::radix_sort::RadixSortBy).
+///
+/// The shaders of these operations lay out a value of type `V` as a struct of this many `u32`
+/// fields (or an equivalent array of `vec4<u32>`). Only meaningful if `V` passes
+/// [check_value_type].
+pub fn value_type_words<V>() -> usize {
+    mem::size_of::<V>() / 4
+}
+
+/// Checks whether values of type `V` can be moved by operations such as
+/// [GatherBy](crate::gather_by::GatherBy) on the `device`, without initializing such an operation.
+///
+/// The generated layout (see [value_type_words]) is a struct of `u32` fields, so `V` must have a
+/// size that is a multiple of `4` and an alignment of at most `4`; a `#[repr(C)]` struct of
+/// 32-bit fields (or arrays thereof) is compatible. Note that only the value's bytes are moved;
+/// any padding within `V` is moved along with the fields.
+pub fn check_value_type<V>(device: &Device) -> Result<(), ValueTypeError> {
+    value_type_field_count::<V>(device).map(|_| ())
+}
+
+/// The WGSL declaration of the `VALUE_TYPE` struct with which the shaders of operations such as
+/// [GatherBy](crate::gather_by::GatherBy) are generated for values of type `V` on the `device`.
+///
+/// The generated shaders are only validated when the device compiles them, so a device error
+/// that reports a shader compilation failure for an unusual value type can be matched against
+/// this declaration. Operations that move values without accessing their fields lay out a value
+/// whose size is a multiple of `16` as an array of `vec4<u32>` words instead.
+pub fn value_type_declaration<V>(device: &Device) -> Result<String, ValueTypeError> {
+    let mut declaration = String::new();
+
+    write_value_type::<V>(device, &mut declaration)?;
+
+    Ok(declaration)
+}
+
+pub fn write_value_type<V>(device: &Device, s: &mut String) -> Result<(), ValueTypeError> {
+    let field_count = value_type_field_count::<V>(device)?;
+
+    write!(s, "struct VALUE_TYPE {{").unwrap();
 
     for i in 0..field_count {
         write!(s, "    field_{}: u32,\n", i).unwrap();
     }
 
     write!(s, "}}\n\n").unwrap();
+
+    Ok(())
+}
+
+/// Writes a `VALUE_TYPE` for shaders that only move values, without accessing their fields.
+///
+/// If the size of the value type is a multiple of `16`, the value is laid out as an array of
+/// `vec4<u32>`, so that a value is moved with vectorized loads and stores; otherwise this is
+/// equivalent to [write_value_type].
+pub fn write_opaque_value_type<V>(device: &Device, s: &mut String) -> Result<(), ValueTypeError> {
+    let field_count = value_type_field_count::<V>(device)?;
+
+    if field_count.rem(4) == 0 {
+        write!(
+            s,
+            "struct VALUE_TYPE {{\n    words: array<vec4<u32>, {}>,\n}}\n\n",
+            field_count / 4
+        )
+        .unwrap();
+
+        Ok(())
+    } else {
+        write_value_type::<V>(device, s)
+    }
+}
+
+fn value_type_field_count<V>(device: &Device) -> Result<usize, ValueTypeError> {
+    let size = mem::size_of::<V>();
+
+    // A struct without fields is not valid WGSL
+    if size == 0 {
+        return Err(ValueTypeError::ZeroSized);
+    }
+
+    if size.rem(4) != 0 {
+        return Err(ValueTypeError::UnalignedSize { size });
+    }
+
+    let align = mem::align_of::<V>();
+
+    if align > 4 {
+        return Err(ValueTypeError::ExceedsAlignment { align });
+    }
+
+    let limit = MAX_VALUE_TYPE_SIZE.min(device.limits().max_storage_buffer_binding_size as usize);
+
+    if size > limit {
+        return Err(ValueTypeError::ExceedsSizeLimit { size, limit });
+    }
+
+    Ok(value_type_words::<V>())
 }
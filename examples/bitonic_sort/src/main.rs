@@ -0,0 +1,124 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::{Device, DeviceDescriptor};
+use empa::native::Instance;
+use empa_tk::bitonic::{BitonicSort, BitonicSortInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut bitonic_sort = BitonicSort::init_u32(device.clone()).await;
+
+    // A count that fits in a single workgroup, and a count that requires the multi-workgroup
+    // network; neither is a power of 2.
+    sort_and_check(&device, &mut bitonic_sort, 1_500, false).await?;
+    sort_and_check(&device, &mut bitonic_sort, 1_500, true).await?;
+    sort_and_check(&device, &mut bitonic_sort, 100_003, false).await?;
+    sort_and_check(&device, &mut bitonic_sort, 100_003, true).await?;
+
+    Ok(())
+}
+
+async fn sort_and_check(
+    device: &Device,
+    bitonic_sort: &mut BitonicSort<u32>,
+    count: usize,
+    descending: bool,
+) -> Result<(), Box<dyn Error>> {
+    println!(
+        "Sorting {} values in {} order...",
+        count,
+        if descending {
+            "descending"
+        } else {
+            "ascending"
+        }
+    );
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        data.push(rng.rand_u32());
+    }
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let timestamp_query_set = device.create_timestamp_query_set(2);
+    let timestamps =
+        device.create_slice_buffer_zeroed(2, buffer::Usages::query_resolve().and_copy_src());
+    let timestamps_readback =
+        device.create_slice_buffer_zeroed(2, buffer::Usages::copy_dst().and_map_read());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = encoder.write_timestamp(&timestamp_query_set, 0);
+    encoder = bitonic_sort.encode(
+        encoder,
+        BitonicSortInput {
+            data: data_buffer.view(),
+            descending,
+            count: None,
+        },
+    );
+    encoder = encoder.write_timestamp(&timestamp_query_set, 1);
+
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+    encoder = encoder.resolve_timestamp_query_set(&timestamp_query_set, 0, timestamps.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(timestamps.view(), timestamps_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    data.sort();
+
+    if descending {
+        data.reverse();
+    }
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    println!("Asserting all values produced by the GPU sort match the values produced by the CPU sort...");
+
+    for i in 0..count {
+        assert_eq!(&readback[i], &data[i]);
+    }
+
+    println!("...successfully!");
+
+    mem::drop(readback);
+
+    readback_buffer.unmap();
+
+    timestamps_readback.map_read().await?;
+
+    let timestamps = timestamps_readback.mapped();
+    let time_elapsed = timestamps[1] - timestamps[0];
+
+    println!("Time elapsed: {} nanoseconds", time_elapsed);
+
+    mem::drop(timestamps);
+
+    timestamps_readback.unmap();
+
+    Ok(())
+}
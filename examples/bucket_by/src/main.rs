@@ -0,0 +1,131 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+    let bin_count = 100;
+
+    println!("Scattering {} values into {} bins...", count, bin_count);
+
+    let mut bucket_by = BucketBy::<u32>::init(device.clone(), bin_count).await?;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut bin_ids: Vec<u32> = Vec::with_capacity(count);
+    let mut values: Vec<u32> = Vec::with_capacity(count);
+
+    for i in 0..count {
+        bin_ids.push(rng.rand_range(0..bin_count));
+        values.push(i as u32);
+    }
+
+    let bin_ids_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*bin_ids, buffer::Usages::storage_binding());
+    let values_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*values, buffer::Usages::storage_binding());
+    let output_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let bin_offsets_buffer: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
+        bin_count as usize,
+        buffer::Usages::storage_binding()
+            .and_copy_dst()
+            .and_copy_src(),
+    );
+
+    let output_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let bin_offsets_readback_buffer: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
+        bin_count as usize,
+        buffer::Usages::map_read().and_copy_dst(),
+    );
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = bucket_by.encode(
+        encoder,
+        BucketByInput {
+            bin_ids: bin_ids_buffer.view(),
+            values: values_buffer.view(),
+            count: None,
+        },
+        BucketByOutput {
+            values: output_buffer.view(),
+            bin_offsets: bin_offsets_buffer.view(),
+        },
+    );
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(output_buffer.view(), output_readback_buffer.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        bin_offsets_buffer.view(),
+        bin_offsets_readback_buffer.view(),
+    );
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected_offsets = vec![0u32; bin_count as usize];
+
+    for bin_id in &bin_ids {
+        if let Some(next) = expected_offsets.get_mut(*bin_id as usize + 1) {
+            *next += 1;
+        }
+    }
+
+    for i in 1..expected_offsets.len() {
+        expected_offsets[i] += expected_offsets[i - 1];
+    }
+
+    output_readback_buffer.map_read().await?;
+    bin_offsets_readback_buffer.map_read().await?;
+
+    let output = output_readback_buffer.mapped();
+    let bin_offsets = bin_offsets_readback_buffer.mapped();
+
+    println!("Asserting the bin offsets match the CPU bin offsets...");
+
+    assert_eq!(&bin_offsets[..], &expected_offsets[..]);
+
+    println!("...successfully!");
+
+    println!("Asserting every value was scattered into its bin exactly once...");
+
+    let mut seen = vec![false; count];
+
+    for bin in 0..bin_count as usize {
+        let start = bin_offsets[bin] as usize;
+        let end = bin_offsets
+            .get(bin + 1)
+            .map(|end| *end as usize)
+            .unwrap_or(count);
+
+        // The order within a bin is unspecified, so only check that each value belongs to the bin
+        for value in &output[start..end] {
+            assert_eq!(bin_ids[*value as usize], bin as u32);
+            assert!(!seen[*value as usize]);
+
+            seen[*value as usize] = true;
+        }
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
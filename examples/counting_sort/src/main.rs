@@ -0,0 +1,76 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::counting_sort::{CountingSort, CountingSortInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+    let k = 1000;
+
+    println!("Sorting {} keys in the range `0..{}`...", count, k);
+
+    let mut counting_sort = CountingSort::init(device.clone()).await;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        data.push(rng.rand_range(0..k));
+    }
+
+    let keys_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let output_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = counting_sort.encode(
+        encoder,
+        CountingSortInput {
+            keys: keys_buffer.view(),
+            k,
+            count: None,
+        },
+        output_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    data.sort();
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    println!("Asserting all values produced by the GPU sort match the values produced by the CPU sort...");
+
+    for i in 0..count {
+        assert_eq!(readback[i], data[i]);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
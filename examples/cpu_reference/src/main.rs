@@ -0,0 +1,144 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::cpu::{find_runs_cpu, prefix_sum_exclusive_cpu, radix_sort_cpu};
+use empa_tk::find_runs::{FindRuns, FindRunsInput};
+use empa_tk::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
+use empa_tk::radix_sort::{RadixSort, RadixSortInput};
+use empa_tk::{read_back, read_back_vec};
+use futures::FutureExt;
+
+// Runs a radix sort, a prefix sum and a run search on a small input on the GPU, and verifies the
+// results against the host implementations of the same operations.
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 50;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let data: Vec<u32> = (0..count).map(|_| rng.rand_range(0..10)).collect();
+
+    println!("Input data: {:?}", data);
+
+    let mut radix_sort = RadixSort::init_u32(device.clone()).await;
+    let mut prefix_sum = PrefixSum::init_exclusive_u32(device.clone()).await;
+    let mut find_runs = FindRuns::init_u32(device.clone()).await;
+
+    let sort_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let sort_temp_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let prefix_sum_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let find_runs_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let find_runs_output = FindRuns::<u32>::output_buffers(&device, count);
+
+    let sort_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let prefix_sum_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let run_count_readback_buffer: Buffer<u32, _> =
+        device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+    let run_starts_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let run_mapping_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortInput {
+            data: sort_buffer.view(),
+            temporary_storage: sort_temp_buffer.view(),
+            count: None,
+        },
+    );
+    encoder = prefix_sum.encode(
+        encoder,
+        PrefixSumInput {
+            data: prefix_sum_buffer.view(),
+            count: None,
+            initial: None,
+            order: ScanOrder::Ascending,
+        },
+    );
+    encoder = find_runs.encode(
+        encoder,
+        FindRunsInput {
+            data: find_runs_buffer.view(),
+            count: None,
+        },
+        find_runs_output.view(),
+    );
+
+    encoder = encoder.copy_buffer_to_buffer_slice(sort_buffer.view(), sort_readback_buffer.view());
+    encoder = encoder
+        .copy_buffer_to_buffer_slice(prefix_sum_buffer.view(), prefix_sum_readback_buffer.view());
+    encoder = encoder.copy_buffer_to_buffer(
+        find_runs_output.run_count.view(),
+        run_count_readback_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        find_runs_output.run_starts.view(),
+        run_starts_readback_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        find_runs_output.run_mapping.view(),
+        run_mapping_readback_buffer.view(),
+    );
+
+    device.queue().submit(encoder.finish());
+
+    println!("Asserting the GPU sort matches the CPU sort...");
+
+    let mut expected_sorted = data.clone();
+
+    radix_sort_cpu(&mut expected_sorted);
+
+    assert_eq!(read_back_vec(&sort_readback_buffer).await?, expected_sorted);
+
+    println!("...successfully!");
+    println!("Asserting the GPU prefix sum matches the CPU prefix sum...");
+
+    let mut expected_prefix_sum = data.clone();
+
+    prefix_sum_exclusive_cpu(&mut expected_prefix_sum);
+
+    assert_eq!(
+        read_back_vec(&prefix_sum_readback_buffer).await?,
+        expected_prefix_sum
+    );
+
+    println!("...successfully!");
+    println!("Asserting the GPU runs match the CPU runs...");
+
+    let expected_runs = find_runs_cpu(&data);
+    let run_count = read_back(&run_count_readback_buffer).await? as usize;
+    let run_starts = read_back_vec(&run_starts_readback_buffer).await?;
+    let run_mapping = read_back_vec(&run_mapping_readback_buffer).await?;
+
+    assert_eq!(run_count, expected_runs.run_starts.len());
+    assert_eq!(&run_starts[..run_count], &expected_runs.run_starts[..]);
+    assert_eq!(run_mapping, expected_runs.run_mapping);
+
+    println!("...successfully!");
+
+    Ok(())
+}
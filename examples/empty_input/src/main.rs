@@ -0,0 +1,150 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let len = 1000;
+
+    println!("Running operators with an indirect count of 0...");
+
+    let data: Vec<u32> = (0..len as u32).rev().collect();
+
+    let mut radix_sort = RadixSort::<u32>::init(device.clone()).await;
+    let mut prefix_sum = PrefixSum::<u32>::init_inclusive(device.clone()).await;
+    let mut find_runs = FindRuns::<u32>::init(device.clone()).await;
+    let mut gather_by = GatherBy::init_u32(device.clone()).await?;
+    let mut scatter_by = ScatterBy::init_u32(device.clone()).await?;
+
+    let count_buffer: Buffer<u32, _> = device.create_buffer(0, buffer::Usages::uniform_binding());
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let temp_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(len, buffer::Usages::storage_binding());
+    let by_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(len, buffer::Usages::storage_binding());
+    let gather_output_buffer: Buffer<[u32], _> = device.create_buffer(
+        vec![u32::MAX; len],
+        buffer::Usages::storage_binding().and_copy_src(),
+    );
+    let scatter_output_buffer: Buffer<[u32], _> = device.create_buffer(
+        vec![u32::MAX; len],
+        buffer::Usages::storage_binding().and_copy_src(),
+    );
+    let run_count_buffer: Buffer<u32, _> =
+        device.create_buffer(u32::MAX, buffer::Usages::storage_binding().and_copy_src());
+    let run_starts_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(len, buffer::Usages::storage_binding());
+    let run_mapping_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(len, buffer::Usages::storage_binding().and_copy_dst());
+
+    let data_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(len, buffer::Usages::map_read().and_copy_dst());
+    let gather_output_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(len, buffer::Usages::map_read().and_copy_dst());
+    let scatter_output_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(len, buffer::Usages::map_read().and_copy_dst());
+    let run_count_readback: Buffer<u32, _> =
+        device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortInput {
+            data: data_buffer.view(),
+            temporary_storage: temp_storage_buffer.view(),
+            count: Some(Count::Indirect(count_buffer.uniform())),
+        },
+    );
+    encoder = prefix_sum.encode(
+        encoder,
+        PrefixSumInput {
+            data: data_buffer.view(),
+            count: Some(count_buffer.uniform()),
+            initial: None,
+            order: ScanOrder::Ascending,
+        },
+    );
+    encoder = find_runs.encode(
+        encoder,
+        FindRunsInput {
+            data: data_buffer.view(),
+            count: Some(count_buffer.uniform()),
+        },
+        FindRunsOutput {
+            run_count: run_count_buffer.view(),
+            run_starts: run_starts_buffer.view(),
+            run_mapping: run_mapping_buffer.view(),
+        },
+    );
+    encoder = gather_by.encode(
+        encoder,
+        GatherByInput {
+            gather_by: by_buffer.view(),
+            data: data_buffer.view(),
+            count: Some(count_buffer.uniform()),
+            index_transform: None,
+        },
+        gather_output_buffer.view(),
+    );
+    encoder = scatter_by.encode(
+        encoder,
+        ScatterByInput {
+            scatter_by: by_buffer.view(),
+            data: data_buffer.view(),
+            count: Some(count_buffer.uniform()),
+            index_transform: None,
+        },
+        scatter_output_buffer.view(),
+    );
+
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), data_readback.view());
+    encoder = encoder
+        .copy_buffer_to_buffer_slice(gather_output_buffer.view(), gather_output_readback.view());
+    encoder = encoder
+        .copy_buffer_to_buffer_slice(scatter_output_buffer.view(), scatter_output_readback.view());
+    encoder = encoder.copy_buffer_to_buffer(run_count_buffer.view(), run_count_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    data_readback.map_read().await?;
+    gather_output_readback.map_read().await?;
+    scatter_output_readback.map_read().await?;
+    run_count_readback.map_read().await?;
+
+    println!("Asserting the data and the outputs were left untouched, and no runs were found...");
+
+    assert_eq!(&*data_readback.mapped(), &*data);
+    assert!(gather_output_readback
+        .mapped()
+        .iter()
+        .all(|v| *v == u32::MAX));
+    assert!(scatter_output_readback
+        .mapped()
+        .iter()
+        .all(|v| *v == u32::MAX));
+    assert_eq!(*run_count_readback.mapped(), 0);
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -6,7 +6,7 @@ use empa::buffer;
 use empa::buffer::Buffer;
 use empa::device::DeviceDescriptor;
 use empa::native::Instance;
-use empa_tk::find_runs::{FindRuns, FindRunsInput, FindRunsOutput};
+use empa_tk::find_runs::{FindRuns, FindRunsInput};
 use futures::FutureExt;
 
 fn main() {
@@ -41,21 +41,14 @@ async fn run() -> Result<(), Box<dyn Error>> {
     let data_buffer: Buffer<[u32], _> =
         device.create_buffer(data, buffer::Usages::storage_binding().and_copy_src());
 
-    let run_count_buffer: Buffer<u32, _> =
-        device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_src());
-    let run_starts_buffer: Buffer<[u32], _> =
-        device.create_slice_buffer_zeroed(total, buffer::Usages::storage_binding().and_copy_src());
-    let run_mapping_buffer: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
-        total,
-        buffer::Usages::storage_binding()
-            .and_copy_dst()
-            .and_copy_src(),
-    );
+    let output_buffers = FindRuns::<u32>::output_buffers(&device, total);
 
     let run_count_readback_buffer: Buffer<u32, _> =
         device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
     let run_starts_readback_buffer: Buffer<[u32], _> =
         device.create_slice_buffer_zeroed(total, buffer::Usages::map_read().and_copy_dst());
+    let run_mapping_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(total, buffer::Usages::map_read().and_copy_dst());
 
     let timestamp_query_set = device.create_timestamp_query_set(2);
     let timestamps =
@@ -72,18 +65,22 @@ async fn run() -> Result<(), Box<dyn Error>> {
             data: data_buffer.view(),
             count: None,
         },
-        FindRunsOutput {
-            run_count: run_count_buffer.view(),
-            run_starts: run_starts_buffer.view(),
-            run_mapping: run_mapping_buffer.view(),
-        },
+        output_buffers.view(),
     );
     encoder = encoder.write_timestamp(&timestamp_query_set, 1);
 
-    encoder = encoder
-        .copy_buffer_to_buffer_slice(run_starts_buffer.view(), run_starts_readback_buffer.view());
-    encoder =
-        encoder.copy_buffer_to_buffer(run_count_buffer.view(), run_count_readback_buffer.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        output_buffers.run_starts.view(),
+        run_starts_readback_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        output_buffers.run_mapping.view(),
+        run_mapping_readback_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer(
+        output_buffers.run_count.view(),
+        run_count_readback_buffer.view(),
+    );
     encoder = encoder.resolve_timestamp_query_set(&timestamp_query_set, 0, timestamps.view());
     encoder = encoder.copy_buffer_to_buffer_slice(timestamps.view(), timestamps_readback.view());
 
@@ -132,6 +129,30 @@ async fn run() -> Result<(), Box<dyn Error>> {
 
     run_starts_readback_buffer.unmap();
 
+    run_mapping_readback_buffer.map_read().await?;
+
+    let run_mapping = run_mapping_readback_buffer.mapped();
+
+    println!(
+        "Asserting the run index of each value computed on the GPU matches the expected index..."
+    );
+
+    let mut i = 0;
+
+    for (run_index, count) in counts.iter().copied().enumerate() {
+        for _ in 0..count {
+            assert_eq!(run_mapping[i], run_index as u32);
+
+            i += 1;
+        }
+    }
+
+    println!("...successfully!");
+
+    mem::drop(run_mapping);
+
+    run_mapping_readback_buffer.unmap();
+
     timestamps_readback.map_read().await?;
 
     let timestamps = timestamps_readback.mapped();
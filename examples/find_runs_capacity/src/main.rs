@@ -0,0 +1,120 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let counts = [1000, 2000, 3000, 4000, 5000, 6000, 7000, 8000, 9000, 10000];
+    let total = counts.iter().sum::<u32>() as usize;
+
+    let mut data: Vec<u32> = Vec::with_capacity(total);
+    let mut expected_run_starts = Vec::with_capacity(counts.len());
+
+    for count in counts.iter().copied() {
+        expected_run_starts.push(data.len() as u32);
+
+        for _ in 0..count {
+            data.push(count);
+        }
+    }
+
+    println!(
+        "Finding {} runs with a run starts buffer sized to the number of runs, and with one that \
+         is too small...",
+        counts.len()
+    );
+
+    let mut find_runs = FindRuns::init_u32(device.clone()).await;
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let run_mapping_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(total, buffer::Usages::storage_binding().and_copy_dst());
+
+    let capacities = [counts.len(), counts.len() / 2];
+
+    let mut run_count_buffers = Vec::new();
+    let mut run_starts_buffers = Vec::new();
+    let mut run_count_readback_buffers = Vec::new();
+    let mut run_starts_readback_buffers = Vec::new();
+
+    for capacity in capacities {
+        let run_count_buffer: Buffer<u32, _> =
+            device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_src());
+        let run_starts_buffer: Buffer<[u32], _> = device
+            .create_slice_buffer_zeroed(capacity, buffer::Usages::storage_binding().and_copy_src());
+        let run_count_readback_buffer: Buffer<u32, _> =
+            device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+        let run_starts_readback_buffer: Buffer<[u32], _> =
+            device.create_slice_buffer_zeroed(capacity, buffer::Usages::map_read().and_copy_dst());
+
+        run_count_buffers.push(run_count_buffer);
+        run_starts_buffers.push(run_starts_buffer);
+        run_count_readback_buffers.push(run_count_readback_buffer);
+        run_starts_readback_buffers.push(run_starts_readback_buffer);
+    }
+
+    let mut encoder = device.create_command_encoder();
+
+    for i in 0..capacities.len() {
+        encoder = find_runs.encode(
+            encoder,
+            FindRunsInput {
+                data: data_buffer.view(),
+                count: None,
+            },
+            FindRunsOutput {
+                run_count: run_count_buffers[i].view(),
+                run_starts: run_starts_buffers[i].view(),
+                run_mapping: run_mapping_buffer.view(),
+            },
+        );
+        encoder = encoder.copy_buffer_to_buffer(
+            run_count_buffers[i].view(),
+            run_count_readback_buffers[i].view(),
+        );
+        encoder = encoder.copy_buffer_to_buffer_slice(
+            run_starts_buffers[i].view(),
+            run_starts_readback_buffers[i].view(),
+        );
+    }
+
+    device.queue().submit(encoder.finish());
+
+    println!("Asserting the runs that fit are found, and that an overflow is detectable...");
+
+    for (i, capacity) in capacities.iter().copied().enumerate() {
+        run_count_readback_buffers[i].map_read().await?;
+        run_starts_readback_buffers[i].map_read().await?;
+
+        let run_count = *run_count_readback_buffers[i].mapped() as usize;
+        let run_starts = run_starts_readback_buffers[i].mapped();
+
+        // The run count reports all runs, also those that did not fit
+        assert_eq!(run_count, counts.len());
+        assert_eq!(run_count > capacity, capacity < counts.len());
+        assert_eq!(&run_starts[..], &expected_run_starts[..capacity]);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
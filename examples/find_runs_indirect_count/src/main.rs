@@ -0,0 +1,114 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::find_runs::{FindRuns, FindRunsInput};
+use empa_tk::{read_back, read_back_vec};
+use futures::FutureExt;
+
+// Searches the runs in only the first part of an over-allocated buffer, with the number of live
+// values provided on the device, and verifies that the values beyond the count (which would each
+// start a run of their own) do not contribute to the runs.
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let capacity = 100_000;
+    let count = 61_234;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(capacity);
+    let mut expected_run_starts = Vec::new();
+
+    // Runs of random lengths in the range `1..8` for the live values
+    while data.len() < count {
+        let value = data.last().map(|v| v + 1).unwrap_or(0);
+        let len = (rng.rand_range(1..8) as usize).min(count - data.len());
+
+        expected_run_starts.push(data.len() as u32);
+        data.extend(std::iter::repeat(value).take(len));
+    }
+
+    // Distinct values beyond the count
+    while data.len() < capacity {
+        data.push(u32::MAX - data.len() as u32);
+    }
+
+    println!(
+        "Finding the runs in the first {} values of a buffer of {} values...",
+        count, capacity
+    );
+
+    let mut find_runs = FindRuns::init_u32(device.clone()).await;
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let count_buffer: Buffer<u32, _> =
+        device.create_buffer(count as u32, buffer::Usages::uniform_binding());
+
+    let output_buffers = FindRuns::<u32>::output_buffers(&device, capacity);
+
+    let run_count_readback: Buffer<u32, _> =
+        device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+    let run_starts_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(capacity, buffer::Usages::map_read().and_copy_dst());
+    let run_mapping_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(capacity, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = find_runs.encode(
+        encoder,
+        FindRunsInput {
+            data: data_buffer.view(),
+            count: Some(count_buffer.uniform()),
+        },
+        output_buffers.view(),
+    );
+    encoder =
+        encoder.copy_buffer_to_buffer(output_buffers.run_count.view(), run_count_readback.view());
+    encoder = encoder
+        .copy_buffer_to_buffer_slice(output_buffers.run_starts.view(), run_starts_readback.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        output_buffers.run_mapping.view(),
+        run_mapping_readback.view(),
+    );
+
+    device.queue().submit(encoder.finish());
+
+    let run_count = read_back(&run_count_readback).await? as usize;
+    let run_starts = read_back_vec(&run_starts_readback).await?;
+    let run_mapping = read_back_vec(&run_mapping_readback).await?;
+
+    println!("Asserting the run count only counts the runs within the count...");
+
+    assert_eq!(run_count, expected_run_starts.len());
+
+    println!("...successfully!");
+    println!("Asserting the run starts and run mapping match the expected runs...");
+
+    assert_eq!(&run_starts[..run_count], &expected_run_starts[..]);
+
+    for i in 0..count {
+        let expected = expected_run_starts.partition_point(|start| *start <= i as u32) - 1;
+
+        assert_eq!(run_mapping[i], expected as u32, "mismatch at index {}", i);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
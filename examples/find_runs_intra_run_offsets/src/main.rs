@@ -0,0 +1,94 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::find_runs::{FindRuns, FindRunsInput};
+use empa_tk::read_back_vec;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+
+    // Runs of random lengths in the range `1..1000`, so that runs span several workgroups
+    while data.len() < count {
+        let value = data.last().map(|v| v + 1).unwrap_or(0);
+        let len = (rng.rand_range(1..1000) as usize).min(count - data.len());
+
+        data.extend(std::iter::repeat(value).take(len));
+    }
+
+    println!(
+        "Finding the offset of each value within its run for {} values...",
+        count
+    );
+
+    let mut find_runs = FindRuns::init_u32(device.clone()).await;
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+
+    let output_buffers = FindRuns::<u32>::output_buffers(&device, count);
+
+    let intra_run_offsets_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+
+    let intra_run_offsets_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = find_runs.encode_with_intra_run_offsets(
+        encoder,
+        FindRunsInput {
+            data: data_buffer.view(),
+            count: None,
+        },
+        output_buffers.view(),
+        intra_run_offsets_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        intra_run_offsets_buffer.view(),
+        intra_run_offsets_readback.view(),
+    );
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected = Vec::with_capacity(count);
+
+    for i in 0..count {
+        if i == 0 || data[i] != data[i - 1] {
+            expected.push(0);
+        } else {
+            expected.push(expected[i - 1] + 1);
+        }
+    }
+
+    let intra_run_offsets = read_back_vec(&intra_run_offsets_readback).await?;
+
+    println!("Asserting the offset of each value within its run matches the expected offset...");
+
+    assert_eq!(intra_run_offsets, expected);
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::find_runs::{FindRuns, FindRunsInput};
+use empa_tk::{read_back, read_back_vec};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+
+    // Runs of random lengths in the range `1..8`, so that many runs are singletons
+    while data.len() < count {
+        let value = data.last().map(|v| v + 1).unwrap_or(0);
+        let len = (rng.rand_range(1..8) as usize).min(count - data.len());
+
+        data.extend(std::iter::repeat(value).take(len));
+    }
+
+    let mut find_runs = FindRuns::init_u32(device.clone()).await;
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+
+    for min_length in [1, 2, 5, 8] {
+        println!(
+            "Finding the runs of at least {} values in {} values...",
+            min_length, count
+        );
+
+        let output_buffers = FindRuns::<u32>::output_buffers(&device, count);
+
+        let run_count_readback: Buffer<u32, _> =
+            device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+        let run_starts_readback: Buffer<[u32], _> =
+            device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+        let mut encoder = device.create_command_encoder();
+
+        encoder = find_runs.encode_with_min_length(
+            encoder,
+            FindRunsInput {
+                data: data_buffer.view(),
+                count: None,
+            },
+            output_buffers.view(),
+            min_length,
+        );
+        encoder = encoder
+            .copy_buffer_to_buffer(output_buffers.run_count.view(), run_count_readback.view());
+        encoder = encoder.copy_buffer_to_buffer_slice(
+            output_buffers.run_starts.view(),
+            run_starts_readback.view(),
+        );
+
+        device.queue().submit(encoder.finish());
+
+        let mut expected = Vec::new();
+        let mut start = 0;
+
+        for i in 1..=count {
+            if i == count || data[i] != data[i - 1] {
+                if i - start >= min_length as usize {
+                    expected.push(start as u32);
+                }
+
+                start = i;
+            }
+        }
+
+        let run_count = read_back(&run_count_readback).await? as usize;
+
+        assert_eq!(run_count, expected.len());
+
+        let run_starts = read_back_vec(&run_starts_readback).await?;
+
+        assert_eq!(&run_starts[..run_count], &expected[..]);
+
+        println!("...successfully!");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+    let array_count = 4;
+
+    println!(
+        "Gathering {} parallel arrays of numbers by the same indices...",
+        array_count
+    );
+
+    let mut by: Vec<u32> = Vec::with_capacity(count);
+
+    for i in 0..count as u32 {
+        by.push(count as u32 - 1 - i);
+    }
+
+    let mut gather_by_multi = GatherByMulti::<u32, u32>::init_u32(device.clone()).await?;
+
+    let arrays: Vec<Vec<u32>> = (0..array_count as u32)
+        .map(|j| (0..count as u32).map(|i| i * 10 + j).collect())
+        .collect();
+
+    let by_buffer: Buffer<[u32], _> = device.create_buffer(&*by, buffer::Usages::storage_binding());
+    let data_buffers: Vec<Buffer<[u32], _>> = arrays
+        .iter()
+        .map(|array| device.create_buffer(&**array, buffer::Usages::storage_binding()))
+        .collect();
+    let output_buffers: Vec<Buffer<[u32], _>> = (0..array_count)
+        .map(|_| {
+            device
+                .create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src())
+        })
+        .collect();
+    let readback_buffers: Vec<Buffer<[u32], _>> = (0..array_count)
+        .map(|_| {
+            device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst())
+        })
+        .collect();
+
+    let data_views: Vec<_> = data_buffers.iter().map(|buffer| buffer.view()).collect();
+    let output_views: Vec<_> = output_buffers.iter().map(|buffer| buffer.view()).collect();
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = gather_by_multi.encode(
+        encoder,
+        GatherByMultiInput {
+            gather_by: by_buffer.view(),
+            data: &data_views,
+            count: None,
+        },
+        &output_views,
+    );
+
+    for (output_buffer, readback_buffer) in output_buffers.iter().zip(&readback_buffers) {
+        encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), readback_buffer.view());
+    }
+
+    device.queue().submit(encoder.finish());
+
+    println!("Asserting each gathered array matches the expected values...");
+
+    for (array, readback_buffer) in arrays.iter().zip(&readback_buffers) {
+        readback_buffer.map_read().await?;
+
+        let readback = readback_buffer.mapped();
+
+        for i in 0..count {
+            assert_eq!(readback[i], array[by[i] as usize]);
+        }
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+    let frame_count = 3;
+
+    println!(
+        "Gathering a list of numbers over {} frames with a single prepared plan...",
+        frame_count
+    );
+
+    let mut by: Vec<u32> = Vec::with_capacity(count);
+
+    for i in 0..count as u32 {
+        by.push(count as u32 - 1 - i);
+    }
+
+    let mut gather_by = GatherBy::<u32, u32>::init_u32(device.clone()).await?;
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_dst());
+    let by_buffer: Buffer<[u32], _> = device.create_buffer(&*by, buffer::Usages::storage_binding());
+    let output_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let plan = gather_by.prepare(
+        GatherByInput {
+            gather_by: by_buffer.view(),
+            data: data_buffer.view(),
+            count: None,
+            index_transform: None,
+        },
+        output_buffer.view(),
+    );
+
+    for frame in 0..frame_count as u32 {
+        // Each frame gathers new contents of the same buffers
+        let frame_data: Vec<u32> = (0..count as u32).map(|i| i * (frame + 1)).collect();
+        let frame_data_buffer: Buffer<[u32], _> =
+            device.create_buffer(&*frame_data, buffer::Usages::copy_src());
+
+        let mut encoder = device.create_command_encoder();
+
+        encoder = encoder.copy_buffer_to_buffer_slice(frame_data_buffer.view(), data_buffer.view());
+        encoder = plan.encode(encoder);
+        encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), readback_buffer.view());
+
+        device.queue().submit(encoder.finish());
+
+        readback_buffer.map_read().await?;
+
+        let readback = readback_buffer.mapped();
+
+        println!(
+            "Asserting the values gathered in frame {} match the expected values...",
+            frame
+        );
+
+        for i in 0..count {
+            assert_eq!(readback[i], frame_data[count - 1 - i]);
+        }
+
+        mem::drop(readback);
+
+        readback_buffer.unmap();
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    println!("Gathering and scattering f32 values with special bit patterns...");
+
+    let special = [
+        f32::NAN,
+        -f32::NAN,
+        // A NaN with a non-default payload
+        f32::from_bits(0x7fa0_0001),
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+        -0.0,
+        0.0,
+        f32::MIN_POSITIVE / 2.0,
+        f32::MAX,
+    ];
+
+    let count = 10_000;
+
+    let mut data: Vec<f32> = Vec::with_capacity(count);
+    let mut by: Vec<u32> = Vec::with_capacity(count);
+
+    for i in 0..count {
+        data.push(special[i % special.len()]);
+        by.push((count - 1 - i) as u32);
+    }
+
+    let mut gather_by = GatherBy::<u32, f32>::init_u32(device.clone()).await?;
+    let mut scatter_by = ScatterBy::<u32, f32>::init_u32(device.clone()).await?;
+
+    let data_buffer: Buffer<[f32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let by_buffer: Buffer<[u32], _> = device.create_buffer(&*by, buffer::Usages::storage_binding());
+    let gathered_buffer: Buffer<[f32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let scattered_buffer: Buffer<[f32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let gathered_readback: Buffer<[f32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let scattered_readback: Buffer<[f32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = gather_by.encode(
+        encoder,
+        GatherByInput {
+            gather_by: by_buffer.view(),
+            data: data_buffer.view(),
+            count: None,
+            index_transform: None,
+        },
+        gathered_buffer.view(),
+    );
+    encoder = scatter_by.encode(
+        encoder,
+        ScatterByInput {
+            scatter_by: by_buffer.view(),
+            data: data_buffer.view(),
+            count: None,
+            index_transform: None,
+        },
+        scattered_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(gathered_buffer.view(), gathered_readback.view());
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(scattered_buffer.view(), scattered_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    gathered_readback.map_read().await?;
+    scattered_readback.map_read().await?;
+
+    let gathered = gathered_readback.mapped();
+    let scattered = scattered_readback.mapped();
+
+    println!("Asserting the values are moved bit-exactly...");
+
+    // The indices reverse the values, so a gather and a scatter produce the same output
+    for i in 0..count {
+        let expected = data[count - 1 - i].to_bits();
+
+        assert_eq!(gathered[i].to_bits(), expected);
+        assert_eq!(scattered[i].to_bits(), expected);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
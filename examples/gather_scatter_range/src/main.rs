@@ -0,0 +1,118 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::gather_by::{GatherBy, GatherByInput, GatherByRange};
+use empa_tk::scatter_by::{ScatterBy, ScatterByInput, ScatterByRange};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+    let window_len = 10_000;
+    let src_offset = 30_000;
+    let dst_offset = 50_000;
+
+    println!("Gathering and scattering a window of a list of numbers...");
+
+    let mut gather_by = GatherBy::<u32, u32>::init_u32(device.clone()).await?;
+    let mut scatter_by = ScatterBy::<u32, u32>::init_u32(device.clone()).await?;
+
+    let data: Vec<u32> = (0..count as u32).map(|i| i * 3).collect();
+
+    // Reverse the window
+    let by: Vec<u32> = (0..count as u32)
+        .map(|i| window_len as u32 - 1 - (i % window_len as u32))
+        .collect();
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let by_buffer: Buffer<[u32], _> = device.create_buffer(&*by, buffer::Usages::storage_binding());
+    let gather_output: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let scatter_output: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let gather_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let scatter_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = gather_by.encode_range(
+        encoder,
+        GatherByInput {
+            gather_by: by_buffer.view(),
+            data: data_buffer.view(),
+            count: None,
+            index_transform: None,
+        },
+        gather_output.view(),
+        GatherByRange {
+            src_offset,
+            dst_offset,
+            len: window_len as u32,
+        },
+    );
+    encoder = scatter_by.encode_range(
+        encoder,
+        ScatterByInput {
+            scatter_by: by_buffer.view(),
+            data: data_buffer.view(),
+            count: None,
+            index_transform: None,
+        },
+        scatter_output.view(),
+        ScatterByRange {
+            src_offset,
+            dst_offset,
+            len: window_len as u32,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(gather_output.view(), gather_readback.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(scatter_output.view(), scatter_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected_gather = vec![0; count];
+    let mut expected_scatter = vec![0; count];
+
+    for i in 0..window_len {
+        let src_index = src_offset as usize + i;
+
+        expected_gather[dst_offset as usize + i] = data[by[src_index] as usize];
+        expected_scatter[dst_offset as usize + by[src_index] as usize] = data[src_index];
+    }
+
+    gather_readback.map_read().await?;
+    scatter_readback.map_read().await?;
+
+    let gathered = gather_readback.mapped();
+    let scattered = scatter_readback.mapped();
+
+    println!("Asserting only the window was written, with the expected values...");
+
+    for i in 0..count {
+        assert_eq!(gathered[i], expected_gather[i]);
+        assert_eq!(scattered[i], expected_scatter[i]);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    // Not a multiple of any tile size, so that the last tile is partial
+    let count = 1_000_003;
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    let data: Vec<u32> = (0..count).map(|_| rng.rand_u32()).collect();
+
+    // Fisher-Yates shuffle
+    let mut permutation: Vec<u32> = (0..count as u32).collect();
+
+    for i in (1..count).rev() {
+        let j = rng.rand_range(0..i as u32 + 1) as usize;
+
+        permutation.swap(i, j);
+    }
+
+    let mut expected_scattered = vec![0; count];
+
+    for (i, value) in data.iter().enumerate() {
+        expected_scattered[permutation[i] as usize] = *value;
+    }
+
+    let expected_gathered: Vec<u32> = permutation.iter().map(|i| data[*i as usize]).collect();
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let permutation_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*permutation, buffer::Usages::storage_binding());
+
+    for values_per_thread in [1, 2, 4, 8] {
+        println!(
+            "Scattering and gathering {} values with {} values per thread...",
+            count, values_per_thread
+        );
+
+        let mut scatter_by = ScatterBy::init_u32_tiled(device.clone(), values_per_thread).await?;
+        let mut gather_by = GatherBy::init_u32_tiled(device.clone(), values_per_thread).await?;
+
+        let scattered_buffer: Buffer<[u32], _> = device
+            .create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+        let gathered_buffer: Buffer<[u32], _> = device
+            .create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+        let scattered_readback_buffer: Buffer<[u32], _> =
+            device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+        let gathered_readback_buffer: Buffer<[u32], _> =
+            device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+        let mut encoder = device.create_command_encoder();
+
+        encoder = scatter_by.encode(
+            encoder,
+            ScatterByInput {
+                scatter_by: permutation_buffer.view(),
+                data: data_buffer.view(),
+                count: None,
+                index_transform: None,
+            },
+            scattered_buffer.view(),
+        );
+        encoder = gather_by.encode(
+            encoder,
+            GatherByInput {
+                gather_by: permutation_buffer.view(),
+                data: data_buffer.view(),
+                count: None,
+                index_transform: None,
+            },
+            gathered_buffer.view(),
+        );
+        encoder = encoder
+            .copy_buffer_to_buffer_slice(scattered_buffer.view(), scattered_readback_buffer.view());
+        encoder = encoder
+            .copy_buffer_to_buffer_slice(gathered_buffer.view(), gathered_readback_buffer.view());
+
+        device.queue().submit(encoder.finish());
+
+        scattered_readback_buffer.map_read().await?;
+        gathered_readback_buffer.map_read().await?;
+
+        assert_eq!(
+            &*scattered_readback_buffer.mapped(),
+            &expected_scattered[..]
+        );
+        assert_eq!(&*gathered_readback_buffer.mapped(), &expected_gathered[..]);
+
+        scattered_readback_buffer.unmap();
+        gathered_readback_buffer.unmap();
+
+        println!("...successfully!");
+    }
+
+    Ok(())
+}
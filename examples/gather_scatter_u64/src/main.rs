@@ -0,0 +1,191 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::gather_by::{GatherBy, GatherByInput};
+use empa_tk::scatter_by::{ScatterBy, ScatterByInput};
+use futures::FutureExt;
+
+const SENTINEL: u32 = u32::MAX;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+fn split(index: u64) -> [u32; 2] {
+    [index as u32, (index >> 32) as u32]
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let window_len = 100_000;
+    let count = 200_000;
+
+    // The window lies beyond the range of 32-bit indices, and straddles a 32-bit boundary of the
+    // low word, so that the index arithmetic must carry into the high word
+    let window_offset: u64 = (5 << 32) - (window_len as u64 / 2);
+
+    println!(
+        "Gathering {} values by 64-bit indices from a window of {} values at offset {}...",
+        count, window_len, window_offset
+    );
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    // Roughly half of the indices fall inside the window
+    let indices: Vec<u64> = (0..count)
+        .map(|_| window_offset - window_len as u64 + rng.rand_range(0..3 * window_len) as u64)
+        .collect();
+    let window: Vec<u32> = (0..window_len)
+        .map(|_| rng.rand_range(0..SENTINEL))
+        .collect();
+
+    let in_window =
+        |index: u64| index >= window_offset && index < window_offset + window_len as u64;
+
+    let by: Vec<[u32; 2]> = indices.iter().map(|index| split(*index)).collect();
+
+    let by_buffer: Buffer<[[u32; 2]], _> =
+        device.create_buffer(&*by, buffer::Usages::storage_binding());
+    let window_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*window, buffer::Usages::storage_binding());
+    let gathered_buffer: Buffer<[u32], _> = device.create_buffer(
+        vec![SENTINEL; count as usize],
+        buffer::Usages::storage_binding().and_copy_src(),
+    );
+    let gathered_readback: Buffer<[u32], _> = device
+        .create_slice_buffer_zeroed(count as usize, buffer::Usages::map_read().and_copy_dst());
+
+    let mut gather_by = GatherBy::init_u64(device.clone()).await?;
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = gather_by.encode_window(
+        encoder,
+        GatherByInput {
+            gather_by: by_buffer.view(),
+            data: window_buffer.view(),
+            count: None,
+            index_transform: None,
+        },
+        gathered_buffer.view(),
+        window_offset,
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(gathered_buffer.view(), gathered_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    gathered_readback.map_read().await?;
+
+    let gathered = gathered_readback.mapped();
+
+    for (i, index) in indices.iter().enumerate() {
+        let expected = if in_window(*index) {
+            window[(index - window_offset) as usize]
+        } else {
+            SENTINEL
+        };
+
+        assert_eq!(gathered[i], expected);
+    }
+
+    mem::drop(gathered);
+
+    gathered_readback.unmap();
+
+    println!("...successfully!");
+
+    println!(
+        "Scattering {} values by 64-bit indices into a window of {} values at offset {}...",
+        window_len, window_len, window_offset
+    );
+
+    // A permutation of the window, offset into the larger buffer, where every other index is moved
+    // out of the window
+    let mut permutation: Vec<u64> = (0..window_len as u64).collect();
+
+    for i in (1..permutation.len()).rev() {
+        permutation.swap(i, rng.rand_range(0..i as u32 + 1) as usize);
+    }
+
+    let indices: Vec<u64> = permutation
+        .iter()
+        .enumerate()
+        .map(|(i, target)| {
+            if i % 2 == 0 {
+                window_offset + target
+            } else {
+                window_offset + window_len as u64 + target
+            }
+        })
+        .collect();
+
+    let by: Vec<[u32; 2]> = indices.iter().map(|index| split(*index)).collect();
+
+    let by_buffer: Buffer<[[u32; 2]], _> =
+        device.create_buffer(&*by, buffer::Usages::storage_binding());
+    let scattered_buffer: Buffer<[u32], _> = device.create_buffer(
+        vec![SENTINEL; window_len as usize],
+        buffer::Usages::storage_binding().and_copy_src(),
+    );
+    let scattered_readback: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
+        window_len as usize,
+        buffer::Usages::map_read().and_copy_dst(),
+    );
+
+    let mut scatter_by = ScatterBy::init_u64(device.clone()).await?;
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = scatter_by.encode_window(
+        encoder,
+        ScatterByInput {
+            scatter_by: by_buffer.view(),
+            data: window_buffer.view(),
+            count: None,
+            index_transform: None,
+        },
+        scattered_buffer.view(),
+        window_offset,
+    );
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(scattered_buffer.view(), scattered_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected = vec![SENTINEL; window_len as usize];
+
+    for (i, index) in indices.iter().enumerate() {
+        if in_window(*index) {
+            expected[(index - window_offset) as usize] = window[i];
+        }
+    }
+
+    scattered_readback.map_read().await?;
+
+    let scattered = scattered_readback.mapped();
+
+    for i in 0..window_len as usize {
+        assert_eq!(scattered[i], expected[i]);
+    }
+
+    mem::drop(scattered);
+
+    scattered_readback.unmap();
+
+    println!("...successfully!");
+
+    Ok(())
+}
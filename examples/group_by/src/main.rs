@@ -0,0 +1,150 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+    let k = 1000;
+
+    println!(
+        "Grouping {} values by {} distinct keys in the range `-{}..{}`...",
+        count,
+        k,
+        k / 2,
+        k / 2
+    );
+
+    let mut group_by = GroupBy::<i32, u32>::init_i32(device.clone()).await?;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut keys: Vec<i32> = Vec::with_capacity(count);
+    let mut values: Vec<u32> = Vec::with_capacity(count);
+
+    for i in 0..count {
+        keys.push(rng.rand_range(0..k) as i32 - k as i32 / 2);
+        values.push(i as u32);
+    }
+
+    let keys_buffer: Buffer<[i32], _> =
+        device.create_buffer(&*keys, buffer::Usages::storage_binding().and_copy_src());
+    let values_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*values, buffer::Usages::storage_binding().and_copy_src());
+    let group_count_buffer: Buffer<u32, _> =
+        device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_src());
+    let group_starts_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let group_lengths_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+
+    let keys_readback_buffer: Buffer<[i32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let values_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let group_count_readback_buffer: Buffer<u32, _> =
+        device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+    let group_starts_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let group_lengths_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = group_by.encode(
+        encoder,
+        GroupByInput {
+            keys: keys_buffer.view(),
+            values: values_buffer.view(),
+            count: None,
+        },
+        GroupByOutput {
+            group_count: group_count_buffer.view(),
+            group_starts: group_starts_buffer.view(),
+            group_lengths: group_lengths_buffer.view(),
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(keys_buffer.view(), keys_readback_buffer.view());
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(values_buffer.view(), values_readback_buffer.view());
+    encoder = encoder.copy_buffer_to_buffer(
+        group_count_buffer.view(),
+        group_count_readback_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        group_starts_buffer.view(),
+        group_starts_readback_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        group_lengths_buffer.view(),
+        group_lengths_readback_buffer.view(),
+    );
+
+    device.queue().submit(encoder.finish());
+
+    // The sort is stable, so sorting the (key, value) pairs by key on the CPU produces the same
+    // order as the GPU
+    let mut expected: Vec<(i32, u32)> = keys.iter().copied().zip(values).collect();
+
+    expected.sort_by_key(|(key, _)| *key);
+
+    let mut expected_starts = Vec::new();
+    let mut expected_lengths: Vec<u32> = Vec::new();
+
+    for (i, (key, _)) in expected.iter().enumerate() {
+        if i == 0 || expected[i - 1].0 != *key {
+            expected_starts.push(i as u32);
+            expected_lengths.push(0);
+        }
+
+        *expected_lengths.last_mut().unwrap() += 1;
+    }
+
+    group_count_readback_buffer.map_read().await?;
+    keys_readback_buffer.map_read().await?;
+    values_readback_buffer.map_read().await?;
+    group_starts_readback_buffer.map_read().await?;
+    group_lengths_readback_buffer.map_read().await?;
+
+    let group_count = *group_count_readback_buffer.mapped() as usize;
+    let sorted_keys = keys_readback_buffer.mapped();
+    let sorted_values = values_readback_buffer.mapped();
+    let group_starts = group_starts_readback_buffer.mapped();
+    let group_lengths = group_lengths_readback_buffer.mapped();
+
+    println!("Asserting the sorted keys and values match the CPU sort...");
+
+    for i in 0..count {
+        assert_eq!(sorted_keys[i], expected[i].0);
+        assert_eq!(sorted_values[i], expected[i].1);
+    }
+
+    println!("...successfully!");
+
+    println!("Asserting the groups found on the GPU match the groups found on the CPU...");
+
+    assert_eq!(group_count, expected_starts.len());
+    assert_eq!(&group_starts[..group_count], &expected_starts[..]);
+    assert_eq!(&group_lengths[..group_count], &expected_lengths[..]);
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::histogram::{Histogram, HistogramInput, HistogramRange};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+    let bin_count = 1000;
+    let range = HistogramRange {
+        min: 1000,
+        max: 100_999,
+    };
+
+    println!(
+        "Counting {} values into {} bins over the range {}..={}...",
+        count, bin_count, range.min, range.max
+    );
+
+    let mut histogram = Histogram::init(device.clone(), bin_count).await;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+
+    // Generate values somewhat outside of the range too, to exercise the clamping behavior.
+    for _ in 0..count {
+        data.push(rng.rand_range(0..110_000));
+    }
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let bins_buffer: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
+        bin_count as usize,
+        buffer::Usages::storage_binding()
+            .and_copy_dst()
+            .and_copy_src(),
+    );
+    let readback_buffer: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
+        bin_count as usize,
+        buffer::Usages::map_read().and_copy_dst(),
+    );
+    let timestamp_query_set = device.create_timestamp_query_set(2);
+    let timestamps =
+        device.create_slice_buffer_zeroed(2, buffer::Usages::query_resolve().and_copy_src());
+    let timestamps_readback =
+        device.create_slice_buffer_zeroed(2, buffer::Usages::copy_dst().and_map_read());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = encoder.write_timestamp(&timestamp_query_set, 0);
+    encoder = histogram.encode(
+        encoder,
+        HistogramInput {
+            data: data_buffer.view(),
+            range,
+            count: None,
+        },
+        bins_buffer.view(),
+    );
+    encoder = encoder.write_timestamp(&timestamp_query_set, 1);
+
+    encoder = encoder.copy_buffer_to_buffer_slice(bins_buffer.view(), readback_buffer.view());
+    encoder = encoder.resolve_timestamp_query_set(&timestamp_query_set, 0, timestamps.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(timestamps.view(), timestamps_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    let bin_width = (range.max - range.min + 1).div_ceil(bin_count);
+    let mut expected = vec![0u32; bin_count as usize];
+
+    for value in data.iter().copied() {
+        let clamped = value.clamp(range.min, range.max);
+        let bin = ((clamped - range.min) / bin_width).min(bin_count - 1);
+
+        expected[bin as usize] += 1;
+    }
+
+    readback_buffer.map_read().await?;
+
+    let bins = readback_buffer.mapped();
+
+    println!("The first 10 bins computed on the GPU: {:#?}", &bins[..10]);
+    println!(
+        "The first 10 bins computed on the CPU (reference): {:#?}",
+        &expected[..10]
+    );
+
+    println!("Asserting all bins computed on the GPU match the bins computed on the CPU...");
+
+    for i in 0..bin_count as usize {
+        assert_eq!(bins[i], expected[i]);
+    }
+
+    println!("...successfully!");
+
+    mem::drop(bins);
+
+    readback_buffer.unmap();
+
+    timestamps_readback.map_read().await?;
+
+    let timestamps = timestamps_readback.mapped();
+    let time_elapsed = timestamps[1] - timestamps[0];
+
+    println!("Time elapsed: {} nanoseconds", time_elapsed);
+
+    mem::drop(timestamps);
+
+    timestamps_readback.unmap();
+
+    Ok(())
+}
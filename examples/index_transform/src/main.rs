@@ -0,0 +1,118 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+
+    println!(
+        "De-interleaving {} (x, y) pairs with a strided gather and re-interleaving the y values \
+        with a strided scatter...",
+        count
+    );
+
+    // Interleaved `[x0, y0, x1, y1, ...]` pairs
+    let mut interleaved: Vec<u32> = Vec::with_capacity(count * 2);
+
+    for i in 0..count as u32 {
+        interleaved.push(i);
+        interleaved.push(i * 3 + 1);
+    }
+
+    // Reverse the order of the pairs
+    let indices: Vec<u32> = (0..count as u32).rev().collect();
+
+    let mut gather_by = GatherBy::init_u32(device.clone()).await?;
+    let mut scatter_by = ScatterBy::init_u32(device.clone()).await?;
+
+    let interleaved_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*interleaved, buffer::Usages::storage_binding());
+    let indices_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*indices, buffer::Usages::storage_binding());
+    let y_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let reinterleaved_buffer: Buffer<[u32], _> = device
+        .create_slice_buffer_zeroed(count * 2, buffer::Usages::storage_binding().and_copy_src());
+    let y_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let reinterleaved_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count * 2, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    // Gathers `y[i] = interleaved[indices[i] * 2 + 1]`
+    encoder = gather_by.encode(
+        encoder,
+        GatherByInput {
+            gather_by: indices_buffer.view(),
+            data: interleaved_buffer.view(),
+            count: None,
+            index_transform: Some(IndexTransform { stride: 2, base: 1 }),
+        },
+        y_buffer.view(),
+    );
+
+    // Scatters `reinterleaved[indices[i] * 2 + 1] = y[i]`, which restores the y values to their
+    // original positions
+    encoder = scatter_by.encode(
+        encoder,
+        ScatterByInput {
+            scatter_by: indices_buffer.view(),
+            data: y_buffer.view(),
+            count: None,
+            index_transform: Some(IndexTransform { stride: 2, base: 1 }),
+        },
+        reinterleaved_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(y_buffer.view(), y_readback_buffer.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        reinterleaved_buffer.view(),
+        reinterleaved_readback_buffer.view(),
+    );
+
+    device.queue().submit(encoder.finish());
+
+    y_readback_buffer.map_read().await?;
+    reinterleaved_readback_buffer.map_read().await?;
+
+    let y = y_readback_buffer.mapped();
+    let reinterleaved = reinterleaved_readback_buffer.mapped();
+
+    println!("Asserting the gathered y values match the expected values...");
+
+    for i in 0..count {
+        assert_eq!(y[i], interleaved[indices[i] as usize * 2 + 1]);
+    }
+
+    println!("...successfully!");
+
+    println!("Asserting the scattered y values are back at their original positions...");
+
+    for i in 0..count {
+        assert_eq!(reinterleaved[i * 2], 0);
+        assert_eq!(reinterleaved[i * 2 + 1], interleaved[i * 2 + 1]);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::search::{LowerBound, LowerBoundInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let haystack_count = 1_000_000;
+    let query_count = 100_000;
+
+    println!(
+        "Finding the lower bounds for {} queries in a sorted list of {} values...",
+        query_count, haystack_count
+    );
+
+    let mut lower_bound = LowerBound::init_u32(device.clone()).await;
+
+    // Use a value range that produces many duplicates in the haystack, and queries that are both
+    // smaller and larger than all haystack values.
+    let mut rng = oorandom::Rand32::new(1);
+    let mut haystack: Vec<u32> = Vec::with_capacity(haystack_count);
+    let mut queries: Vec<u32> = Vec::with_capacity(query_count);
+
+    for _ in 0..haystack_count {
+        haystack.push(rng.rand_range(100..100_000));
+    }
+
+    for _ in 0..query_count {
+        queries.push(rng.rand_range(0..100_100));
+    }
+
+    haystack.sort();
+
+    let haystack_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*haystack, buffer::Usages::storage_binding());
+    let queries_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*queries, buffer::Usages::storage_binding());
+    let output_buffer: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
+        query_count,
+        buffer::Usages::storage_binding().and_copy_src(),
+    );
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(query_count, buffer::Usages::map_read().and_copy_dst());
+    let timestamp_query_set = device.create_timestamp_query_set(2);
+    let timestamps =
+        device.create_slice_buffer_zeroed(2, buffer::Usages::query_resolve().and_copy_src());
+    let timestamps_readback =
+        device.create_slice_buffer_zeroed(2, buffer::Usages::copy_dst().and_map_read());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = encoder.write_timestamp(&timestamp_query_set, 0);
+    encoder = lower_bound.encode(
+        encoder,
+        LowerBoundInput {
+            haystack: haystack_buffer.view(),
+            queries: queries_buffer.view(),
+            count: None,
+        },
+        output_buffer.view(),
+    );
+    encoder = encoder.write_timestamp(&timestamp_query_set, 1);
+
+    encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), readback_buffer.view());
+    encoder = encoder.resolve_timestamp_query_set(&timestamp_query_set, 0, timestamps.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(timestamps.view(), timestamps_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    readback_buffer.map_read().await?;
+
+    let indices = readback_buffer.mapped();
+
+    println!("The first 10 indices: {:#?}", &indices[..10]);
+
+    println!("Asserting the indices computed on the GPU match the expected indices...");
+
+    for i in 0..query_count {
+        let expected = haystack.partition_point(|v| *v < queries[i]);
+
+        assert_eq!(indices[i] as usize, expected);
+    }
+
+    println!("...successfully!");
+
+    mem::drop(indices);
+
+    readback_buffer.unmap();
+
+    timestamps_readback.map_read().await?;
+
+    let timestamps = timestamps_readback.mapped();
+    let time_elapsed = timestamps[1] - timestamps[0];
+
+    println!("Time elapsed: {} nanoseconds", time_elapsed);
+
+    mem::drop(timestamps);
+
+    timestamps_readback.unmap();
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::merge::{Merge, MergeInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let a_count = 1_000_000;
+    let b_count = 50_000;
+    let count = a_count + b_count;
+
+    println!(
+        "Merging a sorted list of {} values with a sorted list of {} values...",
+        a_count, b_count
+    );
+
+    let mut merge = Merge::init_u32(device.clone()).await;
+
+    // Use a small value range so that there are many values that occur in both lists.
+    let mut rng = oorandom::Rand32::new(1);
+    let mut a: Vec<u32> = Vec::with_capacity(a_count);
+    let mut b: Vec<u32> = Vec::with_capacity(b_count);
+
+    for _ in 0..a_count {
+        a.push(rng.rand_range(0..100_000));
+    }
+
+    for _ in 0..b_count {
+        b.push(rng.rand_range(0..100_000));
+    }
+
+    a.sort();
+    b.sort();
+
+    let a_buffer: Buffer<[u32], _> = device.create_buffer(&*a, buffer::Usages::storage_binding());
+    let b_buffer: Buffer<[u32], _> = device.create_buffer(&*b, buffer::Usages::storage_binding());
+    let output_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let timestamp_query_set = device.create_timestamp_query_set(2);
+    let timestamps =
+        device.create_slice_buffer_zeroed(2, buffer::Usages::query_resolve().and_copy_src());
+    let timestamps_readback =
+        device.create_slice_buffer_zeroed(2, buffer::Usages::copy_dst().and_map_read());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = encoder.write_timestamp(&timestamp_query_set, 0);
+    encoder = merge.encode(
+        encoder,
+        MergeInput {
+            a: a_buffer.view(),
+            b: b_buffer.view(),
+        },
+        output_buffer.view(),
+    );
+    encoder = encoder.write_timestamp(&timestamp_query_set, 1);
+
+    encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), readback_buffer.view());
+    encoder = encoder.resolve_timestamp_query_set(&timestamp_query_set, 0, timestamps.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(timestamps.view(), timestamps_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected = a.clone();
+
+    expected.extend_from_slice(&b);
+    expected.sort();
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    println!(
+        "The first 10 numbers computed on the GPU: {:#?}",
+        &readback[..10]
+    );
+    println!(
+        "The last 10 numbers computed on the GPU: {:#?}",
+        &readback[readback.len() - 10..]
+    );
+
+    println!("Asserting the merged values match the expected values...");
+
+    for i in 0..count {
+        assert_eq!(readback[i], expected[i]);
+    }
+
+    println!("...successfully!");
+
+    mem::drop(readback);
+
+    readback_buffer.unmap();
+
+    timestamps_readback.map_read().await?;
+
+    let timestamps = timestamps_readback.mapped();
+    let time_elapsed = timestamps[1] - timestamps[0];
+
+    println!("Time elapsed: {} nanoseconds", time_elapsed);
+
+    mem::drop(timestamps);
+
+    timestamps_readback.unmap();
+
+    Ok(())
+}
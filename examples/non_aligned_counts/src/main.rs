@@ -0,0 +1,252 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::{Device, DeviceDescriptor};
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort = RadixSort::<u32>::init_u32(device.clone()).await;
+    let mut prefix_sum = PrefixSum::<u32>::init_inclusive_u32(device.clone()).await;
+    let mut find_runs = FindRuns::<u32>::init_u32(device.clone()).await;
+
+    let segment_sizes = [
+        radix_sort.histogram_segment_size(),
+        radix_sort.scatter_segment_size(),
+        prefix_sum.segment_size(),
+    ];
+
+    let mut counts = vec![1, 2, 3, 7, 255, 257, 1_009, 65_537, 1_000_003];
+
+    for segment_size in segment_sizes {
+        for multiple in [1, 2, 3] {
+            let aligned = segment_size * multiple;
+
+            counts.extend([aligned - 1, aligned, aligned + 1]);
+        }
+    }
+
+    counts.sort();
+    counts.dedup();
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    for count in counts {
+        println!("Verifying the results for a count of {}...", count);
+
+        verify_radix_sort(&device, &mut radix_sort, &mut rng, count as usize).await?;
+        verify_prefix_sum(&device, &mut prefix_sum, &mut rng, count as usize).await?;
+        verify_find_runs(&device, &mut find_runs, &mut rng, count as usize).await?;
+    }
+
+    println!("...all results match the CPU reference!");
+
+    Ok(())
+}
+
+async fn verify_radix_sort(
+    device: &Device,
+    radix_sort: &mut RadixSort<u32>,
+    rng: &mut oorandom::Rand32,
+    count: usize,
+) -> Result<(), Box<dyn Error>> {
+    let mut data: Vec<u32> = (0..count).map(|_| rng.rand_u32()).collect();
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let temp_storage_buffer = RadixSort::<u32>::temporary_storage_buffer(device, count);
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortInput {
+            data: data_buffer.view(),
+            temporary_storage: temp_storage_buffer.view(),
+            count: None,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    data.sort();
+
+    readback_buffer.map_read().await?;
+
+    assert_eq!(
+        &*readback_buffer.mapped(),
+        &data[..],
+        "radix sort mismatch for a count of {}",
+        count
+    );
+
+    readback_buffer.unmap();
+
+    Ok(())
+}
+
+async fn verify_prefix_sum(
+    device: &Device,
+    prefix_sum: &mut PrefixSum<u32>,
+    rng: &mut oorandom::Rand32,
+    count: usize,
+) -> Result<(), Box<dyn Error>> {
+    let data: Vec<u32> = (0..count).map(|_| rng.rand_range(0..100)).collect();
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = prefix_sum.encode(
+        encoder,
+        PrefixSumInput {
+            data: data_buffer.view(),
+            count: None,
+            initial: None,
+            order: ScanOrder::Ascending,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    let expected: Vec<u32> = data
+        .iter()
+        .scan(0, |sum, value| {
+            *sum += value;
+
+            Some(*sum)
+        })
+        .collect();
+
+    readback_buffer.map_read().await?;
+
+    assert_eq!(
+        &*readback_buffer.mapped(),
+        &expected[..],
+        "prefix sum mismatch for a count of {}",
+        count
+    );
+
+    readback_buffer.unmap();
+
+    Ok(())
+}
+
+async fn verify_find_runs(
+    device: &Device,
+    find_runs: &mut FindRuns<u32>,
+    rng: &mut oorandom::Rand32,
+    count: usize,
+) -> Result<(), Box<dyn Error>> {
+    // Runs of random lengths: each value starts a new run with a probability of 1 in 5
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+    let mut value = 0;
+
+    for _ in 0..count {
+        if rng.rand_range(0..5) == 0 {
+            value += 1;
+        }
+
+        data.push(value);
+    }
+
+    let mut expected_run_starts = Vec::new();
+    let mut expected_run_mapping = Vec::with_capacity(count);
+
+    for (i, value) in data.iter().enumerate() {
+        if i == 0 || data[i - 1] != *value {
+            expected_run_starts.push(i as u32);
+        }
+
+        expected_run_mapping.push(expected_run_starts.len() as u32 - 1);
+    }
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let output_buffers = FindRuns::<u32>::output_buffers(device, count);
+    let run_count_readback: Buffer<u32, _> =
+        device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+    let run_starts_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let run_mapping_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = find_runs.encode(
+        encoder,
+        FindRunsInput {
+            data: data_buffer.view(),
+            count: None,
+        },
+        output_buffers.view(),
+    );
+    encoder =
+        encoder.copy_buffer_to_buffer(output_buffers.run_count.view(), run_count_readback.view());
+    encoder = encoder
+        .copy_buffer_to_buffer_slice(output_buffers.run_starts.view(), run_starts_readback.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        output_buffers.run_mapping.view(),
+        run_mapping_readback.view(),
+    );
+
+    device.queue().submit(encoder.finish());
+
+    run_count_readback.map_read().await?;
+    run_starts_readback.map_read().await?;
+    run_mapping_readback.map_read().await?;
+
+    let run_count = *run_count_readback.mapped() as usize;
+    let run_starts = run_starts_readback.mapped();
+
+    assert_eq!(
+        run_count,
+        expected_run_starts.len(),
+        "run count mismatch for a count of {}",
+        count
+    );
+    assert_eq!(
+        &run_starts[..run_count],
+        &expected_run_starts[..],
+        "run starts mismatch for a count of {}",
+        count
+    );
+    assert_eq!(
+        &*run_mapping_readback.mapped(),
+        &expected_run_mapping[..],
+        "run mapping mismatch for a count of {}",
+        count
+    );
+
+    mem::drop(run_starts);
+
+    run_count_readback.unmap();
+    run_starts_readback.unmap();
+    run_mapping_readback.unmap();
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::nth_element::{NthElement, NthElementInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    let mut nth_element = NthElement::init_f32(device.clone()).await;
+
+    // Quantize the values, so that many values tie with the selected value.
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<f32> = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        data.push((rng.rand_float() * 2_000.0 - 1_000.0).round() / 4.0);
+    }
+
+    let data_buffer: Buffer<[f32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+
+    let mut sorted = data.clone();
+
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    for k in [0, count / 10, count / 2, count * 9 / 10, count - 1] {
+        println!(
+            "Selecting the value of rank {} out of {} values...",
+            k, count
+        );
+
+        let output_buffer: Buffer<f32, _> =
+            device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_src());
+        let readback_buffer: Buffer<f32, _> =
+            device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+
+        let mut encoder = device.create_command_encoder();
+
+        encoder = nth_element.encode(
+            encoder,
+            NthElementInput {
+                data: data_buffer.view(),
+                k: k as u32,
+                count: None,
+            },
+            output_buffer.view(),
+        );
+        encoder = encoder.copy_buffer_to_buffer(output_buffer.view(), readback_buffer.view());
+
+        device.queue().submit(encoder.finish());
+
+        readback_buffer.map_read().await?;
+
+        let selected = *readback_buffer.mapped();
+
+        println!("Asserting the selected value matches the expected value...");
+
+        assert_eq!(selected, sorted[k]);
+
+        println!("...successfully!");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let scan_count = 4;
+    let count = 100_003;
+
+    println!(
+        "Scanning {} buffers of {} values, with the state of all prefix sums reset up front...",
+        scan_count, count
+    );
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut prefix_sums = Vec::with_capacity(scan_count);
+    let mut inputs = Vec::with_capacity(scan_count);
+
+    for _ in 0..scan_count {
+        let mut prefix_sum = PrefixSum::<u32>::init_inclusive_u32(device.clone()).await;
+
+        // Grow the internal state to the size of the input before the batched reset, so that the
+        // reset covers all of it
+        prefix_sum.encode(
+            device.create_command_encoder(),
+            PrefixSumInput {
+                data: device
+                    .create_slice_buffer_zeroed::<u32, _>(count, buffer::Usages::storage_binding())
+                    .view(),
+                count: None,
+                initial: None,
+                order: ScanOrder::Ascending,
+            },
+        );
+
+        prefix_sums.push(prefix_sum);
+        inputs.push(
+            (0..count)
+                .map(|_| rng.rand_range(0..100))
+                .collect::<Vec<u32>>(),
+        );
+    }
+
+    let data_buffers: Vec<Buffer<[u32], _>> = inputs
+        .iter()
+        .map(|data| device.create_buffer(&**data, buffer::Usages::storage_binding().and_copy_src()))
+        .collect();
+    let readback_buffers: Vec<Buffer<[u32], _>> = (0..scan_count)
+        .map(|_| {
+            device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst())
+        })
+        .collect();
+
+    let mut encoder = device.create_command_encoder();
+
+    for prefix_sum in prefix_sums.iter_mut() {
+        encoder = prefix_sum.reset(encoder);
+    }
+
+    for (prefix_sum, data_buffer) in prefix_sums.iter_mut().zip(data_buffers.iter()) {
+        encoder = prefix_sum.encode_after_reset(
+            encoder,
+            PrefixSumInput {
+                data: data_buffer.view(),
+                count: None,
+                initial: None,
+                order: ScanOrder::Ascending,
+            },
+        );
+    }
+
+    for (data_buffer, readback_buffer) in data_buffers.iter().zip(readback_buffers.iter()) {
+        encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+    }
+
+    device.queue().submit(encoder.finish());
+
+    println!("Asserting each prefix sum matches the CPU reference...");
+
+    for (data, readback_buffer) in inputs.iter().zip(readback_buffers.iter()) {
+        let expected: Vec<u32> = data
+            .iter()
+            .scan(0, |sum, value| {
+                *sum += value;
+
+                Some(*sum)
+            })
+            .collect();
+
+        readback_buffer.map_read().await?;
+
+        assert_eq!(&*readback_buffer.mapped(), &expected[..]);
+
+        readback_buffer.unmap();
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut prefix_sum = PrefixSum::<u32>::init_inclusive_u32(device.clone()).await;
+
+    let count = 1_000_003;
+    let segment_size = prefix_sum.segment_size() as usize;
+    let block_count = prefix_sum.workgroups(count as u32) as usize;
+
+    println!(
+        "Computing the inclusive prefix sum of {} values, and the sums of its {} blocks of {} values...",
+        count, block_count, segment_size
+    );
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    let data: Vec<u32> = (0..count).map(|_| rng.rand_range(0..100)).collect();
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let block_sums_buffer: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
+        block_count,
+        buffer::Usages::storage_binding().and_copy_src(),
+    );
+    let data_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let block_sums_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(block_count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = prefix_sum.encode_with_block_sums(
+        encoder,
+        PrefixSumInput {
+            data: data_buffer.view(),
+            count: None,
+            initial: None,
+            order: ScanOrder::Ascending,
+        },
+        block_sums_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), data_readback.view());
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(block_sums_buffer.view(), block_sums_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    let expected_scan: Vec<u32> = data
+        .iter()
+        .scan(0, |sum, value| {
+            *sum += value;
+
+            Some(*sum)
+        })
+        .collect();
+    let expected_block_sums: Vec<u32> = data
+        .chunks(segment_size)
+        .map(|block| block.iter().sum())
+        .collect();
+
+    data_readback.map_read().await?;
+    block_sums_readback.map_read().await?;
+
+    let scan = data_readback.mapped();
+    let block_sums = block_sums_readback.mapped();
+
+    println!("Asserting the prefix sum matches the CPU reference...");
+
+    assert_eq!(&*scan, &expected_scan[..]);
+
+    println!("...successfully!");
+
+    println!("Asserting the block sums match the CPU reference...");
+
+    assert_eq!(&*block_sums, &expected_block_sums[..]);
+
+    println!("...successfully!");
+
+    mem::drop(scan);
+    mem::drop(block_sums);
+
+    data_readback.unmap();
+    block_sums_readback.unmap();
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
+use empa_tk::read_back_vec;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let data: Vec<u32> = (0..count).map(|_| rng.rand_range(0..16)).collect();
+
+    let mut expected_inclusive = Vec::with_capacity(count);
+    let mut expected_exclusive = Vec::with_capacity(count);
+    let mut sum = 0u32;
+
+    for value in &data {
+        expected_exclusive.push(sum);
+        sum += value;
+        expected_inclusive.push(sum);
+    }
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+
+    let evaluators = [
+        (
+            "exclusive",
+            PrefixSum::init_exclusive_u32(device.clone()).await,
+        ),
+        (
+            "inclusive",
+            PrefixSum::init_inclusive_u32(device.clone()).await,
+        ),
+    ];
+
+    for (variant, mut evaluator) in evaluators {
+        println!(
+            "Evaluating both prefix-sums over {} values with an {} prefix-sum...",
+            count, variant
+        );
+
+        let inclusive_buffer: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
+            count,
+            buffer::Usages::storage_binding()
+                .and_copy_dst()
+                .and_copy_src(),
+        );
+        let exclusive_buffer: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
+            count,
+            buffer::Usages::storage_binding()
+                .and_copy_dst()
+                .and_copy_src(),
+        );
+        let inclusive_readback: Buffer<[u32], _> =
+            device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+        let exclusive_readback: Buffer<[u32], _> =
+            device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+        let mut encoder = device.create_command_encoder();
+
+        encoder = evaluator.encode_both(
+            encoder,
+            PrefixSumInput {
+                data: data_buffer.view(),
+                count: None,
+                initial: None,
+                order: ScanOrder::Ascending,
+            },
+            inclusive_buffer.view(),
+            exclusive_buffer.view(),
+        );
+        encoder =
+            encoder.copy_buffer_to_buffer_slice(inclusive_buffer.view(), inclusive_readback.view());
+        encoder =
+            encoder.copy_buffer_to_buffer_slice(exclusive_buffer.view(), exclusive_readback.view());
+
+        device.queue().submit(encoder.finish());
+
+        let inclusive = read_back_vec(&inclusive_readback).await?;
+        let exclusive = read_back_vec(&exclusive_readback).await?;
+
+        assert_eq!(inclusive, expected_inclusive);
+        assert_eq!(exclusive, expected_exclusive);
+
+        println!("...successfully!");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let chunk_size = 100_000;
+    let chunk_count = 4;
+
+    println!(
+        "Evaluating an exclusive prefix-sum over {} chunks of {} values, carrying the total \
+         across chunks...",
+        chunk_count, chunk_size
+    );
+
+    let mut prefix_sum = PrefixSum::init_exclusive_u32(device.clone()).await;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(chunk_size * chunk_count);
+
+    for _ in 0..chunk_size * chunk_count {
+        data.push(rng.rand_range(0..100));
+    }
+
+    let mut chunk_buffers = Vec::with_capacity(chunk_count);
+    let mut readback_buffers = Vec::with_capacity(chunk_count);
+
+    for chunk in data.chunks(chunk_size) {
+        let chunk_buffer: Buffer<[u32], _> =
+            device.create_buffer(chunk, buffer::Usages::storage_binding().and_copy_src());
+        let readback_buffer: Buffer<[u32], _> = device
+            .create_slice_buffer_zeroed(chunk_size, buffer::Usages::map_read().and_copy_dst());
+
+        chunk_buffers.push(chunk_buffer);
+        readback_buffers.push(readback_buffer);
+    }
+
+    let mut encoder = device.create_command_encoder();
+    let mut running_total = 0u32;
+
+    for (i, chunk) in data.chunks(chunk_size).enumerate() {
+        encoder = prefix_sum.encode(
+            encoder,
+            PrefixSumInput {
+                data: chunk_buffers[i].view(),
+                count: None,
+                initial: Some(running_total),
+                order: ScanOrder::Ascending,
+            },
+        );
+        encoder = encoder
+            .copy_buffer_to_buffer_slice(chunk_buffers[i].view(), readback_buffers[i].view());
+
+        // The total is known on the CPU here; in a streaming setting it would be the last output
+        // of the previous chunk plus the previous chunk's last input
+        running_total += chunk.iter().sum::<u32>();
+    }
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected = Vec::with_capacity(data.len());
+    let mut sum = 0u32;
+
+    for value in &data {
+        expected.push(sum);
+
+        sum += value;
+    }
+
+    println!("Asserting the chunked scan matches a scan over all values on the CPU...");
+
+    for (i, readback_buffer) in readback_buffers.iter().enumerate() {
+        readback_buffer.map_read().await?;
+
+        let readback = readback_buffer.mapped();
+
+        assert_eq!(
+            &readback[..],
+            &expected[i * chunk_size..(i + 1) * chunk_size]
+        );
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
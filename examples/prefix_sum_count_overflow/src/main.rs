@@ -0,0 +1,78 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut prefix_sum = PrefixSum::init_inclusive_u32(device.clone()).await;
+
+    let len = 100_000;
+
+    // Counts that exceed the length of the data, as a conservative upstream estimate may; the scan
+    // must be clamped to the data
+    for count in [len as u32 + 1, 10 * len as u32, u32::MAX] {
+        println!(
+            "Scanning {} values with a count of {} on the device...",
+            len, count
+        );
+
+        let data_buffer: Buffer<[u32], _> = device.create_buffer(
+            vec![1; len],
+            buffer::Usages::storage_binding().and_copy_src(),
+        );
+        let count_buffer: Buffer<u32, _> =
+            device.create_buffer(count, buffer::Usages::uniform_binding());
+        let readback_buffer: Buffer<[u32], _> =
+            device.create_slice_buffer_zeroed(len, buffer::Usages::map_read().and_copy_dst());
+
+        let mut encoder = device.create_command_encoder();
+
+        encoder = prefix_sum.encode(
+            encoder,
+            PrefixSumInput {
+                data: data_buffer.view(),
+                count: Some(count_buffer.uniform()),
+                initial: None,
+                order: ScanOrder::Ascending,
+            },
+        );
+        encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+        device.queue().submit(encoder.finish());
+
+        readback_buffer.map_read().await?;
+
+        let data = readback_buffer.mapped();
+
+        for i in 0..len {
+            assert_eq!(data[i], i as u32 + 1);
+        }
+
+        mem::drop(data);
+
+        readback_buffer.unmap();
+
+        println!("...successfully!");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let len = 1_000_000;
+
+    let data: Vec<u32> = (0..len as u32).map(|i| i % 5).collect();
+
+    let count_buffer: Buffer<u32, _> =
+        device.create_buffer(len as u32 / 2 + 3, buffer::Usages::uniform_binding());
+
+    let evaluators = [
+        (
+            "exclusive",
+            PrefixSum::init_exclusive_u32(device.clone()).await,
+        ),
+        (
+            "inclusive",
+            PrefixSum::init_inclusive_u32(device.clone()).await,
+        ),
+    ];
+
+    for (variant, mut evaluator) in evaluators {
+        for indirect in [false, true] {
+            // With an indirect count, only the values up to the count are scanned, starting from the
+            // last value before the count
+            let count = if indirect { len / 2 + 3 } else { len };
+
+            println!(
+                "Evaluating a descending {} prefix-sum over {} of {} values...",
+                variant, count, len
+            );
+
+            let data_buffer: Buffer<[u32], _> =
+                device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+            let readback_buffer: Buffer<[u32], _> =
+                device.create_slice_buffer_zeroed(len, buffer::Usages::map_read().and_copy_dst());
+
+            let mut encoder = device.create_command_encoder();
+
+            encoder = evaluator.encode(
+                encoder,
+                PrefixSumInput {
+                    data: data_buffer.view(),
+                    count: if indirect {
+                        Some(count_buffer.uniform())
+                    } else {
+                        None
+                    },
+                    initial: None,
+                    order: ScanOrder::Descending,
+                },
+            );
+            encoder =
+                encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+            device.queue().submit(encoder.finish());
+
+            let mut expected = data.clone();
+            let mut sum = 0;
+
+            for i in (0..count).rev() {
+                if variant == "exclusive" {
+                    expected[i] = sum;
+                    sum += data[i];
+                } else {
+                    sum += data[i];
+                    expected[i] = sum;
+                }
+            }
+
+            let output = read_back_vec(&readback_buffer).await?;
+
+            assert_eq!(output, expected);
+
+            println!("...successfully!");
+        }
+    }
+
+    Ok(())
+}
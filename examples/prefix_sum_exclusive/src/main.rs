@@ -6,7 +6,7 @@ use empa::buffer;
 use empa::buffer::Buffer;
 use empa::device::DeviceDescriptor;
 use empa::native::Instance;
-use empa_tk::prefix_sum::{PrefixSum, PrefixSumInput};
+use empa_tk::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
 use futures::FutureExt;
 
 fn main() {
@@ -52,6 +52,8 @@ async fn run() -> Result<(), Box<dyn Error>> {
         PrefixSumInput {
             data: data_buffer.view(),
             count: None,
+            initial: None,
+            order: ScanOrder::Ascending,
         },
     );
     encoder = encoder.write_timestamp(&timestamp_query_set, 1);
@@ -0,0 +1,74 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prefix_sum::{PrefixSum, PrefixSumInput, ScanOrder};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+    let value = 10_000;
+
+    println!(
+        "Evaluating a saturating inclusive prefix-sum over a list of {} `{}`s.",
+        count, value
+    );
+
+    let mut evaluator = PrefixSum::init_inclusive_u32_saturating(device.clone()).await;
+
+    let data: Vec<u32> = vec![value; count];
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(data, buffer::Usages::storage_binding().and_copy_src());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_buffer(vec![0; count], buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = evaluator.encode(
+        encoder,
+        PrefixSumInput {
+            data: data_buffer.view(),
+            count: None,
+            initial: None,
+            order: ScanOrder::Ascending,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    readback_buffer.map_read().await?;
+
+    let data = readback_buffer.mapped();
+
+    println!("The last 10 numbers: {:#?}", &data[data.len() - 10..]);
+
+    println!("Asserting the values computed on the GPU saturate rather than wrap around...");
+
+    for i in 0..count {
+        let expected = (i as u32 + 1).saturating_mul(value);
+
+        assert_eq!(data[i], expected);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let chunk_size = 100_000;
+    let chunk_count = 4;
+
+    println!(
+        "Evaluating an inclusive prefix-sum over a stream of {} chunks of {} values, twice, \
+         resetting the stream in between...",
+        chunk_count, chunk_size
+    );
+
+    let mut stream = PrefixSumStream::<u32>::init_inclusive(device.clone()).await;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut sequences: Vec<Vec<u32>> = Vec::with_capacity(2);
+
+    for _ in 0..2 {
+        let mut data = Vec::with_capacity(chunk_size * chunk_count);
+
+        for _ in 0..chunk_size * chunk_count {
+            data.push(rng.rand_range(0..100));
+        }
+
+        sequences.push(data);
+    }
+
+    let mut chunk_buffers = Vec::new();
+    let mut readback_buffers = Vec::new();
+
+    for data in &sequences {
+        for chunk in data.chunks(chunk_size) {
+            let chunk_buffer: Buffer<[u32], _> =
+                device.create_buffer(chunk, buffer::Usages::storage_binding().and_copy_src());
+            let readback_buffer: Buffer<[u32], _> = device
+                .create_slice_buffer_zeroed(chunk_size, buffer::Usages::map_read().and_copy_dst());
+
+            chunk_buffers.push(chunk_buffer);
+            readback_buffers.push(readback_buffer);
+        }
+    }
+
+    let mut encoder = device.create_command_encoder();
+
+    for (i, (chunk_buffer, readback_buffer)) in
+        chunk_buffers.iter().zip(&readback_buffers).enumerate()
+    {
+        if i == chunk_count {
+            encoder = stream.reset(encoder);
+        }
+
+        encoder = stream.encode_chunk(encoder, chunk_buffer.view());
+        encoder = encoder.copy_buffer_to_buffer_slice(chunk_buffer.view(), readback_buffer.view());
+    }
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected = Vec::with_capacity(chunk_size * chunk_count * 2);
+
+    for data in &sequences {
+        let mut sum = 0u32;
+
+        for value in data {
+            sum += value;
+
+            expected.push(sum);
+        }
+    }
+
+    println!("Asserting each stream matches a scan over all of its values on the CPU...");
+
+    for (i, readback_buffer) in readback_buffers.iter().enumerate() {
+        readback_buffer.map_read().await?;
+
+        let readback = readback_buffer.mapped();
+
+        assert_eq!(
+            &readback[..],
+            &expected[i * chunk_size..(i + 1) * chunk_size]
+        );
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
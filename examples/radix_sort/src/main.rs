@@ -38,8 +38,7 @@ async fn run() -> Result<(), Box<dyn Error>> {
 
     let data_buffer: Buffer<[u32], _> =
         device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
-    let temp_storage_buffer: Buffer<[u32], _> =
-        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let temp_storage_buffer = RadixSort::<u32>::temporary_storage_buffer(&device, count);
     let readback_buffer: Buffer<[u32], _> =
         device.create_buffer(vec![0; count], buffer::Usages::map_read().and_copy_dst());
     let timestamp_query_set = device.create_timestamp_query_set(2);
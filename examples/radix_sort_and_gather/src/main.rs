@@ -0,0 +1,85 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::radix_sort::{RadixSortAndGather, RadixSortAndGatherInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    println!(
+        "Sorting {} 16-byte payload values by their keys through an index permutation...",
+        count
+    );
+
+    let mut sort_and_gather = RadixSortAndGather::<[u32; 4]>::init(device.clone()).await?;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut keys: Vec<u32> = Vec::with_capacity(count);
+    let mut payload: Vec<[u32; 4]> = Vec::with_capacity(count);
+
+    for i in 0..count as u32 {
+        let key = rng.rand_u32();
+
+        keys.push(key);
+        payload.push([key, i, !key, i * 2]);
+    }
+
+    let keys_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*keys, buffer::Usages::storage_binding());
+    let payload_buffer: Buffer<[[u32; 4]], _> =
+        device.create_buffer(&*payload, buffer::Usages::storage_binding());
+    let output_buffer: Buffer<[[u32; 4]], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let readback_buffer: Buffer<[[u32; 4]], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = sort_and_gather.encode(
+        encoder,
+        RadixSortAndGatherInput {
+            keys: keys_buffer.view(),
+            payload: payload_buffer.view(),
+            count: None,
+        },
+        output_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    // The sort is stable, so a stable CPU sort by the key must produce the same order
+    payload.sort_by_key(|value| value[0]);
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    println!("Asserting the payload gathered on the GPU matches the payload sorted on the CPU...");
+
+    for i in 0..count {
+        assert_eq!(readback[i], payload[i]);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use std::error::Error;
+use std::mem;
+
+use bytemuck::Zeroable;
+use empa::adapter::Feature;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa::{abi, buffer};
+use empa_tk::radix_sort::{KeyFunction, RadixSortByKey, RadixSortByKeyInput};
+use empa_tk::read_back_vec;
+use futures::FutureExt;
+
+#[derive(abi::Sized, Clone, Copy, PartialEq, Default, Debug, Zeroable)]
+#[repr(C)]
+struct Element {
+    payload_a: u32,
+    key: u32,
+    payload_b: [u32; 2],
+}
+
+// Sorts an array of structs by a key field at a fixed offset within each struct, moving the
+// whole structs, without separating the keys into a buffer of their own.
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 500_000;
+
+    println!(
+        "Sorting {} interleaved elements by their key field...",
+        count
+    );
+
+    let mut radix_sort = RadixSortByKey::<Element>::init(
+        device.clone(),
+        KeyFunction::field_at_offset(mem::offset_of!(Element, key)),
+    )
+    .await?;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<Element> = (0..count)
+        .map(|i| Element {
+            payload_a: i as u32,
+            key: rng.rand_range(0..10_000),
+            payload_b: [rng.rand_u32(), rng.rand_u32()],
+        })
+        .collect();
+
+    let data_buffer: Buffer<[Element], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let temporary_storage_buffer: Buffer<[Element], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let readback_buffer: Buffer<[Element], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortByKeyInput {
+            data: data_buffer.view(),
+            temporary_storage: temporary_storage_buffer.view(),
+            count: None,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    // The GPU sort is stable, so a stable CPU sort by the same key produces the same order
+    data.sort_by_key(|element| element.key);
+
+    println!("Asserting the elements sorted on the GPU match the elements sorted on the CPU...");
+
+    assert_eq!(read_back_vec(&readback_buffer).await?, data);
+
+    println!("...successfully!");
+
+    Ok(())
+}
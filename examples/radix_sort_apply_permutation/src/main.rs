@@ -0,0 +1,93 @@
+use std::error::Error;
+
+use bytemuck::Zeroable;
+use empa::adapter::Feature;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa::{abi, buffer};
+use empa_tk::radix_sort::RadixSortBy;
+use empa_tk::read_back_vec;
+use futures::FutureExt;
+
+#[derive(abi::Sized, Clone, Copy, PartialEq, Default, Debug, Zeroable)]
+#[repr(C)]
+struct MyValue {
+    field_a: u32,
+    field_b: f32,
+    field_c: u32,
+    field_d: u32,
+}
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort_by = RadixSortBy::<u32, MyValue>::init_u32(device.clone()).await?;
+
+    let count = 1_000_000;
+
+    println!("Applying a known permutation to {} values...", count);
+
+    // A permutation from e.g. the preceding frame's sort: a random shuffle of the indices
+    let mut rng = oorandom::Rand32::new(1);
+    let mut permutation: Vec<u32> = (0..count as u32).collect();
+
+    for i in (1..count).rev() {
+        let j = rng.rand_range(0..i as u32 + 1) as usize;
+
+        permutation.swap(i, j);
+    }
+
+    let values: Vec<MyValue> = (0..count)
+        .map(|i| MyValue {
+            field_a: i as u32,
+            field_b: i as f32,
+            field_c: rng.rand_u32(),
+            field_d: !(i as u32),
+        })
+        .collect();
+
+    let permutation_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*permutation, buffer::Usages::storage_binding());
+    let values_buffer: Buffer<[MyValue], _> =
+        device.create_buffer(&*values, buffer::Usages::storage_binding());
+    let output_buffer: Buffer<[MyValue], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let readback_buffer: Buffer<[MyValue], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort_by.apply_permutation(
+        encoder,
+        permutation_buffer.view(),
+        values_buffer.view(),
+        output_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    let expected: Vec<MyValue> = permutation.iter().map(|i| values[*i as usize]).collect();
+
+    let output = read_back_vec(&readback_buffer).await?;
+
+    println!("Asserting the values are in the order of the permutation...");
+
+    assert_eq!(output, expected);
+
+    println!("...successfully!");
+
+    Ok(())
+}
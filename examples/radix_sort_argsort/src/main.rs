@@ -0,0 +1,86 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::radix_sort::{RadixSortBy, RadixSortByInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort_by = RadixSortBy::<u32, u32>::init_u32(device.clone()).await?;
+
+    let count = 1_000_000;
+
+    println!("Computing the sorted permutation of {} keys...", count);
+
+    // A small key range, so that the stable order of equal keys is also verified
+    let mut rng = oorandom::Rand32::new(1);
+    let keys: Vec<u32> = (0..count).map(|_| rng.rand_range(0..1000)).collect();
+
+    let keys_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*keys, buffer::Usages::storage_binding());
+    let temp_key_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let permutation_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let temp_permutation_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+
+    let permutation_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort_by.encode_argsort(
+        encoder,
+        RadixSortByInput {
+            keys: keys_buffer.view(),
+            values: permutation_buffer.view(),
+            temporary_key_storage: temp_key_storage_buffer.view(),
+            temporary_value_storage: temp_permutation_storage_buffer.view(),
+            count: None,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        permutation_buffer.view(),
+        permutation_readback_buffer.view(),
+    );
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected: Vec<u32> = (0..count as u32).collect();
+
+    expected.sort_by_key(|i| keys[*i as usize]);
+
+    permutation_readback_buffer.map_read().await?;
+
+    let permutation = permutation_readback_buffer.mapped();
+
+    println!("Asserting the permutation matches the expected permutation...");
+
+    assert_eq!(&*permutation, &expected[..]);
+
+    println!("...successfully!");
+
+    mem::drop(permutation);
+
+    permutation_readback_buffer.unmap();
+
+    Ok(())
+}
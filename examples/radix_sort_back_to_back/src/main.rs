@@ -0,0 +1,102 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort = RadixSort::init_u32(device.clone()).await;
+
+    // Inputs of different sizes, so that each sort uses different parameters and a different
+    // number of workgroups
+    let counts = [1_000_000, 1_000, 250_000];
+
+    println!(
+        "Encoding sorts of {:?} values back to back into a single command buffer with the same \
+        `RadixSort`...",
+        counts
+    );
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    let inputs: Vec<Vec<u32>> = counts
+        .iter()
+        .map(|count| (0..*count).map(|_| rng.rand_u32()).collect())
+        .collect();
+
+    let data_buffers: Vec<Buffer<[u32], _>> = inputs
+        .iter()
+        .map(|data| device.create_buffer(&**data, buffer::Usages::storage_binding().and_copy_src()))
+        .collect();
+    let readback_buffers: Vec<Buffer<[u32], _>> = counts
+        .iter()
+        .map(|count| {
+            device.create_slice_buffer_zeroed(*count, buffer::Usages::map_read().and_copy_dst())
+        })
+        .collect();
+
+    // The sorts also share their temporary storage
+    let max_count = counts.iter().copied().max().unwrap();
+    let temp_storage_buffer = RadixSort::<u32>::temporary_storage_buffer(&device, max_count);
+
+    let mut encoder = device.create_command_encoder();
+
+    for data_buffer in &data_buffers {
+        encoder = radix_sort.encode(
+            encoder,
+            RadixSortInput {
+                data: data_buffer.view(),
+                temporary_storage: temp_storage_buffer.view(),
+                count: None,
+            },
+        );
+    }
+
+    for (data_buffer, readback_buffer) in data_buffers.iter().zip(&readback_buffers) {
+        encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+    }
+
+    device.queue().submit(encoder.finish());
+
+    println!("Asserting each sort's output matches the CPU sort of its input...");
+
+    for (mut expected, readback_buffer) in inputs.into_iter().zip(&readback_buffers) {
+        expected.sort();
+
+        readback_buffer.map_read().await?;
+
+        let readback = readback_buffer.mapped();
+
+        assert_eq!(readback.len(), expected.len());
+
+        for i in 0..expected.len() {
+            assert_eq!(readback[i], expected[i]);
+        }
+
+        mem::drop(readback);
+
+        readback_buffer.unmap();
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::error::Error;
+use std::fmt::Debug;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::{Device, DeviceDescriptor};
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    println!(
+        "Finding the bounds of {} i32 values while sorting...",
+        count
+    );
+
+    let data: Vec<i32> = (0..count)
+        .map(|_| rng.rand_range(0..2_000_000) as i32 - 1_000_000)
+        .collect();
+    let mut radix_sort = RadixSort::<i32>::init_i32(device.clone()).await;
+
+    sort_and_verify(&device, &mut radix_sort, data).await?;
+
+    println!(
+        "Finding the bounds of {} f32 values while sorting...",
+        count
+    );
+
+    let data: Vec<f32> = (0..count)
+        .map(|_| rng.rand_float() * 2_000_000.0 - 1_000_000.0)
+        .collect();
+    let mut radix_sort = RadixSort::<f32>::init_f32(device.clone()).await;
+
+    sort_and_verify(&device, &mut radix_sort, data).await?;
+
+    println!("Finding the bounds of only negative f32 values while sorting...");
+
+    let data: Vec<f32> = (0..count)
+        .map(|_| -1.0 - rng.rand_float() * 100.0)
+        .collect();
+
+    sort_and_verify(&device, &mut radix_sort, data).await?;
+
+    Ok(())
+}
+
+async fn sort_and_verify<T>(
+    device: &Device,
+    radix_sort: &mut RadixSort<T>,
+    data: Vec<T>,
+) -> Result<(), Box<dyn Error>>
+where
+    T: RadixKey + Copy + PartialOrd + Default + Debug,
+{
+    let expected_min = data
+        .iter()
+        .copied()
+        .fold(data[0], |a, b| if b < a { b } else { a });
+    let expected_max = data
+        .iter()
+        .copied()
+        .fold(data[0], |a, b| if b > a { b } else { a });
+
+    let data_buffer: Buffer<[T], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let temp_storage_buffer: Buffer<[T], _> =
+        device.create_slice_buffer_zeroed(data.len(), buffer::Usages::storage_binding());
+    let bounds_buffer: Buffer<[T; 2], _> = device.create_buffer(
+        [T::default(); 2],
+        buffer::Usages::storage_binding().and_copy_src(),
+    );
+    let bounds_readback: Buffer<[T; 2], _> =
+        device.create_buffer([T::default(); 2], buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode_with_bounds(
+        encoder,
+        RadixSortInput {
+            data: data_buffer.view(),
+            temporary_storage: temp_storage_buffer.view(),
+            count: None,
+        },
+        bounds_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer(bounds_buffer.view(), bounds_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    bounds_readback.map_read().await?;
+
+    let bounds = *bounds_readback.mapped();
+
+    println!("GPU bounds: {:?}", bounds);
+    println!("CPU bounds (reference): {:?}", [expected_min, expected_max]);
+
+    assert_eq!(bounds, [expected_min, expected_max]);
+
+    println!("...bounds match!");
+
+    bounds_readback.unmap();
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    // Use smaller workgroups that process more values per thread, as may be preferable on devices
+    // with narrow SIMD units. The internal state is sized for the expected count up front, so that
+    // the first encode does not allocate.
+    let mut radix_sort = RadixSortBuilder::<u32>::new(device.clone())
+        .group_size(64)
+        .values_per_thread(8)
+        .max_count(count as u32)
+        .finish()
+        .await;
+    let mut prefix_sum = PrefixSumBuilder::<u32>::inclusive(device.clone())
+        .group_size(64)
+        .values_per_thread(16)
+        .max_count(count as u32)
+        .finish()
+        .await;
+
+    println!(
+        "Sorting {} numbers and computing their prefix sum with custom group sizes...",
+        count
+    );
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        data.push(rng.rand_range(0..1_000));
+    }
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let temp_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortInput {
+            data: data_buffer.view(),
+            temporary_storage: temp_storage_buffer.view(),
+            count: None,
+        },
+    );
+    encoder = prefix_sum.encode(
+        encoder,
+        PrefixSumInput {
+            data: data_buffer.view(),
+            count: None,
+            initial: None,
+            order: ScanOrder::Ascending,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    data.sort();
+
+    let mut sum = 0;
+
+    for value in data.iter_mut() {
+        sum += *value;
+        *value = sum;
+    }
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    println!("Asserting all values produced by the GPU match the values produced by the CPU...");
+
+    for i in 0..count {
+        assert_eq!(readback[i], data[i]);
+    }
+
+    println!("...successfully!");
+
+    mem::drop(readback);
+
+    readback_buffer.unmap();
+
+    // With a count that is only known on the device, the sort generates its dispatches on the
+    // device, which must account for the custom segment sizes
+    let indirect_count = count - 12_345;
+
+    println!(
+        "Sorting the first {} of {} numbers with an indirect count and custom group sizes...",
+        indirect_count, count
+    );
+
+    let mut data: Vec<u32> = (0..count).map(|_| rng.rand_range(0..1_000)).collect();
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let count_buffer: Buffer<u32, _> =
+        device.create_buffer(indirect_count as u32, buffer::Usages::uniform_binding());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortInput {
+            data: data_buffer.view(),
+            temporary_storage: temp_storage_buffer.view(),
+            count: Some(Count::Indirect(count_buffer.uniform())),
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    data[..indirect_count].sort();
+
+    println!("Asserting the sorted values and the values beyond the count are as expected...");
+
+    assert_eq!(read_back_vec(&readback_buffer).await?, data);
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -31,7 +31,7 @@ async fn run() -> Result<(), Box<dyn Error>> {
         })
         .await?;
 
-    let mut radix_sort_by = RadixSortBy::init_u32(device.clone()).await;
+    let mut radix_sort_by = RadixSortBy::init_u32(device.clone()).await?;
 
     let count = 1_000_000;
 
@@ -0,0 +1,115 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort_by = RadixSortBy::<u32, u32>::init_u32(device.clone()).await?;
+
+    // The buffers are sized to a capacity; only the first `count` keys are live. The values
+    // buffer is over-allocated further still, to verify that its length does not affect the sort.
+    let key_capacity = 1_200_000;
+    let value_capacity = 1_500_000;
+    let count = 1_000_000;
+
+    println!(
+        "Sorting the first {} of {} keys (with {} values allocated)...",
+        count, key_capacity, value_capacity
+    );
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    let keys: Vec<u32> = (0..key_capacity).map(|_| rng.rand_u32()).collect();
+    let values: Vec<u32> = (0..value_capacity as u32).collect();
+
+    let keys_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*keys, buffer::Usages::storage_binding().and_copy_src());
+    let temp_key_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let values_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*values, buffer::Usages::storage_binding().and_copy_src());
+    let temp_value_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+
+    let keys_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(key_capacity, buffer::Usages::map_read().and_copy_dst());
+    let values_readback_buffer: Buffer<[u32], _> = device
+        .create_slice_buffer_zeroed(value_capacity, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort_by.encode(
+        encoder,
+        RadixSortByInput {
+            keys: keys_buffer.view(),
+            values: values_buffer.view(),
+            temporary_key_storage: temp_key_storage_buffer.view(),
+            temporary_value_storage: temp_value_storage_buffer.view(),
+            count: Some(Count::Static(count as u32)),
+        },
+    );
+
+    encoder = encoder.copy_buffer_to_buffer_slice(keys_buffer.view(), keys_readback_buffer.view());
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(values_buffer.view(), values_readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected: Vec<(u32, u32)> = keys[..count]
+        .iter()
+        .copied()
+        .zip(values[..count].iter().copied())
+        .collect();
+
+    // A stable sort, to match the order of values with equal keys
+    expected.sort_by_key(|(key, _)| *key);
+
+    keys_readback_buffer.map_read().await?;
+    values_readback_buffer.map_read().await?;
+
+    let keys_readback = keys_readback_buffer.mapped();
+    let values_readback = values_readback_buffer.mapped();
+
+    println!("Asserting the live keys and values are sorted...");
+
+    for (i, (key, value)) in expected.iter().enumerate() {
+        assert_eq!(keys_readback[i], *key);
+        assert_eq!(values_readback[i], *value);
+    }
+
+    println!("...successfully!");
+
+    println!("Asserting the elements past the count were left untouched...");
+
+    assert_eq!(&keys_readback[count..], &keys[count..]);
+    assert_eq!(&values_readback[count..], &values[count..]);
+
+    println!("...successfully!");
+
+    mem::drop(keys_readback);
+    mem::drop(values_readback);
+
+    keys_readback_buffer.unmap();
+    values_readback_buffer.unmap();
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort_by = RadixSortBy::init_f32_zero_equal(device.clone()).await?;
+
+    let count = 100_000;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut keys: Vec<f32> = Vec::with_capacity(count);
+
+    // Roughly half of the keys are zeros of either sign, the rest are small integers of either sign
+    for _ in 0..count {
+        let key = match rng.rand_range(0..4) {
+            0 => 0.0,
+            1 => -0.0,
+            _ => rng.rand_range(1..8) as f32 * if rng.rand_u32() & 1 == 0 { 1.0 } else { -1.0 },
+        };
+
+        keys.push(key);
+    }
+
+    let values: Vec<u32> = (0..count as u32).collect();
+
+    println!(
+        "Sorting {} values by `f32` keys that contain many `-0.0` and `+0.0` keys...",
+        count
+    );
+
+    let keys_buffer: Buffer<[f32], _> =
+        device.create_buffer(&*keys, buffer::Usages::storage_binding().and_copy_src());
+    let temp_key_storage_buffer: Buffer<[f32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let values_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*values, buffer::Usages::storage_binding().and_copy_src());
+    let temp_value_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let keys_readback_buffer: Buffer<[f32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let values_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort_by.encode(
+        encoder,
+        RadixSortByInput {
+            keys: keys_buffer.view(),
+            values: values_buffer.view(),
+            temporary_key_storage: temp_key_storage_buffer.view(),
+            temporary_value_storage: temp_value_storage_buffer.view(),
+            count: None,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(keys_buffer.view(), keys_readback_buffer.view());
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(values_buffer.view(), values_readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    // `sort_by` is stable and `partial_cmp` considers `-0.0` and `+0.0` equal
+    let mut expected = values;
+
+    expected.sort_by(|a, b| keys[*a as usize].partial_cmp(&keys[*b as usize]).unwrap());
+
+    keys_readback_buffer.map_read().await?;
+    values_readback_buffer.map_read().await?;
+
+    let keys_readback = keys_readback_buffer.mapped();
+    let values_readback = values_readback_buffer.mapped();
+
+    println!(
+        "Asserting that the GPU sort matches a stable CPU sort that treats both zeros as equal..."
+    );
+
+    for i in 0..count {
+        assert_eq!(values_readback[i], expected[i]);
+
+        // The keys are moved unmodified, so a `-0.0` key must still be `-0.0`
+        let expected_key = keys[expected[i] as usize];
+
+        assert_eq!(keys_readback[i].to_bits(), expected_key.to_bits());
+    }
+
+    println!("...successfully!");
+
+    mem::drop(keys_readback);
+    mem::drop(values_readback);
+
+    keys_readback_buffer.unmap();
+    values_readback_buffer.unmap();
+
+    Ok(())
+}
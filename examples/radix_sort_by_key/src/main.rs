@@ -0,0 +1,86 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+
+    println!("Sorting {} records by the hash of their id field...", count);
+
+    // Each record holds an index in field `0` and an id in field `1`
+    let mut radix_sort =
+        RadixSortByKey::<[u32; 4]>::init(device.clone(), KeyFunction::FieldHash(1)).await?;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<[u32; 4]> = Vec::with_capacity(count);
+
+    for i in 0..count {
+        // Draw the ids from a small range, so that many records share a key
+        data.push([
+            i as u32,
+            rng.rand_range(0..1000),
+            rng.rand_u32(),
+            rng.rand_u32(),
+        ]);
+    }
+
+    let data_buffer: Buffer<[[u32; 4]], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let temporary_storage_buffer: Buffer<[[u32; 4]], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let readback_buffer: Buffer<[[u32; 4]], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortByKeyInput {
+            data: data_buffer.view(),
+            temporary_storage: temporary_storage_buffer.view(),
+            count: None,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    // The GPU sort is stable, so a stable CPU sort by the same key produces the same order
+    data.sort_by_key(|record| KeyFunction::hash(record[1]));
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    println!(
+        "Asserting all records produced by the GPU sort match the records produced by the CPU \
+         sort..."
+    );
+
+    for i in 0..count {
+        assert_eq!(readback[i], data[i]);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
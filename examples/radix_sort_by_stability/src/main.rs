@@ -0,0 +1,110 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::{Device, DeviceDescriptor};
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort_by = RadixSortBy::init_u32(device.clone()).await?;
+
+    let count = 1_000_000;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut keys: Vec<u32> = Vec::with_capacity(count);
+
+    // Few distinct keys spread over all 4 radix digits, so that every pass moves many equal keys
+    for _ in 0..count {
+        keys.push(rng.rand_range(0..16) * 0x01010101);
+    }
+
+    println!(
+        "Sorting {} values by keys with many duplicates; equal keys must keep their values in \
+        their original order...",
+        count
+    );
+
+    sort_and_verify(&device, &mut radix_sort_by, keys).await?;
+
+    println!("...successfully!");
+
+    println!(
+        "Sorting {} values by identical keys; the values must remain unchanged...",
+        count
+    );
+
+    sort_and_verify(&device, &mut radix_sort_by, vec![7; count]).await?;
+
+    println!("...successfully!");
+
+    Ok(())
+}
+
+/// Sorts the indices of the `keys` by the `keys` on the GPU, and asserts that the result matches
+/// a stable sort on the CPU.
+async fn sort_and_verify(
+    device: &Device,
+    radix_sort_by: &mut RadixSortBy<u32, u32>,
+    keys: Vec<u32>,
+) -> Result<(), Box<dyn Error>> {
+    let count = keys.len();
+    let values: Vec<u32> = (0..count as u32).collect();
+
+    let keys_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*keys, buffer::Usages::storage_binding());
+    let temp_key_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let values_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*values, buffer::Usages::storage_binding().and_copy_src());
+    let temp_value_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort_by.encode(
+        encoder,
+        RadixSortByInput {
+            keys: keys_buffer.view(),
+            values: values_buffer.view(),
+            temporary_key_storage: temp_key_storage_buffer.view(),
+            temporary_value_storage: temp_value_storage_buffer.view(),
+            count: None,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(values_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    // `sort_by_key` is stable
+    let mut expected = values;
+
+    expected.sort_by_key(|i| keys[*i as usize]);
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    for i in 0..count {
+        assert_eq!(readback[i], expected[i]);
+    }
+
+    Ok(())
+}
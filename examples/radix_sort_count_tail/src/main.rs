@@ -0,0 +1,115 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::{Device, DeviceDescriptor};
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort = RadixSort::<u32>::init_u32(device.clone()).await;
+
+    let len = 1_000_003;
+
+    for count in [0, 1, 1023, 1025, 65_537, 999_999, len] {
+        for indirect in [false, true] {
+            println!(
+                "Sorting the first {} of {} values with {} count...",
+                count,
+                len,
+                if indirect { "an indirect" } else { "a static" }
+            );
+
+            verify_tail_preserved(&device, &mut radix_sort, len, count, indirect).await?;
+
+            println!("...the values beyond the count are preserved!");
+        }
+    }
+
+    Ok(())
+}
+
+async fn verify_tail_preserved(
+    device: &Device,
+    radix_sort: &mut RadixSort<u32>,
+    len: usize,
+    count: usize,
+    indirect: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut rng = oorandom::Rand32::new(count as u64);
+
+    // The tail holds values that would be sorted to the front if they took part in the sort
+    let data: Vec<u32> = (0..len)
+        .map(|i| {
+            if i < count {
+                rng.rand_u32()
+            } else {
+                i as u32 % 7
+            }
+        })
+        .collect();
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let count_buffer: Buffer<u32, _> =
+        device.create_buffer(count as u32, buffer::Usages::uniform_binding());
+    // An indirect count is only known on the device, so the temporary storage must then be able to
+    // hold all of the data
+    let temp_len = if indirect { len } else { count };
+    let temp_storage_buffer = RadixSort::<u32>::temporary_storage_buffer(device, temp_len.max(1));
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(len, buffer::Usages::map_read().and_copy_dst());
+
+    let count_input = if indirect {
+        Count::Indirect(count_buffer.uniform())
+    } else {
+        Count::Static(count as u32)
+    };
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortInput {
+            data: data_buffer.view(),
+            temporary_storage: temp_storage_buffer.view(),
+            count: Some(count_input),
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected = data.clone();
+
+    expected[..count].sort();
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    assert_eq!(&readback[..count], &expected[..count]);
+    assert_eq!(&readback[count..], &data[count..]);
+
+    mem::drop(readback);
+
+    readback_buffer.unmap();
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+
+    println!(
+        "Counting the distinct digits of {} keys while sorting...",
+        count
+    );
+
+    let mut radix_sort = RadixSort::init_u32(device.clone()).await;
+
+    // Keys with a constant most significant byte and few distinct values in the second byte
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        data.push(0x7F00_0000 | rng.rand_range(0..8) << 16 | rng.rand_range(0..0x1_0000));
+    }
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let temporary_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let readback_buffer: Buffer<[u32; 4], _> =
+        device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortInput {
+            data: data_buffer.view(),
+            temporary_storage: temporary_storage_buffer.view(),
+            count: None,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer(radix_sort.digit_counts(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected = [0u32; 4];
+
+    for (group, expected) in expected.iter_mut().enumerate() {
+        let mut seen = [false; 256];
+
+        for key in &data {
+            seen[((key >> (group * 8)) & 0xFF) as usize] = true;
+        }
+
+        *expected = seen.iter().filter(|seen| **seen).count() as u32;
+    }
+
+    readback_buffer.map_read().await?;
+
+    let digit_counts = *readback_buffer.mapped();
+
+    println!("Asserting the digit counts match the digit counts computed on the CPU...");
+
+    assert_eq!(digit_counts, expected);
+    assert_eq!(digit_counts[3], 1);
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::radix_sort::{FixedSizeRadixSort, RadixSort};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let len = 1_000_000;
+
+    let mut radix_sort = FixedSizeRadixSort::<u32>::init(device.clone(), len).await;
+
+    let data_buffer = RadixSort::<u32>::sorted_output_buffer(&device, len);
+    let temp_storage_buffer = RadixSort::<u32>::temporary_storage_buffer(&device, len);
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(len, buffer::Usages::map_read().and_copy_dst());
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    // Sort a new set of values of the same length each "frame"
+    for frame in 0..4 {
+        println!("Frame {}: sorting {} values...", frame, len);
+
+        let data: Vec<u32> = (0..len).map(|_| rng.rand_u32()).collect();
+
+        let upload_buffer: Buffer<[u32], _> =
+            device.create_buffer(&*data, buffer::Usages::copy_src());
+
+        let mut encoder = device.create_command_encoder();
+
+        encoder = encoder.copy_buffer_to_buffer_slice(upload_buffer.view(), data_buffer.view());
+        encoder = radix_sort.encode(encoder, data_buffer.view(), temp_storage_buffer.view());
+        encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+        device.queue().submit(encoder.finish());
+
+        let mut expected = data;
+
+        expected.sort();
+
+        readback_buffer.map_read().await?;
+
+        let sorted = readback_buffer.mapped();
+
+        assert_eq!(&*sorted, &expected[..]);
+
+        mem::drop(sorted);
+
+        readback_buffer.unmap();
+
+        println!("...successfully!");
+    }
+
+    Ok(())
+}
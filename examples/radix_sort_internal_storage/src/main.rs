@@ -0,0 +1,69 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort = RadixSort::init_u32(device.clone()).await;
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    // A small input followed by a larger one, so that the internal storage has to grow
+    for count in [1_000, 1_000_000] {
+        println!(
+            "Sorting {} values with internal temporary storage...",
+            count
+        );
+
+        let mut data: Vec<u32> = (0..count).map(|_| rng.rand_u32()).collect();
+
+        let data_buffer: Buffer<[u32], _> =
+            device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+        let readback_buffer: Buffer<[u32], _> =
+            device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+        let mut encoder = device.create_command_encoder();
+
+        encoder = radix_sort.encode_with_internal_storage(encoder, data_buffer.view());
+        encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+        device.queue().submit(encoder.finish());
+
+        data.sort();
+
+        readback_buffer.map_read().await?;
+
+        let readback = readback_buffer.mapped();
+
+        for i in 0..count {
+            assert_eq!(readback[i], data[i]);
+        }
+
+        println!("...successfully!");
+
+        mem::drop(readback);
+
+        readback_buffer.unmap();
+    }
+
+    Ok(())
+}
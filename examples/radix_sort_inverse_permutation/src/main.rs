@@ -0,0 +1,104 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::radix_sort::{RadixSortAndGather, RadixSortAndGatherInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    println!(
+        "Sorting {} keys with many duplicates and computing the inverse permutation...",
+        count
+    );
+
+    let mut sort_and_gather = RadixSortAndGather::<u32>::init(device.clone()).await?;
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    let keys: Vec<u32> = (0..count).map(|_| rng.rand_range(0..1000)).collect();
+    let payload: Vec<u32> = (0..count as u32).collect();
+
+    let keys_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*keys, buffer::Usages::storage_binding());
+    let payload_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*payload, buffer::Usages::storage_binding());
+    let permutation_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let inverse_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let permutation_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let inverse_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    // Gathering the original indices yields the forward permutation
+    encoder = sort_and_gather.encode_with_inverse_permutation(
+        encoder,
+        RadixSortAndGatherInput {
+            keys: keys_buffer.view(),
+            payload: payload_buffer.view(),
+            count: None,
+        },
+        permutation_buffer.view(),
+        inverse_buffer.view(),
+    );
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(permutation_buffer.view(), permutation_readback.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(inverse_buffer.view(), inverse_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    // The sort is stable, so a stable CPU argsort must produce the same permutation
+    let mut expected_permutation = payload.clone();
+
+    expected_permutation.sort_by_key(|i| keys[*i as usize]);
+
+    permutation_readback.map_read().await?;
+    inverse_readback.map_read().await?;
+
+    let permutation = permutation_readback.mapped();
+    let inverse = inverse_readback.mapped();
+
+    println!("Asserting the permutation matches a stable argsort on the CPU...");
+
+    assert_eq!(&*permutation, &expected_permutation[..]);
+
+    println!("...successfully!");
+
+    println!("Asserting the inverse permutation inverts the permutation...");
+
+    for (sorted_position, original_position) in permutation.iter().enumerate() {
+        assert_eq!(inverse[*original_position as usize], sorted_position as u32);
+    }
+
+    println!("...successfully!");
+
+    mem::drop(permutation);
+    mem::drop(inverse);
+
+    permutation_readback.unmap();
+    inverse_readback.unmap();
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+    let high_bits = 4;
+
+    println!(
+        "Partitioning {} values into {} buckets by the top {} bits of their keys...",
+        count,
+        1 << high_bits,
+        high_bits
+    );
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        data.push(rng.rand_u32());
+    }
+
+    let mut radix_sort = RadixSort::init_u32(device.clone()).await;
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let output_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let bucket_offsets_buffer: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
+        1 << high_bits,
+        buffer::Usages::storage_binding()
+            .and_copy_dst()
+            .and_copy_src(),
+    );
+    let output_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let bucket_offsets_readback: Buffer<[u32], _> = device
+        .create_slice_buffer_zeroed(1 << high_bits, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode_msd_partition(
+        encoder,
+        RadixSortPartitionInput {
+            data: data_buffer.view(),
+            count: None,
+        },
+        RadixSortPartitionOutput {
+            data: output_buffer.view(),
+            bucket_offsets: bucket_offsets_buffer.view(),
+        },
+        high_bits,
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), output_readback.view());
+    encoder = encoder
+        .copy_buffer_to_buffer_slice(bucket_offsets_buffer.view(), bucket_offsets_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    output_readback.map_read().await?;
+    bucket_offsets_readback.map_read().await?;
+
+    let output = output_readback.mapped();
+    let bucket_offsets = bucket_offsets_readback.mapped();
+
+    // The partition is stable, so each bucket holds its values in input order
+    let bucket = |value: u32| value >> (32 - high_bits);
+    let mut expected = data.clone();
+
+    expected.sort_by_key(|value| bucket(*value));
+
+    let mut expected_offsets = vec![0u32; 1 << high_bits];
+
+    for value in &data {
+        for offset in &mut expected_offsets[bucket(*value) as usize + 1..] {
+            *offset += 1;
+        }
+    }
+
+    println!("Asserting the partition matches a stable partition on the CPU...");
+
+    assert_eq!(&output[..], &expected[..]);
+    assert_eq!(&bucket_offsets[..], &expected_offsets[..]);
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+
+    println!("Sorting {} 128-bit keys...", count);
+
+    let mut radix_sort = RadixSortMultiWord::<[u32; 4]>::init(device.clone()).await?;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<[u32; 4]> = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        // Draw the most significant words from a small range, so that many keys only differ in
+        // their less significant words
+        data.push([
+            rng.rand_range(0..4),
+            rng.rand_range(0..16),
+            rng.rand_u32(),
+            rng.rand_u32(),
+        ]);
+    }
+
+    let data_buffer: Buffer<[[u32; 4]], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let temporary_storage_buffer: Buffer<[[u32; 4]], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let readback_buffer: Buffer<[[u32; 4]], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortMultiWordInput {
+            data: data_buffer.view(),
+            temporary_storage: temporary_storage_buffer.view(),
+            count: None,
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    // Arrays compare lexicographically, which matches the big-endian word order of the GPU sort
+    data.sort();
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    println!(
+        "Asserting all keys produced by the GPU sort match the keys produced by the CPU sort..."
+    );
+
+    for i in 0..count {
+        assert_eq!(readback[i], data[i]);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+
+    println!(
+        "Sorting {} signed 16-bit keys, packed two per word...",
+        count * 2
+    );
+
+    let mut radix_sort = RadixSortPacked16::init_i16(device.clone()).await;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut keys: Vec<i16> = Vec::with_capacity(count * 2);
+
+    for _ in 0..count * 2 {
+        keys.push(rng.rand_u32() as i16);
+    }
+
+    let packed: Vec<u32> = keys
+        .chunks(2)
+        .map(|pair| (pair[0] as u16 as u32) | ((pair[1] as u16 as u32) << 16))
+        .collect();
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(packed, buffer::Usages::storage_binding().and_copy_src());
+    let unpacked_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count * 2, buffer::Usages::storage_binding());
+    let temporary_storage: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count * 2, buffer::Usages::storage_binding());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortPacked16Input {
+            data: data_buffer.view(),
+            unpacked_storage: unpacked_buffer.view(),
+            temporary_storage: temporary_storage.view(),
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+    let sorted: Vec<i16> = readback
+        .iter()
+        .flat_map(|word| [*word as u16 as i16, (*word >> 16) as u16 as i16])
+        .collect();
+
+    keys.sort();
+
+    println!("Asserting the unpacked keys match a sort on the CPU...");
+
+    assert_eq!(sorted, keys);
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use std::error::Error;
+use std::ops::Range;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::radix_sort::{RadixSort, RadixSortInput};
+use empa_tk::read_back_vec;
+use futures::FutureExt;
+
+// Splits a sort across multiple submissions by encoding its passes in ranges, using a fresh
+// temporary buffer for every range, and verifies the sorted result.
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let data: Vec<u32> = (0..count).map(|_| rng.rand_u32()).collect();
+
+    let mut expected = data.clone();
+
+    expected.sort();
+
+    let mut radix_sort = RadixSort::init_u32(device.clone()).await;
+
+    let splits: [&[Range<usize>]; 3] = [&[0..2, 2..4], &[0..1, 1..3, 3..4], &[0..3, 3..4]];
+
+    for ranges in splits {
+        println!(
+            "Sorting {} values in separate submissions for the passes {:?}...",
+            count, ranges
+        );
+
+        let data_buffer: Buffer<[u32], _> =
+            device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+
+        for range in ranges {
+            let temporary_storage: Buffer<[u32], _> =
+                device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+
+            let encoder = radix_sort.encode_passes(
+                device.create_command_encoder(),
+                RadixSortInput {
+                    data: data_buffer.view(),
+                    temporary_storage: temporary_storage.view(),
+                    count: None,
+                },
+                range.clone(),
+            );
+
+            device.queue().submit(encoder.finish());
+        }
+
+        let readback_buffer: Buffer<[u32], _> =
+            device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+        let encoder = device
+            .create_command_encoder()
+            .copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+        device.queue().submit(encoder.finish());
+
+        assert_eq!(read_back_vec(&readback_buffer).await?, expected);
+
+        println!("...successfully!");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::radix_sort::{RadixSortAndGather, RadixSortAndGatherInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 100_000;
+
+    println!(
+        "Sorting {} slot ids by `i32` keys and mapping the old positions to the new positions...",
+        count
+    );
+
+    let mut sort_and_gather = RadixSortAndGather::<u32, i32>::init(device.clone()).await?;
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    let keys: Vec<i32> = (0..count)
+        .map(|_| rng.rand_range(0..200) as i32 - 100)
+        .collect();
+    let slot_ids: Vec<u32> = (0..count).map(|_| rng.rand_u32()).collect();
+
+    let keys_buffer: Buffer<[i32], _> =
+        device.create_buffer(&*keys, buffer::Usages::storage_binding().and_copy_src());
+    let slot_ids_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*slot_ids, buffer::Usages::storage_binding());
+    let sorted_slot_ids_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let position_map_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let keys_readback: Buffer<[i32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let sorted_slot_ids_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let position_map_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = sort_and_gather.encode_with_inverse_permutation(
+        encoder,
+        RadixSortAndGatherInput {
+            keys: keys_buffer.view(),
+            payload: slot_ids_buffer.view(),
+            count: None,
+        },
+        sorted_slot_ids_buffer.view(),
+        position_map_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(keys_buffer.view(), keys_readback.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(
+        sorted_slot_ids_buffer.view(),
+        sorted_slot_ids_readback.view(),
+    );
+    encoder = encoder
+        .copy_buffer_to_buffer_slice(position_map_buffer.view(), position_map_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    keys_readback.map_read().await?;
+    sorted_slot_ids_readback.map_read().await?;
+    position_map_readback.map_read().await?;
+
+    let sorted_keys = keys_readback.mapped();
+    let sorted_slot_ids = sorted_slot_ids_readback.mapped();
+    let position_map = position_map_readback.mapped();
+
+    // The sort is stable, so a stable CPU argsort must produce the same order
+    let mut permutation: Vec<usize> = (0..count).collect();
+
+    permutation.sort_by_key(|i| keys[*i]);
+
+    println!("Asserting the keys and slot ids are sorted as on the CPU...");
+
+    for (sorted_position, original_position) in permutation.iter().copied().enumerate() {
+        assert_eq!(sorted_keys[sorted_position], keys[original_position]);
+        assert_eq!(
+            sorted_slot_ids[sorted_position],
+            slot_ids[original_position]
+        );
+    }
+
+    println!("...successfully!");
+
+    println!("Asserting the position map points each original position at its new position...");
+
+    for original_position in 0..count {
+        let new_position = position_map[original_position] as usize;
+
+        assert_eq!(sorted_keys[new_position], keys[original_position]);
+        assert_eq!(sorted_slot_ids[new_position], slot_ids[original_position]);
+        assert_eq!(permutation[new_position], original_position);
+    }
+
+    println!("...successfully!");
+
+    mem::drop(sorted_keys);
+    mem::drop(sorted_slot_ids);
+    mem::drop(position_map);
+
+    keys_readback.unmap();
+    sorted_slot_ids_readback.unmap();
+    position_map_readback.unmap();
+
+    Ok(())
+}
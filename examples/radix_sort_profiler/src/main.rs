@@ -0,0 +1,107 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort = RadixSort::init_u32(device.clone()).await;
+    let mut profiler = Profiler::new(&device, 8);
+
+    let count = 1_000_000;
+
+    println!(
+        "Sorting {} values and timing each pass of the sort...",
+        count
+    );
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        data.push(rng.rand_u32());
+    }
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let temp_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode_with_profiler(
+        encoder,
+        RadixSortInput {
+            data: data_buffer.view(),
+            temporary_storage: temp_storage_buffer.view(),
+            count: None,
+        },
+        &mut profiler,
+    );
+
+    let timestamp_count = profiler.len() as usize;
+    let timestamps: Buffer<[u64], _> = device.create_slice_buffer_zeroed(
+        timestamp_count,
+        buffer::Usages::query_resolve().and_copy_src(),
+    );
+    let timestamps_readback: Buffer<[u64], _> = device
+        .create_slice_buffer_zeroed(timestamp_count, buffer::Usages::copy_dst().and_map_read());
+
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+    encoder = encoder.resolve_timestamp_query_set(profiler.query_set(), 0, timestamps.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(timestamps.view(), timestamps_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    data.sort();
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    println!("Asserting all values produced by the GPU sort match the values produced by the CPU sort...");
+
+    for i in 0..count {
+        assert_eq!(readback[i], data[i]);
+    }
+
+    println!("...successfully!");
+
+    timestamps_readback.map_read().await?;
+
+    let timestamps = timestamps_readback.mapped();
+
+    for i in 1..timestamp_count {
+        println!(
+            "Stage `{}`: {} nanoseconds",
+            profiler.stage_name(i as u32).unwrap(),
+            timestamps[i] - timestamps[i - 1]
+        );
+    }
+
+    println!(
+        "Total: {} nanoseconds",
+        timestamps[timestamp_count - 1] - timestamps[0]
+    );
+
+    Ok(())
+}
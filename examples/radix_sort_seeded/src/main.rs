@@ -0,0 +1,115 @@
+use std::error::Error;
+use std::{env, mem};
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::{Device, DeviceDescriptor};
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use empa_tk::testing;
+use futures::FutureExt;
+
+// Sorts pseudo-random `u32`, `i32` and `f32` inputs generated from a seed, and verifies the
+// results against the reference sort.
+//
+// Usage: `radix-sort-seeded-example [seed] [count]`; reproduces a failing input exactly from the
+// seed and count it was reported with.
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+    let seed: u64 = args
+        .next()
+        .map(|arg| arg.parse())
+        .transpose()?
+        .unwrap_or(42);
+    let count: usize = args
+        .next()
+        .map(|arg| arg.parse())
+        .transpose()?
+        .unwrap_or(1_000_003);
+
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    println!(
+        "Sorting {} `u32` values generated from seed {}...",
+        count, seed
+    );
+
+    let mut radix_sort = RadixSort::init_u32(device.clone()).await;
+
+    sort_and_verify(&device, &mut radix_sort, testing::random_u32(seed, count)).await?;
+
+    println!("...successfully!");
+    println!(
+        "Sorting {} `i32` values generated from seed {}...",
+        count, seed
+    );
+
+    let mut radix_sort = RadixSort::init_i32(device.clone()).await;
+
+    sort_and_verify(&device, &mut radix_sort, testing::random_i32(seed, count)).await?;
+
+    println!("...successfully!");
+    println!(
+        "Sorting {} `f32` values generated from seed {}...",
+        count, seed
+    );
+
+    let mut radix_sort = RadixSort::init_f32(device.clone()).await;
+
+    sort_and_verify(&device, &mut radix_sort, testing::random_f32(seed, count)).await?;
+
+    println!("...successfully!");
+
+    Ok(())
+}
+
+async fn sort_and_verify<T>(
+    device: &Device,
+    radix_sort: &mut RadixSort<T>,
+    mut data: Vec<T>,
+) -> Result<(), Box<dyn Error>>
+where
+    T: RadixKey + Copy + PartialEq + std::fmt::Debug,
+{
+    let count = data.len();
+
+    let data_buffer: Buffer<[T], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let readback_buffer: Buffer<[T], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode_with_internal_storage(encoder, data_buffer.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    testing::sort_reference(&mut data);
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    for i in 0..count {
+        assert_eq!(readback[i], data[i], "mismatch at index {}", i);
+    }
+
+    mem::drop(readback);
+
+    readback_buffer.unmap();
+
+    Ok(())
+}
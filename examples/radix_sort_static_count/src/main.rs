@@ -0,0 +1,84 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort = RadixSort::init_u32(device.clone()).await;
+
+    let len = 1_000_000;
+    let count = 600_000;
+
+    println!(
+        "Sorting the first {} values of a buffer of {} values with a host-side count...",
+        count, len
+    );
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(len);
+
+    for _ in 0..len {
+        data.push(rng.rand_u32());
+    }
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    // With a static count, the temporary storage only needs to hold the values that are sorted
+    let temp_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(len, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode(
+        encoder,
+        RadixSortInput {
+            data: data_buffer.view(),
+            temporary_storage: temp_storage_buffer.view(),
+            count: Some(Count::Static(count as u32)),
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected = data.clone();
+
+    expected[..count].sort();
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    println!(
+        "Asserting the first {} values are sorted and the remaining values are untouched...",
+        count
+    );
+
+    for i in 0..len {
+        assert_eq!(readback[i], expected[i]);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::{Device, DeviceDescriptor};
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut radix_sort = RadixSort::<u32>::init_u32(device.clone()).await;
+
+    let count = 1_000_000;
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    println!("Sorting unsorted data...");
+
+    let data: Vec<u32> = (0..count).map(|_| rng.rand_u32()).collect();
+
+    sort_and_verify(&device, &mut radix_sort, data, false).await?;
+
+    println!("Sorting data that is already sorted...");
+
+    let mut data: Vec<u32> = (0..count).map(|_| rng.rand_u32()).collect();
+
+    data.sort();
+
+    sort_and_verify(&device, &mut radix_sort, data.clone(), true).await?;
+
+    println!("Sorting data that is sorted except for its last value...");
+
+    data[count - 1] = 0;
+
+    sort_and_verify(&device, &mut radix_sort, data, false).await?;
+
+    println!("Sorting data in which all values are equal...");
+
+    sort_and_verify(&device, &mut radix_sort, vec![7; count], true).await?;
+
+    Ok(())
+}
+
+async fn sort_and_verify(
+    device: &Device,
+    radix_sort: &mut RadixSort<u32>,
+    mut data: Vec<u32>,
+    expect_sorted: bool,
+) -> Result<(), Box<dyn Error>> {
+    let count = data.len();
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let temp_storage_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding());
+    let is_sorted_buffer: Buffer<u32, _> =
+        device.create_buffer(2, buffer::Usages::storage_binding().and_copy_src());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let is_sorted_readback: Buffer<u32, _> =
+        device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = radix_sort.encode_unless_sorted(
+        encoder,
+        RadixSortInput {
+            data: data_buffer.view(),
+            temporary_storage: temp_storage_buffer.view(),
+            count: None,
+        },
+        is_sorted_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(data_buffer.view(), readback_buffer.view());
+    encoder = encoder.copy_buffer_to_buffer(is_sorted_buffer.view(), is_sorted_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    data.sort();
+
+    is_sorted_readback.map_read().await?;
+    readback_buffer.map_read().await?;
+
+    let is_sorted = *is_sorted_readback.mapped();
+
+    println!("The input was reported as sorted: {}", is_sorted == 1);
+
+    assert_eq!(is_sorted, expect_sorted as u32);
+    assert_eq!(&*readback_buffer.mapped(), &data[..]);
+
+    println!("...and the output is sorted!");
+
+    is_sorted_readback.unmap();
+    readback_buffer.unmap();
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::{Device, DeviceDescriptor};
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let data: Vec<u32> = (0..count).map(|_| rng.rand_u32()).collect();
+
+    let mut radix_sort = RadixSort::init_u32(device.clone()).await;
+
+    println!("Verifying that {} random values are not sorted...", count);
+
+    assert_eq!(verify(&device, &mut radix_sort, &data, false).await?, 0);
+
+    println!("...successfully!");
+    println!(
+        "Verifying that the sort of {} random values is sorted...",
+        count
+    );
+
+    assert_eq!(verify(&device, &mut radix_sort, &data, true).await?, 1);
+
+    println!("...successfully!");
+    println!("Verifying that an empty input is sorted...");
+
+    assert_eq!(verify(&device, &mut radix_sort, &[], false).await?, 1);
+
+    println!("...successfully!");
+    println!("Verifying that `-0.0` after `0.0` is not sorted...");
+
+    let mut radix_sort = RadixSort::init_f32(device.clone()).await;
+
+    assert_eq!(
+        verify(&device, &mut radix_sort, &[-1.0, 0.0, -0.0, 1.0], false).await?,
+        0
+    );
+    assert_eq!(
+        verify(&device, &mut radix_sort, &[-1.0, -0.0, 0.0, 1.0], false).await?,
+        1
+    );
+
+    println!("...successfully!");
+
+    Ok(())
+}
+
+/// Verifies whether the `data` is sorted on the GPU, optionally sorting it first, and returns the
+/// resulting flag.
+async fn verify<T>(
+    device: &Device,
+    radix_sort: &mut RadixSort<T>,
+    data: &[T],
+    sort_first: bool,
+) -> Result<u32, Box<dyn Error>>
+where
+    T: RadixKey,
+{
+    let data_buffer: Buffer<[T], _> = device.create_buffer(data, buffer::Usages::storage_binding());
+    let is_sorted: Buffer<u32, _> =
+        device.create_buffer(2, buffer::Usages::storage_binding().and_copy_src());
+    let readback: Buffer<u32, _> =
+        device.create_buffer(0, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    if sort_first {
+        encoder = radix_sort.encode_with_internal_storage(encoder, data_buffer.view());
+    }
+
+    encoder = radix_sort.verify_sorted(encoder, data_buffer.view(), is_sorted.view());
+    encoder = encoder.copy_buffer_to_buffer(is_sorted.view(), readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    readback.map_read().await?;
+
+    let value = *readback.mapped();
+
+    readback.unmap();
+
+    Ok(value)
+}
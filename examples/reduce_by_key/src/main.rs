@@ -0,0 +1,168 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::{Device, DeviceDescriptor};
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    let mut random_keys: Vec<u32> = (0..count).map(|_| rng.rand_range(0..10_000)).collect();
+
+    random_keys.sort();
+
+    let values: Vec<u32> = (0..count).map(|_| rng.rand_range(0..1000)).collect();
+
+    for operator in [
+        ReduceOperator::Sum,
+        ReduceOperator::Min,
+        ReduceOperator::Max,
+    ] {
+        let mut reduce_by_key = ReduceByKey::<u32, u32>::init(device.clone(), operator).await;
+
+        println!("Reducing random sorted keys with {:?}...", operator);
+        reduce_and_verify(&device, &mut reduce_by_key, operator, &random_keys, &values).await?;
+
+        println!(
+            "Reducing a single group spanning the whole input with {:?}...",
+            operator
+        );
+        reduce_and_verify(
+            &device,
+            &mut reduce_by_key,
+            operator,
+            &vec![7; count],
+            &values,
+        )
+        .await?;
+
+        println!("Reducing all distinct keys with {:?}...", operator);
+        let distinct_keys: Vec<u32> = (0..count as u32).collect();
+        reduce_and_verify(
+            &device,
+            &mut reduce_by_key,
+            operator,
+            &distinct_keys,
+            &values,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn reduce_and_verify(
+    device: &Device,
+    reduce_by_key: &mut ReduceByKey<u32, u32>,
+    operator: ReduceOperator,
+    keys: &[u32],
+    values: &[u32],
+) -> Result<(), Box<dyn Error>> {
+    let count = keys.len();
+
+    let mut expected_keys: Vec<u32> = Vec::new();
+    let mut expected_aggregates: Vec<u32> = Vec::new();
+
+    for (key, value) in keys.iter().zip(values) {
+        if expected_keys.last() == Some(key) {
+            let aggregate = expected_aggregates.last_mut().unwrap();
+
+            *aggregate = match operator {
+                ReduceOperator::Sum => aggregate.wrapping_add(*value),
+                ReduceOperator::Min => (*aggregate).min(*value),
+                ReduceOperator::Max => (*aggregate).max(*value),
+            };
+        } else {
+            expected_keys.push(*key);
+            expected_aggregates.push(*value);
+        }
+    }
+
+    let keys_buffer: Buffer<[u32], _> =
+        device.create_buffer(keys, buffer::Usages::storage_binding());
+    let values_buffer: Buffer<[u32], _> =
+        device.create_buffer(values, buffer::Usages::storage_binding());
+    let unique_keys_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let aggregates_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let group_count_buffer: Buffer<u32, _> =
+        device.create_buffer_zeroed(buffer::Usages::storage_binding().and_copy_src());
+
+    let unique_keys_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let aggregates_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let group_count_readback: Buffer<u32, _> =
+        device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = reduce_by_key.encode(
+        encoder,
+        ReduceByKeyInput {
+            keys: keys_buffer.view(),
+            values: values_buffer.view(),
+            count: None,
+        },
+        ReduceByKeyOutput {
+            unique_keys: unique_keys_buffer.view(),
+            aggregates: aggregates_buffer.view(),
+            group_count: group_count_buffer.view(),
+        },
+    );
+
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(unique_keys_buffer.view(), unique_keys_readback.view());
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(aggregates_buffer.view(), aggregates_readback.view());
+    encoder = encoder.copy_buffer_to_buffer(group_count_buffer.view(), group_count_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    group_count_readback.map_read().await?;
+    unique_keys_readback.map_read().await?;
+    aggregates_readback.map_read().await?;
+
+    let group_count = *group_count_readback.mapped() as usize;
+    let unique_keys = unique_keys_readback.mapped();
+    let aggregates = aggregates_readback.mapped();
+
+    assert_eq!(group_count, expected_keys.len());
+    assert_eq!(&unique_keys[..group_count], &expected_keys[..]);
+    assert_eq!(&aggregates[..group_count], &expected_aggregates[..]);
+
+    println!(
+        "...found {} groups, matching the CPU reference",
+        group_count
+    );
+
+    mem::drop(unique_keys);
+    mem::drop(aggregates);
+
+    group_count_readback.unmap();
+    unique_keys_readback.unmap();
+    aggregates_readback.unmap();
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::run_length_decode::{RunLengthDecode, RunLengthDecodeInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let run_count = 100_000;
+
+    println!("Decoding {} runs...", run_count);
+
+    let mut run_length_decode = RunLengthDecode::<u32>::init(device.clone()).await?;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut values: Vec<u32> = Vec::with_capacity(run_count);
+    let mut lengths: Vec<u32> = Vec::with_capacity(run_count);
+
+    for _ in 0..run_count {
+        values.push(rng.rand_u32());
+        // Include some empty runs
+        lengths.push(rng.rand_range(0..20));
+    }
+
+    let mut expected: Vec<u32> = Vec::new();
+
+    for (value, length) in values.iter().zip(lengths.iter()) {
+        for _ in 0..*length {
+            expected.push(*value);
+        }
+    }
+
+    let count = expected.len();
+
+    let values_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*values, buffer::Usages::storage_binding());
+    let lengths_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*lengths, buffer::Usages::storage_binding().and_copy_src());
+    let output_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = run_length_decode.encode(
+        encoder,
+        RunLengthDecodeInput {
+            values: values_buffer.view(),
+            lengths: lengths_buffer.view(),
+            count: None,
+        },
+        output_buffer.view(),
+    );
+    encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    readback_buffer.map_read().await?;
+
+    let readback = readback_buffer.mapped();
+
+    println!("Asserting all values decoded by the GPU match the values decoded by the CPU...");
+
+    for i in 0..count {
+        assert_eq!(readback[i], expected[i]);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
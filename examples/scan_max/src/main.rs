@@ -0,0 +1,107 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prefix_sum::{ScanMax, ScanMaxInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    println!("Computing the running maxima of {} values...", count);
+
+    let mut scan_max_inclusive = ScanMax::init_inclusive_f32(device.clone()).await;
+    let mut scan_max_exclusive = ScanMax::init_exclusive_f32(device.clone()).await;
+
+    // Use a slowly rising signal, so that the running maximum changes throughout the data, and
+    // sprinkle in some NaN values, which should be ignored.
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<f32> = Vec::with_capacity(count);
+
+    for i in 0..count {
+        if rng.rand_range(0..1000) == 0 {
+            data.push(f32::NAN);
+        } else {
+            data.push(rng.rand_float() - 0.5 + i as f32 * 0.00001);
+        }
+    }
+
+    let inclusive_buffer: Buffer<[f32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let exclusive_buffer: Buffer<[f32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding().and_copy_src());
+    let inclusive_readback: Buffer<[f32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let exclusive_readback: Buffer<[f32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = scan_max_inclusive.encode(
+        encoder,
+        ScanMaxInput {
+            data: inclusive_buffer.view(),
+            count: None,
+        },
+    );
+    encoder = scan_max_exclusive.encode(
+        encoder,
+        ScanMaxInput {
+            data: exclusive_buffer.view(),
+            count: None,
+        },
+    );
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(inclusive_buffer.view(), inclusive_readback.view());
+    encoder =
+        encoder.copy_buffer_to_buffer_slice(exclusive_buffer.view(), exclusive_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    let mut expected_inclusive = Vec::with_capacity(count);
+    let mut expected_exclusive = Vec::with_capacity(count);
+    let mut running_max = f32::NEG_INFINITY;
+
+    for value in data {
+        expected_exclusive.push(running_max);
+
+        if !value.is_nan() {
+            running_max = running_max.max(value);
+        }
+
+        expected_inclusive.push(running_max);
+    }
+
+    inclusive_readback.map_read().await?;
+    exclusive_readback.map_read().await?;
+
+    let inclusive = inclusive_readback.mapped();
+    let exclusive = exclusive_readback.mapped();
+
+    println!("Asserting all values produced by the GPU match the values produced by the CPU...");
+
+    for i in 0..count {
+        assert_eq!(inclusive[i], expected_inclusive[i]);
+        assert_eq!(exclusive[i], expected_exclusive[i]);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
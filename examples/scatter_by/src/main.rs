@@ -35,7 +35,7 @@ async fn run() -> Result<(), Box<dyn Error>> {
         by.push(count as u32 - 1 - i);
     }
 
-    let mut scatter_by = ScatterBy::init_u32(device.clone()).await;
+    let mut scatter_by = ScatterBy::init_u32(device.clone()).await?;
 
     let data_buffer: Buffer<[u32], _> =
         device.create_buffer(data, buffer::Usages::storage_binding());
@@ -59,6 +59,7 @@ async fn run() -> Result<(), Box<dyn Error>> {
             scatter_by: by_buffer.view(),
             data: data_buffer.view(),
             count: None,
+            index_transform: None,
         },
         output_buffer.view(),
     );
@@ -0,0 +1,117 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::prelude::*;
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    println!(
+        "Scattering {} values by a random permutation and gathering them back...",
+        count
+    );
+
+    let mut rng = oorandom::Rand32::new(1);
+
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        data.push(rng.rand_u32());
+    }
+
+    // Fisher-Yates shuffle
+    let mut permutation: Vec<u32> = (0..count as u32).collect();
+
+    for i in (1..count).rev() {
+        let j = rng.rand_range(0..i as u32 + 1) as usize;
+
+        permutation.swap(i, j);
+    }
+
+    let mut scatter_by = ScatterBy::init_u32(device.clone()).await?;
+    let mut gather_by = GatherBy::init_u32(device.clone()).await?;
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let permutation_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*permutation, buffer::Usages::storage_binding());
+    let scattered_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let gathered_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+    let scattered_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+    let gathered_readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = scatter_by.encode(
+        encoder,
+        ScatterByInput {
+            scatter_by: permutation_buffer.view(),
+            data: data_buffer.view(),
+            count: None,
+            index_transform: None,
+        },
+        scattered_buffer.view(),
+    );
+    encoder = gather_by.encode(
+        encoder,
+        GatherByInput {
+            gather_by: permutation_buffer.view(),
+            data: scattered_buffer.view(),
+            count: None,
+            index_transform: None,
+        },
+        gathered_buffer.view(),
+    );
+    encoder = encoder
+        .copy_buffer_to_buffer_slice(scattered_buffer.view(), scattered_readback_buffer.view());
+    encoder = encoder
+        .copy_buffer_to_buffer_slice(gathered_buffer.view(), gathered_readback_buffer.view());
+
+    device.queue().submit(encoder.finish());
+
+    scattered_readback_buffer.map_read().await?;
+    gathered_readback_buffer.map_read().await?;
+
+    let scattered = scattered_readback_buffer.mapped();
+    let gathered = gathered_readback_buffer.mapped();
+
+    println!("Asserting each value was scattered to the position given by the permutation...");
+
+    for i in 0..count {
+        assert_eq!(scattered[permutation[i] as usize], data[i]);
+    }
+
+    println!("...successfully!");
+
+    println!("Asserting gathering by the same permutation restores the original values...");
+
+    for i in 0..count {
+        assert_eq!(gathered[i], data[i]);
+    }
+
+    println!("...successfully!");
+
+    Ok(())
+}
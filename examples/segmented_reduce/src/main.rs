@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::mem;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::segmented_reduce::{ReduceOperator, SegmentedReduce, SegmentedReduceInput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_000;
+
+    let mut rng = oorandom::Rand32::new(1);
+    let mut data: Vec<u32> = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        data.push(rng.rand_range(0..1000));
+    }
+
+    // Generate segments with widely varying sizes: many empty and small segments, and the
+    // occasional segment that spans several thousand values.
+    let mut segment_offsets: Vec<u32> = Vec::new();
+    let mut offset = 0;
+
+    while offset < count as u32 {
+        segment_offsets.push(offset);
+
+        offset += match rng.rand_range(0..10) {
+            0..=2 => 0,
+            3..=8 => rng.rand_range(1..100),
+            _ => rng.rand_range(1000..10_000),
+        };
+    }
+
+    let segment_count = segment_offsets.len();
+
+    println!("Reducing {} values in {} segments...", count, segment_count);
+
+    let mut segmented_reduce = SegmentedReduce::init_u32(device.clone(), ReduceOperator::Max).await;
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let segment_offsets_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*segment_offsets, buffer::Usages::storage_binding());
+    let output_buffer: Buffer<[u32], _> = device.create_slice_buffer_zeroed(
+        segment_count,
+        buffer::Usages::storage_binding().and_copy_src(),
+    );
+    let readback_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(segment_count, buffer::Usages::map_read().and_copy_dst());
+    let timestamp_query_set = device.create_timestamp_query_set(2);
+    let timestamps =
+        device.create_slice_buffer_zeroed(2, buffer::Usages::query_resolve().and_copy_src());
+    let timestamps_readback =
+        device.create_slice_buffer_zeroed(2, buffer::Usages::copy_dst().and_map_read());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = encoder.write_timestamp(&timestamp_query_set, 0);
+    encoder = segmented_reduce.encode(
+        encoder,
+        SegmentedReduceInput {
+            data: data_buffer.view(),
+            segment_offsets: segment_offsets_buffer.view(),
+            count: None,
+        },
+        output_buffer.view(),
+    );
+    encoder = encoder.write_timestamp(&timestamp_query_set, 1);
+
+    encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), readback_buffer.view());
+    encoder = encoder.resolve_timestamp_query_set(&timestamp_query_set, 0, timestamps.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(timestamps.view(), timestamps_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    readback_buffer.map_read().await?;
+
+    let aggregates = readback_buffer.mapped();
+
+    println!("The first 10 aggregates: {:#?}", &aggregates[..10]);
+
+    println!("Asserting the aggregates computed on the GPU match the expected aggregates...");
+
+    for i in 0..segment_count {
+        let start = segment_offsets[i] as usize;
+        let end = segment_offsets.get(i + 1).copied().unwrap_or(count as u32) as usize;
+
+        // An empty segment reduces to the identity of the max operator
+        let expected = data[start..end].iter().copied().max().unwrap_or(0);
+
+        assert_eq!(aggregates[i], expected);
+    }
+
+    println!("...successfully!");
+
+    mem::drop(aggregates);
+
+    readback_buffer.unmap();
+
+    timestamps_readback.map_read().await?;
+
+    let timestamps = timestamps_readback.mapped();
+    let time_elapsed = timestamps[1] - timestamps[0];
+
+    println!("Time elapsed: {} nanoseconds", time_elapsed);
+
+    mem::drop(timestamps);
+
+    timestamps_readback.unmap();
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::set_ops::{SetOperation, SetOps, SetOpsInput, SetOpsOutput};
+use futures::FutureExt;
+
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let mut set_ops = SetOps::init(device.clone()).await;
+
+    // Use lopsided sets, to exercise the merge path partitioning
+    let mut rng = oorandom::Rand32::new(1);
+    let a: BTreeSet<u32> = (0..1_000_000)
+        .map(|_| rng.rand_range(0..4_000_000))
+        .collect();
+    let b: BTreeSet<u32> = (0..10_000).map(|_| rng.rand_range(0..4_000_000)).collect();
+
+    let a_data: Vec<u32> = a.iter().copied().collect();
+    let b_data: Vec<u32> = b.iter().copied().collect();
+
+    let a_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*a_data, buffer::Usages::storage_binding());
+    let b_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*b_data, buffer::Usages::storage_binding());
+
+    for (operation, expected) in [
+        (
+            SetOperation::Intersection,
+            a.intersection(&b).copied().collect::<Vec<u32>>(),
+        ),
+        (SetOperation::Union, a.union(&b).copied().collect()),
+        (
+            SetOperation::Difference,
+            a.difference(&b).copied().collect(),
+        ),
+    ] {
+        println!(
+            "Computing the {:?} of sets with {} and {} elements...",
+            operation,
+            a_data.len(),
+            b_data.len()
+        );
+
+        let max_len = operation.max_result_len(a_data.len(), b_data.len());
+
+        let result_count_buffer: Buffer<u32, _> = device.create_buffer_zeroed(
+            buffer::Usages::storage_binding()
+                .and_copy_dst()
+                .and_copy_src(),
+        );
+        let output_buffer: Buffer<[u32], _> = device
+            .create_slice_buffer_zeroed(max_len, buffer::Usages::storage_binding().and_copy_src());
+        let result_count_readback: Buffer<u32, _> =
+            device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+        let readback_buffer: Buffer<[u32], _> =
+            device.create_slice_buffer_zeroed(max_len, buffer::Usages::map_read().and_copy_dst());
+
+        let mut encoder = device.create_command_encoder();
+
+        encoder = set_ops.encode(
+            encoder,
+            operation,
+            SetOpsInput {
+                a: a_buffer.view(),
+                b: b_buffer.view(),
+            },
+            SetOpsOutput {
+                result_count: result_count_buffer.view(),
+                data: output_buffer.view(),
+            },
+        );
+        encoder =
+            encoder.copy_buffer_to_buffer(result_count_buffer.view(), result_count_readback.view());
+        encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), readback_buffer.view());
+
+        device.queue().submit(encoder.finish());
+
+        result_count_readback.map_read().await?;
+        readback_buffer.map_read().await?;
+
+        let result_count = *result_count_readback.mapped();
+        let readback = readback_buffer.mapped();
+
+        println!("Asserting the GPU result matches the CPU result...");
+
+        assert_eq!(result_count as usize, expected.len());
+
+        for i in 0..expected.len() {
+            assert_eq!(readback[i], expected[i]);
+        }
+
+        println!("...successfully!");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use std::error::Error;
+
+use empa::adapter::Feature;
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::DeviceDescriptor;
+use empa::native::Instance;
+use empa_tk::stream_compaction::{StreamCompaction, StreamCompactionInput, StreamCompactionOutput};
+use empa_tk::{read_back, read_back_vec};
+use futures::FutureExt;
+
+// Compacts values selected by bit-packed flags and verifies the result against a compaction on
+// the host. The count is not a multiple of 32, and the unused bits of the last flag word are set,
+// to verify that they are ignored.
+fn main() {
+    pollster::block_on(run().map(|res| res.unwrap()));
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let instance = Instance::default();
+    let adapter = instance.get_adapter(Default::default())?;
+    let device = adapter
+        .request_device(&DeviceDescriptor {
+            required_features: Feature::TimestampQuery | Feature::TimestampQueryInsideEncoders,
+            required_limits: Default::default(),
+        })
+        .await?;
+
+    let count = 1_000_003;
+    let word_count = count.div_ceil(32);
+
+    let mut rng = oorandom::Rand32::new(1);
+    let data: Vec<u32> = (0..count).map(|_| rng.rand_u32()).collect();
+    let mut flags: Vec<u32> = (0..word_count).map(|_| rng.rand_u32()).collect();
+
+    flags[word_count - 1] |= !((1 << (count % 32)) - 1);
+
+    let expected: Vec<u32> = (0..count)
+        .filter(|i| flags[i / 32] & (1 << (i % 32)) != 0)
+        .map(|i| data[i])
+        .collect();
+
+    println!(
+        "Compacting {} values selected by {} bit-packed flag words...",
+        count, word_count
+    );
+
+    let mut stream_compaction = StreamCompaction::init(device.clone()).await;
+
+    let data_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*data, buffer::Usages::storage_binding());
+    let flags_buffer: Buffer<[u32], _> =
+        device.create_buffer(&*flags, buffer::Usages::storage_binding());
+    let kept_count_buffer: Buffer<u32, _> = device.create_buffer_zeroed(
+        buffer::Usages::storage_binding()
+            .and_copy_dst()
+            .and_copy_src(),
+    );
+    let output_buffer: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::storage_binding().and_copy_src());
+
+    let kept_count_readback: Buffer<u32, _> =
+        device.create_buffer_zeroed(buffer::Usages::map_read().and_copy_dst());
+    let output_readback: Buffer<[u32], _> =
+        device.create_slice_buffer_zeroed(count, buffer::Usages::map_read().and_copy_dst());
+
+    let mut encoder = device.create_command_encoder();
+
+    encoder = stream_compaction.encode(
+        encoder,
+        StreamCompactionInput {
+            data: data_buffer.view(),
+            flags: flags_buffer.view(),
+        },
+        StreamCompactionOutput {
+            kept_count: kept_count_buffer.view(),
+            data: output_buffer.view(),
+        },
+    );
+    encoder = encoder.copy_buffer_to_buffer(kept_count_buffer.view(), kept_count_readback.view());
+    encoder = encoder.copy_buffer_to_buffer_slice(output_buffer.view(), output_readback.view());
+
+    device.queue().submit(encoder.finish());
+
+    let kept_count = read_back(&kept_count_readback).await? as usize;
+    let output = read_back_vec(&output_readback).await?;
+
+    println!("Asserting the kept count matches the number of set flags...");
+
+    assert_eq!(kept_count, expected.len());
+
+    println!("...successfully!");
+    println!("Asserting the kept values match the expected values...");
+
+    assert_eq!(&output[..kept_count], &expected[..]);
+
+    println!("...successfully!");
+
+    Ok(())
+}